@@ -79,6 +79,8 @@ function test() {
         local tmp=${golden:?}.tmp
         ${MAIN_EXECUTABLE:?} --config - --mode dark <"${asset:?}" -o "${tmp:?}"
         diff "${golden:?}" "${tmp:?}"
+        ${MAIN_EXECUTABLE:?} --config - --mode dark --input "${asset:?}" -o "${tmp:?}"
+        diff "${golden:?}" "${tmp:?}"
         rm -f ${tmp:?}
     done
 
@@ -0,0 +1,110 @@
+// std imports
+use std::rc::Rc;
+
+// local imports
+use crate::{
+    Convert, capture,
+    config::{
+        Settings,
+        mode::Mode,
+        winstyle::{Window, WindowStyleConfig},
+    },
+    font::{self, FontFile},
+    render::{FontMetrics, FontOptions, svg::SvgRenderer},
+    term::Terminal,
+    theme::{AdaptiveTheme, Theme},
+};
+
+/// Configuration for rendering with [`render_bytes`].
+#[derive(Debug, Clone)]
+pub struct RenderConfig {
+    /// Settings, including the terminal size limits and rendering options.
+    pub settings: Settings,
+    /// Color theme.
+    pub theme: Rc<Theme>,
+    /// Window style, used if the window is enabled in the settings.
+    pub window: Window,
+    /// Window title.
+    pub title: Option<String>,
+    /// Color mode.
+    pub mode: Mode,
+    /// Metrics of the font, no font files are loaded so the text is rendered with the font families by name.
+    pub font_metrics: FontMetrics,
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            settings: Settings::default(),
+            theme: AdaptiveTheme::default().resolve(Mode::Dark),
+            window: WindowStyleConfig::default().window,
+            title: None,
+            mode: Mode::Dark,
+            font_metrics: DEFAULT_FONT_METRICS,
+        }
+    }
+}
+
+/// Renders the output of a program captured as a byte stream to an SVG document.
+///
+/// The terminal is sized according to `config.settings.terminal` the same way as for a command,
+/// so no PTY or child process is needed.
+///
+/// # Examples
+///
+/// ```
+/// let svg = termframe::render_bytes(b"\x1b[1mhello\x1b[0m world", Default::default()).unwrap();
+/// assert!(svg.contains("hello"));
+/// ```
+pub fn render_bytes(input: &[u8], config: RenderConfig) -> anyhow::Result<String> {
+    let settings = Rc::new(config.settings);
+
+    let mut terminal = Terminal::new(capture::terminal_options(&settings, &config.theme, None));
+    terminal.advance(input);
+    capture::fit_terminal(&mut terminal, &settings, false, false);
+
+    let font = FontOptions {
+        family: settings.font.family.resolve(),
+        size: settings.font.size.into(),
+        metrics: config.font_metrics,
+        faces: Vec::new(),
+        weights: settings.font.weights.convert(),
+    };
+    let mut options = capture::render_options(
+        settings.clone(),
+        font,
+        config.theme,
+        config.window,
+        config.title,
+        config.mode,
+        &terminal,
+    );
+    if settings.window.enabled
+        && let Some(title) = &options.title
+    {
+        // Only local font files are used, remote ones are never downloaded.
+        options.title_advances =
+            capture::measure_title(&settings, &options.window, title, |file| {
+                let location = font::Location::from(file);
+                match location.url() {
+                    Some(url) if location.is_remote() => Err(font::Unavailable(url.clone()).into()),
+                    _ => FontFile::load(location),
+                }
+            });
+    }
+
+    let mut output = Vec::new();
+    SvgRenderer::new(options).render(terminal.surface(), &mut output)?;
+
+    Ok(String::from_utf8(output)?)
+}
+
+/// Font metrics used when no font files are available.
+const DEFAULT_FONT_METRICS: FontMetrics = FontMetrics {
+    width: 0.6,
+    ascender: 1.02,
+    descender: -0.3,
+};
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,55 @@
+use super::*;
+
+#[test]
+fn test_render_bytes() {
+    let svg = render_bytes(b"hello", RenderConfig::default()).unwrap();
+
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains(">hello<"));
+}
+
+#[test]
+fn test_render_bytes_fits_size() {
+    let mut config = RenderConfig::default();
+    config.settings.window.enabled = false;
+    config.settings.terminal.width = "4..40".parse().unwrap();
+
+    let svg = render_bytes(b"0123456789\r\nde\r\n", config.clone()).unwrap();
+    let size = |svg: &str| {
+        let width = svg
+            .split("width=\"")
+            .nth(1)
+            .unwrap()
+            .split('"')
+            .next()
+            .unwrap();
+        width.parse::<f32>().unwrap()
+    };
+
+    let wide = render_bytes(b"0123456789abcdefghij\r\nde\r\n", config).unwrap();
+    assert!(size(&wide) > size(&svg));
+}
+
+#[test]
+fn test_render_bytes_full_output() {
+    let mut config = RenderConfig::default();
+    config.settings.window.enabled = false;
+    config.settings.terminal.height = "2".parse().unwrap();
+
+    let input = b"one\r\ntwo\r\nthree\r\nfour";
+    let svg = render_bytes(input, config.clone()).unwrap();
+    assert!(!svg.contains(">one<"));
+    assert!(svg.contains(">four<"));
+
+    config.settings.rendering.full_output = true;
+    let svg = render_bytes(input, config).unwrap();
+    assert!(svg.contains(">one<"));
+    assert!(svg.contains(">four<"));
+}
+
+#[test]
+fn test_render_bytes_sixel_image() {
+    let svg = render_bytes(b"\x1bPq#0;2;100;0;0#0~~\x1b\\", RenderConfig::default()).unwrap();
+
+    assert!(svg.contains("data:image/png;base64,"));
+}
@@ -0,0 +1,270 @@
+// std imports
+use std::{borrow::Cow, collections::HashMap, fmt, rc::Rc};
+
+// third-party imports
+use base64::prelude::*;
+
+// local imports
+use crate::{
+    Convert,
+    config::{self, Dimension, Settings, mode::Mode, winstyle::Window},
+    font::{self, FontFile},
+    fontformat::FontFormat,
+    render::{self, FontFace, FontOptions},
+    term::{self, Terminal},
+    theme::Theme,
+};
+
+/// Creates terminal options based on the settings and theme.
+///
+/// The initial size is taken from the settings, or from `size` if the settings do not set it.
+pub fn terminal_options(
+    settings: &Settings,
+    theme: &Theme,
+    size: Option<(u16, u16)>,
+) -> term::Options {
+    let width = &settings.terminal.width;
+    let height = &settings.terminal.height;
+
+    term::Options {
+        cols: Some(
+            width.initial_or(
+                size.map(|size| size.0)
+                    .or_else(|| width.min())
+                    .or_else(|| width.max())
+                    .unwrap_or(240),
+            ),
+        ),
+        rows: Some(
+            height.initial_or(
+                size.map(|size| size.1)
+                    .or_else(|| height.min())
+                    .or_else(|| height.max())
+                    .unwrap_or(1024),
+            ),
+        ),
+        background: Some(theme.bg.convert()),
+        foreground: Some(theme.fg.convert()),
+        env: settings.env.clone(),
+        tab_width: Some(settings.terminal.tab_width),
+        scrollback_limit: Some(settings.terminal.scrollback_limit),
+        formfeed: settings.terminal.formfeed.convert(),
+        wrap_mode: settings.terminal.wrap_mode.convert(),
+        c0_filter: settings.terminal.c0_filter.convert(),
+        newline: settings.terminal.newline.convert(),
+        device_attributes: settings.terminal.device_attributes.clone(),
+        palette: theme.palette.iter().map(|color| color.convert()).collect(),
+    }
+}
+
+/// Resizes the terminal to fit its content within the size limits of the settings.
+///
+/// The width and height are kept as they are if they are fixed in the settings, or if `keep_width`
+/// and `keep_height` are set. With `rendering.full-output`, the height is extended to show the whole transcript.
+pub fn fit_terminal(
+    terminal: &mut Terminal,
+    settings: &Settings,
+    keep_width: bool,
+    keep_height: bool,
+) {
+    let width = &settings.terminal.width;
+    let height = &settings.terminal.height;
+
+    if !keep_width && !matches!(width.current, Dimension::Fixed(_)) {
+        let recommended = terminal.recommended_width();
+        log::info!("recommended terminal width: {recommended}");
+        let width = width.fit(recommended);
+        if terminal.surface().dimensions().0 as u16 != width {
+            terminal.set_width(width);
+        }
+    }
+
+    let rows = if keep_height || matches!(height.current, Dimension::Fixed(_)) {
+        terminal.surface().dimensions().1 as u16
+    } else {
+        let recommended = terminal.recommended_height();
+        log::info!("recommended terminal height: {recommended}");
+        height.fit(recommended)
+    };
+    let rows = if settings.rendering.full_output && !keep_height {
+        // Make room for the whole transcript, so that nothing is left in the scrollback
+        rows.max(terminal.recommended_height())
+    } else {
+        rows
+    };
+    terminal.set_height(rows);
+}
+
+/// Creates render options for the terminal screen.
+///
+/// Colors, palette changes, images and the scrollback size are taken from the terminal.
+/// The window title falls back to the one set by the program running in the terminal.
+pub fn render_options(
+    settings: Rc<Settings>,
+    font: FontOptions,
+    theme: Rc<Theme>,
+    window: Window,
+    title: Option<String>,
+    mode: Mode,
+    terminal: &Terminal,
+) -> render::Options {
+    render::Options {
+        settings,
+        font,
+        theme,
+        window,
+        title: title.or_else(|| terminal.title().map(|title| title.to_owned())),
+        mode,
+        background: Some(terminal.background().convert()),
+        foreground: Some(terminal.foreground().convert()),
+        cursor: terminal.cursor_color().map(|color| color.convert()),
+        scrollback: terminal.scrollback_len(),
+        highlights: Vec::new(),
+        images: terminal.images().iter().map(Into::into).collect(),
+        palette: terminal
+            .palette()
+            .iter()
+            .map(|(&i, color)| (i, color.convert()))
+            .collect(),
+        title_advances: HashMap::new(),
+    }
+}
+
+/// Measures the advances of the title characters with the first loadable title font.
+///
+/// Returns an empty map if none of the title font files can be loaded.
+pub fn measure_title<F, E>(
+    settings: &Settings,
+    window: &Window,
+    title: &str,
+    load: F,
+) -> HashMap<char, f32>
+where
+    F: Fn(&str) -> Result<FontFile, E>,
+    E: fmt::Display,
+{
+    let families = &window.title.font.family;
+
+    let file = families
+        .iter()
+        .flat_map(|family| {
+            settings
+                .fonts
+                .iter()
+                .filter(move |font| font.family == *family)
+        })
+        .flat_map(|font| &font.files)
+        .find_map(|file| match load(file) {
+            Ok(file) => Some(file),
+            Err(err) => {
+                log::debug!("failed to load title font {file}: {err}");
+                None
+            }
+        });
+
+    let Some(mut font) = file.as_ref().and_then(|file| file.font().ok()) else {
+        return HashMap::new();
+    };
+
+    title
+        .chars()
+        .filter_map(|ch| font.advance(ch).map(|advance| (ch, advance)))
+        .collect()
+}
+
+/// Embeds the font file into the face as a data URL.
+///
+/// With `subset`, only the glyphs of `chars` are embedded, compressed to WOFF2 if `woff2` is set,
+/// and the `unicode-range` of the face is limited to them. Otherwise the whole file is embedded.
+pub fn embed_font_face<C>(
+    face: &mut FontFace,
+    file: &FontFile,
+    font: &mut font::Font,
+    chars: C,
+    subset: bool,
+    woff2: bool,
+) -> font::Result<()>
+where
+    C: IntoIterator<Item = char> + Clone,
+{
+    let data = if subset {
+        // Only the subset keeps the used characters, so the range is not needed for whole fonts
+        face.unicode_range = render::char_ranges(chars.clone());
+        let data = font.subset(chars)?;
+        let compressed = woff2.then(|| font::woff2(&data));
+        let (data, format) = match compressed {
+            Some(Ok(compressed)) => (compressed, FontFormat::Woff2),
+            None => (data, FontFormat::Ttf),
+            Some(Err(err)) => {
+                log::warn!(
+                    "font face {url}: falling back to ttf: {err}",
+                    url = face.url
+                );
+                (data, FontFormat::Ttf)
+            }
+        };
+        face.format = Some(format);
+        Cow::Owned(data)
+    } else {
+        Cow::Borrowed(file.data())
+    };
+
+    log::debug!(
+        "embed font face {url}: {len} bytes",
+        url = face.url,
+        len = data.len()
+    );
+    face.url = format!(
+        "data:{};base64,{}",
+        face.format
+            .or(file.format())
+            .unwrap_or(FontFormat::Ttf)
+            .mime(),
+        BASE64_STANDARD.encode(data)
+    );
+
+    Ok(())
+}
+
+impl Convert<term::FormFeed> for config::FormFeed {
+    fn convert(&self) -> term::FormFeed {
+        match self {
+            Self::Newline => term::FormFeed::Newline,
+            Self::Clear => term::FormFeed::Clear,
+            Self::Pagebreak => term::FormFeed::Pagebreak,
+        }
+    }
+}
+
+impl Convert<term::WrapMode> for config::WrapMode {
+    fn convert(&self) -> term::WrapMode {
+        match self {
+            Self::Char => term::WrapMode::Char,
+            Self::Word => term::WrapMode::Word,
+            Self::None => term::WrapMode::None,
+        }
+    }
+}
+
+impl Convert<term::C0Filter> for config::C0Filter {
+    fn convert(&self) -> term::C0Filter {
+        match self {
+            Self::Ignore => term::C0Filter::Ignore,
+            Self::Caret => term::C0Filter::Caret,
+            Self::Replace => term::C0Filter::Replace,
+        }
+    }
+}
+
+impl Convert<term::Newline> for config::Newline {
+    fn convert(&self) -> term::Newline {
+        match self {
+            Self::Auto => term::Newline::Auto,
+            Self::Lf => term::Newline::Lf,
+            Self::Crlf => term::Newline::Crlf,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
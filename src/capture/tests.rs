@@ -0,0 +1,56 @@
+use super::*;
+
+use crate::{config::FontFace as FontFaceConfig, render::CharSetFn};
+
+fn face(url: &str) -> FontFace {
+    FontFace {
+        family: "Termframe Test".to_string(),
+        weight: render::FontWeight::Normal,
+        style: None,
+        url: url.to_string(),
+        format: None,
+        chars: Rc::new(CharSetFn::new(|_| true)),
+        metrics_match: true,
+        unicode_range: Vec::new(),
+    }
+}
+
+#[test]
+fn test_embed_font_face_subset_override() {
+    let mut settings = Settings::default();
+    settings.rendering.svg.subset_fonts = true;
+
+    let file = FontFile::load_bytes(
+        include_bytes!("../../assets/test/fonts/metrics.ttf"),
+        font::Location::from("metrics.ttf"),
+    )
+    .unwrap();
+    let configured = |subset| FontFaceConfig {
+        family: "Termframe Test".to_string(),
+        files: vec!["metrics.ttf".to_string()],
+        fallback: None,
+        subset,
+    };
+
+    let faces = [configured(None), configured(Some(false))].map(|configured| {
+        let mut face = face("metrics.ttf");
+        let subset = configured.should_subset(&settings.rendering.svg);
+        let mut font = file.font().unwrap();
+        embed_font_face(&mut face, &file, &mut font, "0a".chars(), subset, false).unwrap();
+        face
+    });
+
+    let whole = format!(
+        "data:font/ttf;base64,{}",
+        BASE64_STANDARD.encode(file.data())
+    );
+
+    // The face following the global setting is subset.
+    assert!(faces[0].url.starts_with("data:font/ttf;base64,"));
+    assert_ne!(faces[0].url, whole);
+    assert_eq!(faces[0].unicode_range, render::char_ranges("0a".chars()));
+
+    // The face with subsetting disabled is embedded whole.
+    assert_eq!(faces[1].url, whole);
+    assert!(faces[1].unicode_range.is_empty());
+}
@@ -1,5 +1,5 @@
 // std imports
-use std::{fmt, str::FromStr};
+use std::{fmt, path::PathBuf, str::FromStr};
 
 // third-party imports
 use clap::{
@@ -11,8 +11,13 @@ use clap_complete::Shell;
 use enumset_ext::convert::str::EnumSet;
 
 // local imports
-use crate::config::{
-    self, DimensionWithInitial, FontFamilyOption, PaddingOption, Settings, ThemeSetting,
+use crate::{
+    config::{
+        self, DimensionWithInitial, FontFamilyOption, HighlightLayer, PaddingOption, Settings,
+        ThemeSetting,
+    },
+    render::{Crop, Highlight},
+    theme,
 };
 
 const STYLES: Styles = Styles::styled()
@@ -40,6 +45,57 @@ pub struct Opt {
     #[arg(long, short = 'H', default_value_t = cfg().terminal.height, overrides_with = "height", value_name = "LINES")]
     pub height: DimensionWithInitial<u16>,
 
+    /// Render without wrapping.
+    ///
+    /// Join wrapped lines back together and widen the terminal to fit the longest of them,
+    /// ignoring the terminal width limits.
+    #[arg(long)]
+    pub no_wrap_render: bool,
+
+    /// Render the full output.
+    ///
+    /// Grow the terminal to fit the whole transcript including lines scrolled out of the screen,
+    /// ignoring the terminal height limits.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_value_t = cfg().rendering.full_output,
+        default_missing_value = "true",
+        hide_possible_values = true,
+        overrides_with = "full_output",
+        value_name = "ENABLED",
+    )]
+    pub full_output: bool,
+
+    /// Show line numbers.
+    ///
+    /// Draw a right-aligned column of line numbers to the left of the text.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_value_t = cfg().rendering.line_numbers.enabled,
+        default_missing_value = "true",
+        hide_possible_values = true,
+        overrides_with = "line_numbers",
+        value_name = "ENABLED",
+    )]
+    pub line_numbers: bool,
+
+    /// Work offline.
+    ///
+    /// Do not download fonts, font URLs are only loaded from the cache of earlier downloads
+    /// and fonts missing there are skipped.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_value_t = cfg().rendering.offline,
+        default_missing_value = "true",
+        hide_possible_values = true,
+        overrides_with = "offline",
+        value_name = "ENABLED",
+    )]
+    pub offline: bool,
+
     /// Override padding for the inner text in font size units.
     #[arg(long, overrides_with = "padding", value_name = "EM")]
     pub padding: Option<f32>,
@@ -47,6 +103,9 @@ pub struct Opt {
     /// Font family.
     ///
     /// Multiple comma separated values can be provided.
+    /// Values containing a colon are treated as fontconfig patterns, e.g. `monospace:weight=bold`,
+    /// and are resolved to system fonts if termframe is built with the `fontconfig` feature.
+    /// Families that are not configured are looked up in the installed system fonts.
     #[arg(long, value_parser = trim, num_args = 1.., value_delimiter = ',', overrides_with = "font_family", value_name = "NAME")]
     pub font_family: Vec<String>,
 
@@ -112,6 +171,41 @@ pub struct Opt {
     #[arg(long, default_value_t = cfg().rendering.faint_opacity.into(), overrides_with = "faint_opacity", value_name = "0..1")]
     pub faint_opacity: f32,
 
+    /// Opacity of the whole capture including the window.
+    #[arg(long, default_value_t = cfg().rendering.opacity.into(), overrides_with = "opacity", value_name = "0..1")]
+    pub opacity: f32,
+
+    /// Highlight a part of the screen with a translucent rectangle.
+    ///
+    /// Rows and columns are zero-based, the end column is exclusive.
+    /// May be specified multiple times.
+    #[arg(long = "highlight", value_name = "ROW[:START..END][=COLOR]")]
+    pub highlights: Vec<Highlight>,
+
+    /// Render only the given area of the screen.
+    ///
+    /// The area is aligned to the bottom left corner of the screen unless the zero-based
+    /// column X and row Y of its top left corner are given, e.g. `3` for the last 3 rows.
+    #[arg(long, conflicts_with = "animate", value_name = "ROWS[,COLUMNS][@X,Y]")]
+    pub crop: Option<Crop>,
+
+    /// Highlight cells that differ from the capture of the given baseline input file.
+    #[arg(long, overrides_with = "diff_against", value_name = "FILE")]
+    pub diff_against: Option<String>,
+
+    /// Color of the highlights of differing cells.
+    #[arg(
+        long,
+        overrides_with = "diff_color",
+        value_name = "COLOR",
+        requires = "diff_against"
+    )]
+    pub diff_color: Option<csscolorparser::Color>,
+
+    /// Layer where highlights are drawn.
+    #[arg(long, value_enum, default_value_t = cfg().rendering.highlight_layer, overrides_with = "highlight_layer")]
+    pub highlight_layer: HighlightLayer,
+
     /// Faint text font weight.
     #[arg(long, default_value_t = cfg().font.weights.faint.into(), overrides_with = "faint_font_weight", value_name = "WEIGHT")]
     pub faint_font_weight: FontWeight,
@@ -168,16 +262,16 @@ pub struct Opt {
     /// Show command.
     ///
     /// Show the executed command in the terminal output.
+    /// Any value other than true or false is used as the command prompt and enables it.
     #[arg(
         long,
         num_args = 0..=1,
-        default_value_t = cfg().command.show,
+        default_value_t = ShowCommand::Enabled(cfg().command.show),
         default_missing_value = "true",
-        hide_possible_values = true,
         overrides_with = "show_command",
-        value_name = "ENABLED"
+        value_name = "ENABLED|PROMPT"
     )]
-    pub show_command: bool,
+    pub show_command: ShowCommand,
 
     /// Command prompt to show before the executed command.
     #[arg(
@@ -209,12 +303,125 @@ pub struct Opt {
     )]
     pub var_palette: bool,
 
+    /// Use CSS classes for palette colors.
+    ///
+    /// Assign palette colors using CSS classes instead of inline fill attributes, implies --var-palette.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_value_t = cfg().rendering.svg.color_classes,
+        default_missing_value = "true",
+        hide_possible_values = true,
+        overrides_with = "color_classes",
+        value_name = "ENABLED",
+    )]
+    pub color_classes: bool,
+
+    /// Minify SVG output.
+    ///
+    /// Strip insignificant whitespace and default attributes to make the output smaller.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_value_t = cfg().rendering.svg.minify,
+        default_missing_value = "true",
+        hide_possible_values = true,
+        overrides_with = "minify",
+        value_name = "ENABLED",
+    )]
+    pub minify: bool,
+
     /// Output file.
     ///
     /// Use '-' for stdout.
     #[arg(long, short = 'o', overrides_with = "output", value_name = "FILE")]
     pub output: Option<String>,
 
+    /// Output format.
+    ///
+    /// Defaults to the format matching the output file extension, or svg.
+    /// HTML output is a styled `<pre>` block to be embedded into a page.
+    /// SVGZ output is a gzip-compressed SVG.
+    /// Text and ANSI outputs contain the screen text without and with SGR escape sequences.
+    /// PNG output requires termframe to be built with the `png` feature,
+    /// and WebP output with the `webp` feature.
+    #[arg(long, value_enum, overrides_with = "format")]
+    pub format: Option<OutputFormat>,
+
+    /// Scale factor for raster output formats, e.g. 2 for high DPI screens.
+    #[arg(
+        long,
+        default_value_t = 1.0,
+        overrides_with = "scale",
+        value_name = "FACTOR"
+    )]
+    pub scale: f32,
+
+    /// Compression quality of WebP output from 0 to 100.
+    ///
+    /// WebP output is lossless if not set.
+    #[arg(long, value_parser = quality, overrides_with = "quality", value_name = "QUALITY")]
+    pub quality: Option<f32>,
+
+    /// Also export the screen as an asciicast v2 recording.
+    ///
+    /// The recording contains a single frame printing the whole screen
+    /// and can be replayed by asciinema compatible players.
+    #[arg(long, overrides_with = "export_cast", value_name = "FILE")]
+    pub export_cast: Option<String>,
+
+    /// Dump traced background shapes as JSON to stderr or the given file, for debugging.
+    #[arg(
+        long,
+        hide = true,
+        num_args = 0..=1,
+        default_missing_value = "-",
+        require_equals = true,
+        value_name = "FILE"
+    )]
+    pub dump_shapes: Option<String>,
+
+    /// Text to show instead of an empty capture, e.g. '(no output)'.
+    #[arg(long, overrides_with = "placeholder", value_name = "TEXT")]
+    pub placeholder: Option<String>,
+
+    /// Fail instead of rendering an empty capture.
+    #[arg(long, conflicts_with = "placeholder")]
+    pub fail_on_empty: bool,
+
+    /// Exit with the exit code of the command if it fails, after the output is rendered.
+    #[arg(long, requires = "command")]
+    pub propagate_exit: bool,
+
+    /// Run the command line with a shell, so pipes and redirections can be used.
+    ///
+    /// The shell is taken from the configuration or the `SHELL` environment variable.
+    #[arg(long, requires = "command")]
+    pub shell: bool,
+
+    /// Working directory of the command, the current directory by default.
+    #[arg(
+        long,
+        requires = "command",
+        overrides_with = "cwd",
+        value_parser = existing_dir,
+        value_name = "DIR"
+    )]
+    pub cwd: Option<PathBuf>,
+
+    /// Set an environment variable for the command.
+    ///
+    /// Overrides the variable with the same name from the configuration.
+    /// May be specified multiple times.
+    #[arg(long = "env", value_parser = key_value, value_name = "KEY=VALUE")]
+    pub env: Vec<(String, String)>,
+
+    /// Keep capturing the output after the command exits until the timeout or the end of output.
+    ///
+    /// Useful for commands leaving behind background processes that write to the terminal.
+    #[arg(long, requires = "command")]
+    pub hold: bool,
+
     /// Command timeout.
     #[arg(
         long,
@@ -224,6 +431,29 @@ pub struct Opt {
     )]
     pub timeout: u64,
 
+    /// Input file to render instead of running a command.
+    ///
+    /// Either a recorded session in asciicast v2 format, whose terminal size and title are used unless overridden, or raw program output with ANSI escape sequences.
+    #[arg(
+        long,
+        overrides_with = "input",
+        conflicts_with = "command",
+        value_name = "FILE"
+    )]
+    pub input: Option<String>,
+
+    /// Animate the recording passed with --input instead of rendering its final screen.
+    ///
+    /// The terminal keeps the recorded size. Only SVG output can be animated.
+    #[arg(long, requires = "input", conflicts_with = "no_wrap_render")]
+    pub animate: bool,
+
+    /// Theme preview.
+    ///
+    /// Render a color swatch card for the selected theme instead of reading input.
+    #[arg(long, conflicts_with = "command")]
+    pub theme_preview: bool,
+
     /// List themes.
     ///
     /// Print available themes optionally filtered by tags and exit.
@@ -236,6 +466,24 @@ pub struct Opt {
     )]
     pub list_themes: Option<Option<ThemeTagSet>>,
 
+    /// Import theme.
+    ///
+    /// Convert a color scheme of another terminal emulator into a custom theme and exit, supported formats are: alacritty, windows-terminal, iterm2.
+    #[arg(long, value_name = "FORMAT:PATH")]
+    pub import_theme: Option<theme::import::Source>,
+
+    /// Export theme.
+    ///
+    /// Print the theme resolved for the selected mode as a complete TOML document to stdout or the output file and exit.
+    #[arg(long, value_name = "NAME")]
+    pub export_theme: Option<String>,
+
+    /// Validate theme.
+    ///
+    /// Check the theme file, or the theme with the given name, for problems in both dark and light modes and exit, the exit code is non-zero if any problems are found.
+    #[arg(long, value_name = "PATH")]
+    pub validate_theme: Option<String>,
+
     /// List syntax highlighting themes.
     ///
     /// Print available syntax highlighting themes and exit.
@@ -256,9 +504,16 @@ pub struct Opt {
 
     /// List fonts.
     ///
-    /// Print configured fonts and exit, any font not listed here cannot be embedded and may not be properly rendered.
-    #[arg(long)]
-    pub list_fonts: bool,
+    /// Print configured and installed system fonts and exit, any font not listed here cannot be embedded and may not be properly rendered.
+    /// With --list-fonts=long, also print the location, format, weight and style of each font file and whether it could be loaded.
+    #[arg(
+        long,
+        num_args=0..=1,
+        value_name = "VERBOSITY",
+        default_missing_value = "short",
+        require_equals = true,
+    )]
+    pub list_fonts: Option<ListVerbosity>,
 
     /// Print help.
     #[arg(
@@ -288,6 +543,15 @@ pub struct Opt {
     pub args: Vec<String>,
 }
 
+impl Opt {
+    /// Returns the output format, given explicitly or detected by the output file extension.
+    pub fn output_format(&self) -> OutputFormat {
+        self.format
+            .or_else(|| self.output.as_deref().and_then(OutputFormat::from_path))
+            .unwrap_or_default()
+    }
+}
+
 impl config::Patch for Opt {
     /// Applies the options from the command line arguments to the given settings.
     ///
@@ -313,9 +577,16 @@ impl config::Patch for Opt {
         settings.rendering.svg.embed_fonts = self.embed_fonts;
         settings.rendering.svg.subset_fonts = self.subset_fonts;
         settings.rendering.svg.var_palette = self.var_palette;
+        settings.rendering.svg.color_classes = self.color_classes;
+        settings.rendering.svg.minify = self.minify;
         settings.rendering.faint_opacity = self.faint_opacity.into();
+        settings.rendering.opacity = self.opacity.into();
+        settings.rendering.highlight_layer = self.highlight_layer;
         settings.rendering.line_height = self.line_height.into();
         settings.rendering.bold_is_bright = self.bold_is_bright;
+        settings.rendering.full_output = self.full_output;
+        settings.rendering.line_numbers.enabled = self.line_numbers;
+        settings.rendering.offline = self.offline;
         settings.theme = self.theme.clone();
         if let Some(theme) = &self.syntax_theme {
             settings.syntax.theme = Some(theme.clone());
@@ -332,6 +603,12 @@ impl config::Patch for Opt {
             settings.window.margin = Some(PaddingOption::Uniform(margin.into()));
         }
         settings.mode = self.mode;
+        settings.env.extend(self.env.iter().cloned());
+        if self.output_format().is_raster() {
+            // The rasterizer only sees the fonts embedded into the SVG, and cannot read WOFF2 files.
+            settings.rendering.svg.embed_fonts = true;
+            settings.rendering.svg.subset_fonts = true;
+        }
 
         settings
     }
@@ -409,7 +686,6 @@ impl BootstrapOpt {
 }
 
 pub type ThemeTagSet = EnumSet<config::theme::Tag>;
-pub type Dimension<T> = config::Dimension<T>;
 
 /// Font weight option.
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -482,12 +758,120 @@ impl fmt::Display for FontWeight {
     }
 }
 
+/// Show command option, either a flag or a prompt to show before the command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShowCommand {
+    Enabled(bool),
+    Prompt(String),
+}
+
+impl ShowCommand {
+    /// Returns true if the command should be shown.
+    pub fn enabled(&self) -> bool {
+        match self {
+            Self::Enabled(enabled) => *enabled,
+            Self::Prompt(_) => true,
+        }
+    }
+
+    /// Returns the prompt given with the option, if any.
+    pub fn prompt(&self) -> Option<&str> {
+        match self {
+            Self::Enabled(_) => None,
+            Self::Prompt(prompt) => Some(prompt),
+        }
+    }
+}
+
+impl FromStr for ShowCommand {
+    type Err = String;
+
+    /// Parses a string into a `ShowCommand`.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The string to parse.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the parsed `ShowCommand`, it never fails.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "true" => Ok(Self::Enabled(true)),
+            "false" => Ok(Self::Enabled(false)),
+            s => Ok(Self::Prompt(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for ShowCommand {
+    /// Formats the `ShowCommand` for display.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The formatter.
+    ///
+    /// # Returns
+    ///
+    /// A `fmt::Result`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Enabled(enabled) => write!(f, "{enabled}"),
+            Self::Prompt(prompt) => write!(f, "{prompt}"),
+        }
+    }
+}
+
+/// Output format option.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Svg,
+    Svgz,
+    Png,
+    Webp,
+    Html,
+    Text,
+    Ansi,
+}
+
+impl OutputFormat {
+    /// Detects the output format by the file extension, if it is a known one.
+    pub fn from_path(path: &str) -> Option<Self> {
+        let (_, ext) = path.rsplit_once('.')?;
+        match ext.to_ascii_lowercase().as_str() {
+            "svg" => Some(Self::Svg),
+            "svgz" => Some(Self::Svgz),
+            "png" => Some(Self::Png),
+            "webp" => Some(Self::Webp),
+            "html" | "htm" => Some(Self::Html),
+            "txt" => Some(Self::Text),
+            "ans" => Some(Self::Ansi),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the format is produced by rasterizing the SVG output.
+    pub fn is_raster(self) -> bool {
+        matches!(self, Self::Png | Self::Webp)
+    }
+}
+
 #[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HelpVerbosity {
     Short,
     Long,
 }
 
+/// Verbosity of font listings.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListVerbosity {
+    /// Print family names only.
+    Short,
+    /// Also print details of each font file.
+    Long,
+}
+
 /// Trims whitespace from a string.
 ///
 /// # Arguments
@@ -501,6 +885,58 @@ fn trim(s: &str) -> Result<String, String> {
     Ok(s.trim().to_string())
 }
 
+/// Parses a compression quality.
+///
+/// # Arguments
+///
+/// * `s` - The quality, a number from 0 to 100.
+///
+/// # Returns
+///
+/// A `Result` containing the quality or an error message.
+fn quality(s: &str) -> Result<f32, String> {
+    match s.trim().parse::<f32>() {
+        Ok(quality) if (0.0..=100.0).contains(&quality) => Ok(quality),
+        _ => Err(format!("expected a number from 0 to 100, got {s:?}")),
+    }
+}
+
+/// Checks that the directory exists.
+///
+/// # Arguments
+///
+/// * `s` - The path to the directory.
+///
+/// # Returns
+///
+/// A `Result` containing the path or an error message.
+fn existing_dir(s: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(s);
+    if path.is_dir() {
+        Ok(path)
+    } else if path.exists() {
+        Err(format!("{s} is not a directory"))
+    } else {
+        Err(format!("directory {s} does not exist"))
+    }
+}
+
+/// Parses an environment variable assignment in `KEY=VALUE` format.
+///
+/// # Arguments
+///
+/// * `s` - The assignment to parse.
+///
+/// # Returns
+///
+/// A `Result` containing the variable name and value or an error message.
+fn key_value(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got {s:?}")),
+    }
+}
+
 /// Retrieves the global settings.
 ///
 /// # Returns
@@ -1,6 +1,8 @@
 use std::str::FromStr;
 
-use crate::cli::FontWeight;
+use clap::Parser;
+
+use crate::cli::{FontWeight, ListVerbosity, Opt, ShowCommand};
 use crate::config::{self, FontFamilyOption, PaddingOption, Patch, Settings, ThemeSetting};
 
 #[test]
@@ -51,6 +53,53 @@ fn test_font_weight_conversion() {
     assert!(matches!(config_fixed, config::FontWeight::Fixed(600)));
 }
 
+#[test]
+fn test_show_command_from_str() {
+    let flag = ShowCommand::from_str("true").unwrap();
+    assert_eq!(flag, ShowCommand::Enabled(true));
+    assert!(flag.enabled());
+    assert_eq!(flag.prompt(), None);
+
+    let flag = ShowCommand::from_str("false").unwrap();
+    assert!(!flag.enabled());
+
+    let prompt = ShowCommand::from_str("% ").unwrap();
+    assert!(prompt.enabled());
+    assert_eq!(prompt.prompt(), Some("% "));
+    assert_eq!(prompt.to_string(), "% ");
+}
+
+#[test]
+fn test_show_command_arg() {
+    let opt = Opt::try_parse_from(["termframe", "--show-command", "false", "ls"]).unwrap();
+    assert_eq!(opt.show_command, ShowCommand::Enabled(false));
+    assert_eq!(opt.command.as_deref(), Some("ls"));
+
+    let opt = Opt::try_parse_from(["termframe", "--show-command", "% ", "ls", "-l"]).unwrap();
+    assert_eq!(opt.show_command.prompt(), Some("% "));
+    assert_eq!(opt.command.as_deref(), Some("ls"));
+    assert_eq!(opt.args, ["-l"]);
+
+    let opt = Opt::try_parse_from(["termframe", "--show-command=> ", "ls"]).unwrap();
+    assert_eq!(opt.show_command.prompt(), Some("> "));
+
+    let opt = Opt::try_parse_from(["termframe", "--show-command", "--", "ls"]).unwrap();
+    assert_eq!(opt.show_command, ShowCommand::Enabled(true));
+    assert_eq!(opt.command.as_deref(), Some("ls"));
+}
+
+#[test]
+fn test_list_fonts_arg() {
+    let opt = Opt::try_parse_from(["termframe", "--list-fonts"]).unwrap();
+    assert_eq!(opt.list_fonts, Some(ListVerbosity::Short));
+
+    let opt = Opt::try_parse_from(["termframe", "--list-fonts=long"]).unwrap();
+    assert_eq!(opt.list_fonts, Some(ListVerbosity::Long));
+
+    let opt = Opt::try_parse_from(["termframe", "ls"]).unwrap();
+    assert_eq!(opt.list_fonts, None);
+}
+
 #[test]
 fn test_opt_patch() {
     // Create base settings
@@ -131,3 +180,66 @@ fn create_test_opt() -> impl Patch {
         padding: Some(8.0),
     }
 }
+
+#[test]
+fn test_output_format_from_path() {
+    use crate::cli::OutputFormat;
+
+    assert_eq!(OutputFormat::from_path("out.svg"), Some(OutputFormat::Svg));
+    assert_eq!(
+        OutputFormat::from_path("out.svgz"),
+        Some(OutputFormat::Svgz)
+    );
+    assert_eq!(OutputFormat::from_path("out.PNG"), Some(OutputFormat::Png));
+    assert_eq!(
+        OutputFormat::from_path("out.WEBP"),
+        Some(OutputFormat::Webp)
+    );
+    assert_eq!(
+        OutputFormat::from_path("out.html"),
+        Some(OutputFormat::Html)
+    );
+    assert_eq!(OutputFormat::from_path("out.txt"), Some(OutputFormat::Text));
+    assert_eq!(OutputFormat::from_path("out.json"), None);
+    assert_eq!(OutputFormat::from_path("out"), None);
+}
+
+#[test]
+fn test_quality() {
+    use crate::cli::quality;
+
+    assert_eq!(quality("75"), Ok(75.0));
+    assert_eq!(quality(" 0 "), Ok(0.0));
+    assert_eq!(quality("100"), Ok(100.0));
+    assert!(quality("101").is_err());
+    assert!(quality("-1").is_err());
+    assert!(quality("high").is_err());
+}
+
+#[test]
+fn test_existing_dir() {
+    let dir = std::env::temp_dir();
+    assert_eq!(super::existing_dir(&dir.to_string_lossy()), Ok(dir.clone()));
+
+    let missing = dir.join(format!("termframe-missing-{}", std::process::id()));
+    let err = super::existing_dir(&missing.to_string_lossy()).unwrap_err();
+    assert!(err.contains("does not exist"), "{err}");
+}
+
+#[test]
+fn test_key_value() {
+    assert_eq!(
+        super::key_value("FOO=bar"),
+        Ok(("FOO".to_string(), "bar".to_string()))
+    );
+    assert_eq!(
+        super::key_value("FOO=a=b"),
+        Ok(("FOO".to_string(), "a=b".to_string()))
+    );
+    assert_eq!(
+        super::key_value("FOO="),
+        Ok(("FOO".to_string(), String::new()))
+    );
+    assert!(super::key_value("FOO").is_err());
+    assert!(super::key_value("=bar").is_err());
+}
@@ -18,26 +18,80 @@ pub fn to_title(
     )
 }
 
+/// Joins a command and its arguments into a shell script without quoting them.
+///
+/// Used when the command line is to be interpreted by a shell, so pipes and redirections are kept as is.
+pub fn to_script(
+    command: impl AsRef<str>,
+    args: impl IntoIterator<Item = impl AsRef<str>>,
+) -> String {
+    std::iter::once(command.as_ref().to_owned())
+        .chain(args.into_iter().map(|arg| arg.as_ref().to_owned()))
+        .join(" ")
+}
+
+/// Returns the shell used to run scripts.
+///
+/// The configured shell is preferred, then the `SHELL` environment variable, then `sh`.
+pub fn shell(configured: Option<&str>) -> String {
+    configured
+        .map(str::to_owned)
+        .or_else(|| std::env::var("SHELL").ok())
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or_else(|| "sh".to_owned())
+}
+
+/// Command prompt shown before the command line.
+#[derive(Debug, Clone, Copy)]
+pub struct Prompt<'a> {
+    /// Prompt text.
+    pub text: &'a str,
+    /// SGR parameters applied to the prompt text, e.g. `1;32` for bold green.
+    pub style: &'a str,
+}
+
+impl<'a> From<&'a str> for Prompt<'a> {
+    fn from(text: &'a str) -> Self {
+        Self {
+            text,
+            style: DEFAULT_PROMPT_STYLE,
+        }
+    }
+}
+
 /// Formats a command line with syntax highlighting for display in the terminal surface.
 ///
 /// Uses tree-sitter-based syntax highlighting to colorize the command as bash.
-/// The prompt is rendered as-is, followed by the highlighted command and a trailing newline.
-pub fn to_terminal(
-    prompt: impl AsRef<str>,
+/// The prompt is rendered as-is in its style, followed by the highlighted command and a trailing newline.
+pub fn to_terminal<'a>(
+    prompt: impl Into<Prompt<'a>>,
     command: impl AsRef<str>,
     args: impl IntoIterator<Item = impl AsRef<str>>,
     theme: Option<Theme>,
 ) -> Vec<u8> {
-    let prompt = prompt.as_ref();
-    let command = command_string(command, args);
+    script_to_terminal(prompt, command_string(command, args), theme)
+}
+
+/// Formats a shell script with syntax highlighting for display in the terminal surface.
+///
+/// Same as [`to_terminal`], but the script is shown as is, without quoting.
+pub fn script_to_terminal<'a>(
+    prompt: impl Into<Prompt<'a>>,
+    script: impl AsRef<str>,
+    theme: Option<Theme>,
+) -> Vec<u8> {
+    let prompt = prompt.into();
+    let command = script.as_ref();
 
     let highlighter = Highlighter::new(Language::Bash, theme);
 
     let mut output = Vec::new();
-    output.extend(b"\x1b[35m");
-    output.extend(prompt.as_bytes());
+    if !prompt.style.is_empty() {
+        output.extend(format!("\x1b[{}m", prompt.style).as_bytes());
+    }
+    output.extend(prompt.text.as_bytes());
     output.extend(b"\x1b[0m");
-    highlighter.format(&command, &mut output).unwrap();
+    highlighter.format(command, &mut output).unwrap();
     output.push(b'\n');
 
     output
@@ -55,5 +109,8 @@ fn command_string(
         .join(" ")
 }
 
+/// Default SGR parameters for the prompt, magenta foreground.
+const DEFAULT_PROMPT_STYLE: &str = "35";
+
 #[cfg(test)]
 mod tests;
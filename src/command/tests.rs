@@ -28,6 +28,28 @@ fn test_to_title_with_special_chars() {
     assert!(title_str.contains("HOME"));
 }
 
+#[test]
+fn test_to_script() {
+    assert_eq!(
+        to_script("ls | grep foo", Vec::<&str>::new()),
+        "ls | grep foo"
+    );
+    assert_eq!(to_script("ls", ["-la", "|", "wc -l"]), "ls -la | wc -l");
+}
+
+#[test]
+fn test_shell() {
+    assert_eq!(shell(Some("/bin/bash")), "/bin/bash");
+    assert!(!shell(None).is_empty());
+}
+
+#[test]
+fn test_script_to_terminal() {
+    let s = String::from_utf8(script_to_terminal("$ ", "ls | grep foo", None)).unwrap();
+    assert!(s.contains("grep"), "{s:?}");
+    assert!(!s.contains('\''), "script must not be quoted: {s:?}");
+}
+
 #[test]
 fn test_to_terminal_with_theme() {
     let s = to_terminal_str("$ ", "echo", &["hello"], Some(test_theme()));
@@ -77,3 +99,20 @@ fn test_to_terminal_special_chars() {
     assert!(s.contains("Hello,"));
     assert!(s.contains("World"));
 }
+
+#[test]
+fn test_to_terminal_prompt_style() {
+    let prompt = Prompt {
+        text: "% ",
+        style: "1;32",
+    };
+    let s = String::from_utf8(to_terminal(prompt, "ls", ["-la"], None)).unwrap();
+    assert!(s.starts_with("\x1b[1;32m% \x1b[0m"));
+
+    let prompt = Prompt {
+        text: "% ",
+        style: "",
+    };
+    let s = String::from_utf8(to_terminal(prompt, "ls", ["-la"], None)).unwrap();
+    assert!(s.starts_with("% \x1b[0m"));
+}
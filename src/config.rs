@@ -8,6 +8,7 @@ use std::{
 
 // third-party imports
 use anyhow::{Context, Result};
+use clap::ValueEnum;
 use config::{Config, File, FileFormat};
 use serde::Deserialize;
 
@@ -146,6 +147,8 @@ impl Default for &'static Settings {
 pub struct Command {
     pub show: bool,
     pub prompt: String,
+    pub prompt_style: String,
+    pub shell: Option<String>,
 }
 
 // Syntax highlighting settings structure.
@@ -161,10 +164,53 @@ pub struct Syntax {
 pub struct Rendering {
     pub line_height: Number,
     pub faint_opacity: Number,
+    pub opacity: Number,
     pub bold_is_bright: bool,
+    pub synthetic_bold: bool,
+    pub min_contrast: Option<Number>,
+    pub max_colors: Option<usize>,
+    pub full_width_bg: bool,
+    pub full_output: bool,
+    pub offline: bool,
+    pub indent_guides: bool,
+    pub highlight_layer: HighlightLayer,
+    pub scrollbar: bool,
+    pub line_numbers: LineNumbers,
+    pub cursor: Cursor,
     pub svg: Svg,
 }
 
+/// Line number gutter settings structure.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct LineNumbers {
+    pub enabled: bool,
+    pub start: usize,
+}
+
+/// Cursor settings structure.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Cursor {
+    pub enabled: bool,
+    pub style: CursorStyle,
+    pub color: Option<csscolorparser::Color>,
+    pub blink: bool,
+}
+
+/// Cursor shape enumeration.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum CursorStyle {
+    /// Cell-sized block.
+    #[default]
+    Block,
+    /// Vertical bar at the left edge of the cell.
+    Bar,
+    /// Horizontal line at the bottom edge of the cell.
+    Underline,
+}
+
 /// SVG settings structure.
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
@@ -174,6 +220,47 @@ pub struct Svg {
     pub embed_fonts: bool,
     pub subset_fonts: bool,
     pub var_palette: bool,
+    pub color_classes: bool,
+    pub row_ids: bool,
+    pub word_ids: bool,
+    pub merge_diagonals: bool,
+    pub embed_text: bool,
+    pub merge_spans: bool,
+    pub hyperlinks: bool,
+    pub images: bool,
+    pub synthetic_oblique: bool,
+    pub minify: bool,
+    pub id_prefix: String,
+    pub blink: bool,
+    pub animation: Animation,
+}
+
+/// Animation settings structure.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Animation {
+    pub speed: Number,
+    pub r#loop: bool,
+}
+
+/// Layer where highlights are drawn enumeration.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum HighlightLayer {
+    /// Above the text, so that translucent highlights tint it.
+    #[default]
+    Overlay,
+    /// Between the background and the text.
+    Underlay,
+}
+
+impl fmt::Display for HighlightLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overlay => write!(f, "overlay"),
+            Self::Underlay => write!(f, "underlay"),
+        }
+    }
 }
 
 /// Window settings structure.
@@ -184,6 +271,7 @@ pub struct Window {
     pub shadow: bool,
     pub style: String,
     pub margin: Option<PaddingOption>,
+    pub title: Option<String>,
 }
 
 /// Theme setting enumeration.
@@ -262,6 +350,15 @@ pub struct FontFace {
     pub family: String,
     pub files: Vec<String>,
     pub fallback: Option<FontFaceFallback>,
+    pub subset: Option<bool>,
+}
+
+impl FontFace {
+    /// Check whether the font files should be subset when embedded,
+    /// falling back to the global `subset-fonts` setting if not overridden.
+    pub fn should_subset(&self, svg: &Svg) -> bool {
+        self.subset.unwrap_or(svg.subset_fonts)
+    }
 }
 
 /// Font face fallback structure.
@@ -278,6 +375,66 @@ pub struct FontFaceFallback {
 pub struct Terminal {
     pub width: DimensionWithInitial<u16>,
     pub height: DimensionWithInitial<u16>,
+    pub tab_width: usize,
+    /// Maximum number of lines kept above the visible screen, `0` disables scrollback.
+    pub scrollback_limit: usize,
+    pub formfeed: FormFeed,
+    pub wrap_mode: WrapMode,
+    pub c0_filter: C0Filter,
+    pub newline: Newline,
+    pub device_attributes: Vec<u16>,
+}
+
+/// Form feed handling enumeration.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum FormFeed {
+    /// Treat form feed as a new line.
+    #[default]
+    Newline,
+    /// Clear the screen.
+    Clear,
+    /// Insert a blank separator row.
+    Pagebreak,
+}
+
+/// Line rewrapping mode enumeration.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum WrapMode {
+    /// Wrap at any character.
+    #[default]
+    Char,
+    /// Wrap at whitespace, splitting only tokens that do not fit in a row.
+    Word,
+    /// Do not wrap, text of long lines past the right edge of the terminal is cut off.
+    None,
+}
+
+/// Handling of stray C0 control characters enumeration.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum C0Filter {
+    /// Drop the control character.
+    #[default]
+    Ignore,
+    /// Print the control character in caret notation, e.g. `^@` for NUL.
+    Caret,
+    /// Print a replacement character instead of the control character.
+    Replace,
+}
+
+/// Input newline normalization enumeration.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum Newline {
+    /// Keep the input as is, both LF and CR LF end a line and a lone CR returns to the line start.
+    #[default]
+    Auto,
+    /// Lines end with LF, CR characters are dropped so that they never overwrite text.
+    Lf,
+    /// Lines end with CR LF, a lone CR also ends a line instead of overwriting it.
+    Crlf,
 }
 
 /// Font settings structure.
@@ -287,6 +444,9 @@ pub struct Font {
     pub family: FontFamilyOption,
     pub size: Number,
     pub weights: FontWeights,
+    pub baseline_offset: Number,
+    /// Time in seconds after which cached downloaded font files are revalidated.
+    pub cache_ttl: u64,
 }
 
 /// Font family option enumeration.
@@ -351,10 +511,28 @@ pub enum FontWeight {
     #[default]
     Normal,
     Bold,
-    #[serde(untagged)]
+    #[serde(untagged, deserialize_with = "deserialize_font_weight")]
     Fixed(u16),
 }
 
+/// Deserializes a numeric font weight given either as a number or as a string, e.g. `800` or `"800"`.
+fn deserialize_font_weight<'de, D>(deserializer: D) -> std::result::Result<u16, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Number(u16),
+        String(String),
+    }
+
+    match Repr::deserialize(deserializer)? {
+        Repr::Number(weight) => Ok(weight),
+        Repr::String(weight) => weight.parse().map_err(serde::de::Error::custom),
+    }
+}
+
 impl fmt::Display for FontWeight {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -95,6 +95,15 @@ pub trait Categorize {
     fn category(&self) -> ErrorCategory;
 }
 
+impl Categorize for Error {
+    fn category(&self) -> ErrorCategory {
+        match self {
+            Self::ItemNotFound { .. } => ErrorCategory::ItemNotFound,
+            _ => ErrorCategory::Other,
+        }
+    }
+}
+
 /// Enum for error categories.
 pub enum ErrorCategory {
     ItemNotFound,
@@ -1,4 +1,6 @@
-use crate::config::{FontFamilyOption, FontWeight, Number, PaddingOption, Settings, ThemeSetting};
+use crate::config::{
+    FileFormat, FontFamilyOption, FontWeight, Number, PaddingOption, Settings, Source, ThemeSetting,
+};
 
 #[test]
 fn test_default_settings() {
@@ -6,6 +8,7 @@ fn test_default_settings() {
     let settings = Settings::default();
     assert_eq!(settings.terminal.width.min(), 80.into());
     assert_eq!(settings.terminal.height.min(), 24.into());
+    assert_eq!(settings.terminal.scrollback_limit, 10_000);
 }
 
 #[test]
@@ -198,3 +201,60 @@ fn test_global_config() {
     assert_eq!(global_settings.terminal.width.current, 100.into());
     assert_eq!(global_settings.terminal.height.current, 40.into());
 }
+
+#[test]
+fn test_font_weight_numeric() {
+    for weights in ["{ bold = 800 }", "{ bold = \"800\" }"] {
+        let settings = Settings::load([Source::string(
+            format!("[font]\nweights = {weights}"),
+            FileFormat::Toml,
+        )])
+        .unwrap();
+
+        assert_eq!(settings.font.weights.bold, FontWeight::Fixed(800));
+        assert_eq!(settings.font.weights.normal, FontWeight::Normal);
+    }
+}
+
+#[test]
+fn test_font_face_should_subset() {
+    let mut settings = Settings::default();
+    settings.rendering.svg.subset_fonts = true;
+
+    let face = |subset| crate::config::FontFace {
+        family: "Monospace".to_string(),
+        files: vec!["monospace.ttf".to_string()],
+        fallback: None,
+        subset,
+    };
+
+    assert!(face(None).should_subset(&settings.rendering.svg));
+    assert!(face(Some(true)).should_subset(&settings.rendering.svg));
+    assert!(!face(Some(false)).should_subset(&settings.rendering.svg));
+
+    settings.rendering.svg.subset_fonts = false;
+    assert!(!face(None).should_subset(&settings.rendering.svg));
+    assert!(face(Some(true)).should_subset(&settings.rendering.svg));
+}
+
+#[test]
+fn test_window_buttons_presets() {
+    use crate::config::winstyle::{WindowButtonsPreset, WindowStyleConfig};
+
+    for preset in [
+        WindowButtonsPreset::Macos,
+        WindowButtonsPreset::Windows,
+        WindowButtonsPreset::Gnome,
+    ] {
+        assert_eq!(
+            preset.buttons().unwrap().items.len(),
+            3,
+            "preset {preset:?}"
+        );
+    }
+
+    // The default window style refers to the macos preset.
+    let style = WindowStyleConfig::default();
+    assert_eq!(style.window.buttons.items.len(), 3);
+    assert_eq!(style.window.buttons.items[0].offset.f32(), 14.0);
+}
@@ -1,7 +1,7 @@
 // std imports
 use std::{
     collections::HashMap,
-    io,
+    fmt, io,
     path::PathBuf,
     str::FromStr,
     sync::{Arc, LazyLock},
@@ -10,6 +10,7 @@ use std::{
 // third-party imports
 use csscolorparser::Color;
 use enumset::{EnumSet, EnumSetType};
+use itertools::Itertools;
 use rust_embed::RustEmbed;
 use serde::Deserialize;
 use strum::Display;
@@ -20,7 +21,7 @@ use super::{
     load::{self, Categorize, ErrorCategory, Load},
     mode::Mode,
 };
-use crate::xerr::{HighlightQuoted, Suggestions};
+use crate::xerr::{Highlight, HighlightQuoted, Suggestions};
 
 /// Error is an error which may occur in the application.
 #[derive(Error, Debug)]
@@ -61,6 +62,23 @@ pub enum Error {
         name: Arc<str>,
         source: load::ParseError,
     },
+
+    /// Error when a palette index of a theme is not a number from 0 to 255.
+    #[error("invalid palette index {index} in {table} of theme {name}", index=.index.hlq(), table=.table.hl(), name=.name.hlq())]
+    InvalidPaletteIndex {
+        name: Arc<str>,
+        table: &'static str,
+        index: Arc<str>,
+        suggestions: Suggestions,
+    },
+
+    /// Error when a theme does not define some of the 16 ANSI palette colors.
+    #[error("missing palette entries {indices} in {table} of theme {name}", indices=.indices.iter().join(", ").hl(), table=.table.hl(), name=.name.hlq())]
+    MissingPaletteEntries {
+        name: Arc<str>,
+        table: &'static str,
+        indices: Vec<u8>,
+    },
 }
 
 impl From<load::Error> for Error {
@@ -140,6 +158,90 @@ impl Theme {
     }
 }
 
+impl ThemeConfig {
+    /// Checks the theme for problems not detected while parsing it.
+    ///
+    /// The palette of each mode must define all 16 ANSI colors using valid indices.
+    pub fn validate(&self, name: &str) -> Vec<Error> {
+        let tables = match &self.theme {
+            Theme::Fixed(fixed) => vec![("theme.colors.palette", &fixed.colors)],
+            Theme::Adaptive(adaptive) => vec![
+                (
+                    "theme.modes.dark.colors.palette",
+                    &adaptive.modes.dark.colors,
+                ),
+                (
+                    "theme.modes.light.colors.palette",
+                    &adaptive.modes.light.colors,
+                ),
+            ],
+        };
+
+        let mut problems = Vec::new();
+        for (table, colors) in tables {
+            let mut defined = [false; 16];
+            for index in colors.palette.keys() {
+                match index.resolve() {
+                    Some(i) => {
+                        if let Some(defined) = defined.get_mut(i as usize) {
+                            *defined = true;
+                        }
+                    }
+                    None => {
+                        let index = index.to_string();
+                        problems.push(Error::InvalidPaletteIndex {
+                            name: name.into(),
+                            table,
+                            suggestions: Suggestions::new(&index, (0..=255).map(|i| i.to_string())),
+                            index: index.into(),
+                        });
+                    }
+                }
+            }
+
+            let missing: Vec<u8> = (0..16).filter(|&i| !defined[i as usize]).collect();
+            if !missing.is_empty() {
+                problems.push(Error::MissingPaletteEntries {
+                    name: name.into(),
+                    table,
+                    indices: missing,
+                });
+            }
+        }
+
+        problems
+    }
+
+    /// Formats the theme configuration as a TOML document laid out like the stock themes.
+    pub fn to_toml(&self) -> String {
+        let tags = self
+            .tags
+            .iter()
+            .map(|tag| format!("{:?}", tag.to_string()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut lines = vec![format!("tags = [{tags}]")];
+        match &self.theme {
+            Theme::Fixed(fixed) => fixed.colors.to_toml("theme.colors", &mut lines),
+            Theme::Adaptive(adaptive) => {
+                let modes = &adaptive.modes;
+                modes
+                    .dark
+                    .colors
+                    .to_toml("theme.modes.dark.colors", &mut lines);
+                modes
+                    .light
+                    .colors
+                    .to_toml("theme.modes.light.colors", &mut lines);
+            }
+        }
+        lines.push(String::new());
+
+        lines.join("\n")
+    }
+}
+
 impl Load for ThemeConfig {
     type Assets = Assets;
     type Error = Error;
@@ -194,9 +296,41 @@ pub struct Colors {
     pub background: Color,
     pub foreground: Color,
     pub bright_foreground: Option<Color>,
+    pub cursor: Option<Color>,
     pub palette: Palette,
 }
 
+impl Colors {
+    /// Appends the colors as TOML lines of the given table and its palette subtable.
+    fn to_toml(&self, table: &str, lines: &mut Vec<String>) {
+        let entry = |key: &str, color: &Color| format!("{key} = {:?}", color.to_css_hex());
+
+        lines.extend([String::new(), format!("[{table}]")]);
+        lines.push(entry("background", &self.background));
+        lines.push(entry("foreground", &self.foreground));
+        if let Some(color) = &self.bright_foreground {
+            lines.push(entry("bright-foreground", color));
+        }
+        if let Some(color) = &self.cursor {
+            lines.push(entry("cursor", color));
+        }
+
+        let mut palette: Vec<_> = self
+            .palette
+            .iter()
+            .filter_map(|(index, color)| Some((index.resolve()?, color)))
+            .collect();
+        palette.sort_by_key(|(index, _)| *index);
+
+        lines.extend([String::new(), format!("[{table}.palette]")]);
+        lines.extend(
+            palette
+                .into_iter()
+                .map(|(index, color)| entry(&index.to_string(), color)),
+        );
+    }
+}
+
 pub type Palette = HashMap<PaletteIndex, Color>;
 
 /// Index for a color in the palette.
@@ -217,6 +351,15 @@ impl PaletteIndex {
     }
 }
 
+impl fmt::Display for PaletteIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::U8(value) => write!(f, "{value}"),
+            Self::String(value) => write!(f, "{value}"),
+        }
+    }
+}
+
 /// A map for aliasing theme names.
 struct AliasMap {
     a2n: HashMap<String, String>,
@@ -257,3 +400,6 @@ impl AliasMap {
 pub struct Assets;
 
 static ALIAS_MAP: LazyLock<AliasMap> = LazyLock::new(AliasMap::load);
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,102 @@
+use super::*;
+
+const VALID: &str = r##"
+tags = ["dark"]
+
+[theme.colors]
+background = "#000000"
+foreground = "#ffffff"
+
+[theme.colors.palette]
+0 = "#000000"
+1 = "#800000"
+2 = "#008000"
+3 = "#808000"
+4 = "#000080"
+5 = "#800080"
+6 = "#008080"
+7 = "#c0c0c0"
+8 = "#808080"
+9 = "#ff0000"
+10 = "#00ff00"
+11 = "#ffff00"
+12 = "#0000ff"
+13 = "#ff00ff"
+14 = "#00ffff"
+15 = "#ffffff"
+"##;
+
+fn parse(data: &str) -> ThemeConfig {
+    ThemeConfig::from_buf(data.as_bytes(), load::Format::Toml).unwrap()
+}
+
+#[test]
+fn test_validate_valid_theme() {
+    assert!(parse(VALID).validate("valid").is_empty());
+}
+
+#[test]
+fn test_validate_stock_themes() {
+    for name in ["Atom One Dark", "One Double"] {
+        let cfg = ThemeConfig::load(name).unwrap();
+        let problems = cfg.validate(name);
+        assert!(problems.is_empty(), "{name}: {problems:?}");
+    }
+}
+
+#[test]
+fn test_validate_missing_palette_entry() {
+    let data = VALID.replace("12 = \"#0000ff\"\n", "");
+    let problems = parse(&data).validate("missing");
+
+    assert_eq!(problems.len(), 1);
+    match &problems[0] {
+        Error::MissingPaletteEntries {
+            name,
+            table,
+            indices,
+        } => {
+            assert_eq!(name.as_ref(), "missing");
+            assert_eq!(*table, "theme.colors.palette");
+            assert_eq!(indices, &[12]);
+        }
+        err => panic!("unexpected error: {err}"),
+    }
+}
+
+#[test]
+fn test_validate_invalid_palette_index() {
+    let data = VALID.replace("15 = ", "\"15 \" = ");
+    let problems = parse(&data).validate("invalid");
+
+    assert_eq!(problems.len(), 2);
+    assert!(matches!(
+        &problems[0],
+        Error::InvalidPaletteIndex { index, suggestions, .. }
+            if index.as_ref() == "15 " && suggestions.iter().next() == Some("15")
+    ));
+    assert!(matches!(
+        &problems[1],
+        Error::MissingPaletteEntries { indices, .. } if indices == &[15]
+    ));
+}
+
+#[test]
+fn test_validate_adaptive_theme_reports_mode() {
+    let light = VALID
+        .replace("[theme.colors", "[theme.modes.light.colors")
+        .replace("tags = [\"dark\"]", "");
+    let dark = VALID
+        .replace("[theme.colors", "[theme.modes.dark.colors")
+        .replace("tags = [\"dark\"]", "")
+        .replace("3 = \"#808000\"\n", "");
+    let data = format!("tags = [\"dark\", \"light\"]\n{dark}\n{light}");
+    let problems = parse(&data).validate("adaptive");
+
+    assert_eq!(problems.len(), 1);
+    assert!(matches!(
+        &problems[0],
+        Error::MissingPaletteEntries { table: "theme.modes.dark.colors.palette", indices, .. }
+            if indices == &[3]
+    ));
+}
@@ -7,8 +7,10 @@ use std::{
 
 // third-party imports
 use csscolorparser::Color;
+use itertools::Itertools;
 use rust_embed::RustEmbed;
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer, de::Error as _};
+use strum::Display;
 use thiserror::Error;
 
 // local imports
@@ -58,6 +60,10 @@ pub enum Error {
         name: Arc<str>,
         source: load::ParseError,
     },
+
+    /// Error when a window style extends itself directly or through its base styles.
+    #[error("window style {name} extends itself: {chain}", name=.chain[0].hlq(), chain=.chain.iter().map(|name| name.hlq()).join(" -> "))]
+    WindowStyleInheritanceCycle { chain: Vec<Arc<str>> },
 }
 
 impl From<load::Error> for Error {
@@ -94,9 +100,42 @@ impl Categorize for Error {
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct WindowStyleConfig {
+    /// Name or path of the window style this one is based on, overriding some of its settings.
+    pub extends: Option<String>,
     pub window: Window,
 }
 
+impl WindowStyleConfig {
+    /// Merges the window style with the chain of styles it extends and parses the result.
+    fn inherit(name: &str, layer: Layer) -> Result<Self, Error> {
+        let mut chain: Vec<Arc<str>> = vec![name.into()];
+        let mut layers = vec![layer.0];
+
+        while let Some(base) = layers
+            .last()
+            .and_then(|layer| layer.get("extends")?.as_str())
+        {
+            let base: Arc<str> = base.into();
+            let cycle = chain.contains(&base);
+            chain.push(base.clone());
+            if cycle {
+                return Err(Error::WindowStyleInheritanceCycle { chain });
+            }
+            layers.push(Layer::load_hybrid(&base)?.0);
+        }
+
+        let mut value = layers.pop().unwrap_or_default();
+        while let Some(layer) = layers.pop() {
+            merge(&mut value, layer);
+        }
+
+        serde_json::from_value(value).map_err(|e| Error::FailedToParseWindowStyle {
+            name: name.into(),
+            source: e.into(),
+        })
+    }
+}
+
 impl Default for WindowStyleConfig {
     fn default() -> Self {
         DEFAULT.as_ref().clone()
@@ -124,6 +163,59 @@ impl Load for WindowStyleConfig {
     fn is_not_found_error(err: &Error) -> bool {
         matches!(err, Error::WindowStyleNotFound { .. })
     }
+
+    fn load(name: &str) -> Result<Self, Error> {
+        Self::inherit(name, Layer::load(name)?)
+    }
+
+    fn load_hybrid(name_or_path: &str) -> Result<Self, Error> {
+        Self::inherit(name_or_path, Layer::load_hybrid(name_or_path)?)
+    }
+}
+
+/// Window style as it is written in a file, before merging it with the style it extends.
+#[derive(Deserialize)]
+#[serde(transparent)]
+struct Layer(serde_json::Value);
+
+impl Load for Layer {
+    type Assets = Assets;
+    type Error = Error;
+
+    fn category() -> &'static str {
+        WindowStyleConfig::category()
+    }
+
+    fn dir_name() -> &'static str {
+        WindowStyleConfig::dir_name()
+    }
+
+    fn is_not_found_error(err: &Error) -> bool {
+        WindowStyleConfig::is_not_found_error(err)
+    }
+}
+
+/// Merges the overriding settings into the base ones.
+///
+/// Tables are merged recursively, any other values including arrays are replaced.
+/// A table referring to a preset, like `buttons = { preset = "macos" }`, replaces the base table
+/// as well, because the preset defines all of its settings.
+fn merge(base: &mut serde_json::Value, patch: serde_json::Value) {
+    use serde_json::Value;
+
+    match (base, patch) {
+        (Value::Object(base), Value::Object(patch)) if !patch.contains_key("preset") => {
+            for (key, value) in patch {
+                match base.get_mut(&key) {
+                    Some(base) => merge(base, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, patch) => *base = patch,
+    }
 }
 
 /// Configuration for a window.
@@ -135,6 +227,7 @@ pub struct Window {
     pub border: WindowBorder,
     pub header: WindowHeader,
     pub title: WindowTitle,
+    #[serde(deserialize_with = "deserialize_window_buttons")]
     pub buttons: WindowButtons,
     pub shadow: WindowShadow,
 }
@@ -145,10 +238,35 @@ pub struct Window {
 pub struct WindowBorder {
     pub colors: WindowBorderColors,
     pub width: Number,
-    pub radius: Number,
+    pub radius: CornerRadius,
     pub gap: Option<Number>,
 }
 
+/// Radius of the window corners, either the same for all of them or one per corner.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+pub enum CornerRadius {
+    Uniform(Number),
+    /// Radii of the top-left, top-right, bottom-right and bottom-left corners.
+    PerCorner([Number; 4]),
+}
+
+impl CornerRadius {
+    /// Resolve the radii of the top-left, top-right, bottom-right and bottom-left corners.
+    pub fn resolve(&self) -> [f32; 4] {
+        match self {
+            Self::Uniform(value) => [value.f32(); 4],
+            Self::PerCorner(values) => values.map(Number::f32),
+        }
+    }
+}
+
+impl From<Number> for CornerRadius {
+    fn from(value: Number) -> Self {
+        Self::Uniform(value)
+    }
+}
+
 /// Colors for a window border.
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
@@ -180,6 +298,20 @@ pub struct WindowHeaderBorder {
 pub struct WindowTitle {
     pub color: SelectiveColor,
     pub font: Font,
+    /// Marker appended to a trimmed title, `…` if not set, empty to cut the title without a marker.
+    pub ellipsis: Option<String>,
+    #[serde(default)]
+    pub align: TitleAlign,
+}
+
+/// Horizontal alignment of a window title in the header.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TitleAlign {
+    Left,
+    #[default]
+    Center,
+    Right,
 }
 
 /// Configuration for a font.
@@ -202,6 +334,68 @@ pub struct WindowButtons {
     pub items: Vec<WindowButton>,
 }
 
+impl Load for WindowButtons {
+    type Assets = ButtonsAssets;
+    type Error = load::Error;
+
+    fn category() -> &'static str {
+        "window buttons presets"
+    }
+
+    fn dir_name() -> &'static str {
+        "window-buttons"
+    }
+
+    fn is_not_found_error(err: &load::Error) -> bool {
+        matches!(err, load::Error::ItemNotFound { .. })
+    }
+}
+
+/// Built-in window buttons preset, selected with `buttons = { preset = "macos" }`.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Display)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum WindowButtonsPreset {
+    Macos,
+    Windows,
+    Gnome,
+}
+
+impl WindowButtonsPreset {
+    /// Loads the window buttons configuration of the preset.
+    pub fn buttons(self) -> Result<WindowButtons, load::Error> {
+        WindowButtons::embedded(&self.to_string())
+    }
+}
+
+/// Deserializes window buttons given either as a preset reference or in full.
+///
+/// A table with a `preset` key is a preset reference, any other value is a full configuration.
+fn deserialize_window_buttons<'de, D>(deserializer: D) -> Result<WindowButtons, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct PresetRef {
+        preset: WindowButtonsPreset,
+    }
+
+    let value = serde_json::Value::deserialize(deserializer)?;
+
+    if value.get("preset").is_some() {
+        let r = PresetRef::deserialize(value).map_err(|e| {
+            D::Error::custom(format!("invalid window buttons preset reference: {e}"))
+        })?;
+        r.preset
+            .buttons()
+            .map_err(|e| D::Error::custom(format!("failed to load window buttons preset: {e}")))
+    } else {
+        WindowButtons::deserialize(value)
+            .map_err(|e| D::Error::custom(format!("invalid window buttons: {e}")))
+    }
+}
+
 /// Configuration for a window button.
 #[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
@@ -227,6 +421,8 @@ pub enum WindowButtonsPosition {
 pub enum WindowButtonShape {
     Circle,
     Square,
+    Triangle,
+    Diamond,
 }
 
 /// Icon for a window button.
@@ -248,6 +444,9 @@ pub enum WindowButtonIconKind {
     Close,
     Minimize,
     Maximize,
+    /// Raw SVG path data drawn in a unit square and scaled to the icon size,
+    /// e.g. `kind = { custom = "M0,0.5 H1" }`.
+    Custom(String),
 }
 
 /// Line cap style for a window button icon.
@@ -268,6 +467,41 @@ pub struct WindowShadow {
     pub x: Number,
     pub y: Number,
     pub blur: Number,
+    #[serde(default)]
+    pub spread: Number,
+    #[serde(default)]
+    pub inset: bool,
+    #[serde(default)]
+    pub layers: Vec<WindowShadowLayer>,
+}
+
+impl WindowShadow {
+    /// Returns all shadow layers in painting order, starting with the one defined by the shadow itself.
+    pub fn all_layers(&self) -> impl Iterator<Item = WindowShadowLayer> + '_ {
+        let main = WindowShadowLayer {
+            color: self.color.clone(),
+            x: self.x,
+            y: self.y,
+            blur: self.blur,
+            spread: self.spread,
+            inset: self.inset,
+        };
+        std::iter::once(main).chain(self.layers.iter().cloned())
+    }
+}
+
+/// Configuration for a single window shadow layer.
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct WindowShadowLayer {
+    pub color: SelectiveColor,
+    pub x: Number,
+    pub y: Number,
+    pub blur: Number,
+    #[serde(default)]
+    pub spread: Number,
+    #[serde(default)]
+    pub inset: bool,
 }
 
 /// Color that can be either uniform or adaptive based on the mode.
@@ -299,5 +533,13 @@ impl SelectiveColor {
 #[folder = "assets/window-styles/"]
 pub struct Assets;
 
+/// Embeds the assets for window buttons presets.
+#[derive(RustEmbed)]
+#[folder = "assets/window-buttons/"]
+pub struct ButtonsAssets;
+
 static DEFAULT: LazyLock<Arc<WindowStyleConfig>> =
     LazyLock::new(|| Arc::new(WindowStyleConfig::load("macos").unwrap()));
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,142 @@
+use std::path::PathBuf;
+
+use super::*;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "termframe-winstyle-test-{}-{name}",
+        std::process::id()
+    ));
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn path(dir: &std::path::Path, name: &str) -> String {
+    dir.join(name).to_string_lossy().into_owned()
+}
+
+#[test]
+fn test_extends_overrides_header_color() {
+    let dir = temp_dir("extends");
+    std::fs::write(
+        dir.join("child.toml"),
+        "extends = \"macos\"\n\n[window.header]\ncolor = \"#ff0000\"\n",
+    )
+    .unwrap();
+
+    let base = WindowStyleConfig::load("macos").unwrap();
+    let child = WindowStyleConfig::load_hybrid(&path(&dir, "child.toml")).unwrap();
+
+    assert_eq!(child.extends.as_deref(), Some("macos"));
+    assert_eq!(
+        child.window.header.color.resolve(Mode::Dark).to_css_hex(),
+        "#ff0000"
+    );
+    assert_eq!(
+        child.window.header.color.resolve(Mode::Light).to_css_hex(),
+        "#ff0000"
+    );
+    assert_eq!(child.window.header.height, base.window.header.height);
+    assert_eq!(child.window.border.radius, base.window.border.radius);
+    assert_eq!(
+        child.window.title.font.family,
+        base.window.title.font.family
+    );
+    assert_eq!(
+        child.window.buttons.items.len(),
+        base.window.buttons.items.len()
+    );
+    assert_eq!(child.window.shadow.blur, base.window.shadow.blur);
+}
+
+#[test]
+fn test_extends_chain_of_files() {
+    let dir = temp_dir("chain");
+    std::fs::write(
+        dir.join("middle.toml"),
+        "extends = \"compact\"\n\n[window.header]\nheight = 40\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("child.toml"),
+        format!(
+            "extends = {:?}\n\n[window.header.color]\ndark = \"#101010\"\n",
+            path(&dir, "middle.toml")
+        ),
+    )
+    .unwrap();
+
+    let base = WindowStyleConfig::load("compact").unwrap();
+    let child = WindowStyleConfig::load_hybrid(&path(&dir, "child.toml")).unwrap();
+
+    assert_eq!(child.window.header.height.f32(), 40.0);
+    assert_eq!(
+        child.window.header.color.resolve(Mode::Dark).to_css_hex(),
+        "#101010"
+    );
+    assert_eq!(
+        child.window.header.color.resolve(Mode::Light),
+        base.window.header.color.resolve(Mode::Light)
+    );
+}
+
+#[test]
+fn test_extends_cycle() {
+    let dir = temp_dir("cycle");
+    let a = path(&dir, "a.toml");
+    let b = path(&dir, "b.toml");
+    std::fs::write(&a, format!("extends = {b:?}\n")).unwrap();
+    std::fs::write(&b, format!("extends = {a:?}\n")).unwrap();
+
+    match WindowStyleConfig::load_hybrid(&a) {
+        Err(Error::WindowStyleInheritanceCycle { chain }) => {
+            let chain: Vec<&str> = chain.iter().map(|name| name.as_ref()).collect();
+            assert_eq!(chain, [a.as_str(), b.as_str(), a.as_str()]);
+        }
+        other => panic!("expected an inheritance cycle, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_merge_replaces_preset_table() {
+    let mut base = serde_json::json!({
+        "buttons": { "position": "left", "size": 12, "items": [] },
+        "margin": 8,
+    });
+    merge(
+        &mut base,
+        serde_json::json!({ "buttons": { "preset": "windows" }, "margin": { "horizontal": 1 } }),
+    );
+
+    assert_eq!(
+        base,
+        serde_json::json!({
+            "buttons": { "preset": "windows" },
+            "margin": { "horizontal": 1 },
+        })
+    );
+}
+
+#[test]
+fn test_window_buttons_errors() {
+    #[derive(Debug, Deserialize)]
+    struct Test {
+        #[serde(deserialize_with = "deserialize_window_buttons")]
+        buttons: WindowButtons,
+    }
+
+    let err = toml::from_str::<Test>("buttons = { preset = \"amiga\" }").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("invalid window buttons preset reference"),
+        "{err}"
+    );
+
+    let err = toml::from_str::<Test>("buttons = { position = \"left\", size = 12 }").unwrap_err();
+    assert!(err.to_string().contains("invalid window buttons:"), "{err}");
+    assert!(err.to_string().contains("items"), "{err}");
+
+    let test = toml::from_str::<Test>("buttons = { preset = \"gnome\" }").unwrap();
+    assert_eq!(test.buttons.items.len(), 3);
+}
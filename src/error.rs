@@ -62,6 +62,10 @@ pub enum Error {
     #[error("failed to parse json: {0}")]
     JsonParse(#[from] serde_json::Error),
 
+    /// Property list parsing error
+    #[error("failed to parse plist: {0}")]
+    Plist(#[from] plist::Error),
+
     /// Integer conversion error
     #[error(transparent)]
     TryFromInt(#[from] TryFromIntError),
@@ -95,6 +99,14 @@ impl Error {
                     usage,
                 }
             }
+            Error::Theme(theme::Error::InvalidPaletteIndex { suggestions, .. }) => Tips {
+                did_you_mean: did_you_mean(suggestions),
+                usage: None,
+            },
+            Error::Theme(theme::Error::MissingPaletteEntries { .. }) => Tips {
+                did_you_mean: None,
+                usage: Some("define colors for all palette indices from 0 to 15".into()),
+            },
             Error::WindowStyle(winstyle::Error::WindowStyleNotFound { suggestions, .. }) => {
                 let did_you_mean = did_you_mean(suggestions);
                 let usage = usage(app, UsageRequest::ListWindowStyles)
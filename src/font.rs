@@ -1,5 +1,9 @@
 // std imports
-use std::{path::PathBuf, sync::LazyLock, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    sync::LazyLock,
+    time::Duration,
+};
 
 // third-party imports
 use allsorts::{
@@ -15,7 +19,7 @@ use exponential_backoff::Backoff;
 use url::Url;
 
 // local imports
-use crate::fontformat::FontFormat;
+use crate::{fontformat::FontFormat, render::FontMetrics};
 
 // retry loop backoff configuration
 static BACKOFF: LazyLock<Backoff> =
@@ -86,6 +90,14 @@ impl FontFile {
         }
     }
 
+    /// Load a font file downloaded earlier from a URL from the cache in the given directory.
+    pub fn load_cached(url: Url, cache_dir: &Path) -> Result<Self> {
+        match crate::ureqmw::cache::lookup(cache_dir, url.as_str()) {
+            Some(data) => Self::load_bytes(&data, Location::Url(url)),
+            None => Err(Unavailable(url).into()),
+        }
+    }
+
     /// Load a font file from raw bytes.
     pub fn load_bytes(bytes: &[u8], location: Location) -> Result<Self> {
         let data = ReadScopeOwned::new(ReadScope::new(bytes));
@@ -118,14 +130,25 @@ impl FontFile {
         &self.location
     }
 
+    /// Get the metrics of the font used to lay out the terminal grid, in em units.
+    #[allow(dead_code)]
+    pub fn metrics(&self) -> Result<FontMetrics> {
+        Ok(self.font()?.metrics())
+    }
+
+    /// Get the family name of the font, reading only the name table.
+    ///
+    /// The typographic family is preferred, the legacy family name is used if it is missing.
+    pub fn family(&self) -> Result<Option<String>> {
+        let provider = self.data.scope().read::<FontData>()?.table_provider(0)?;
+        let (name, family) = names(&provider)?;
+        Ok(family.or(name))
+    }
+
     /// Get the font object from the font file.
     pub fn font(&self) -> Result<Font<'_>> {
         let provider = self.data.scope().read::<FontData>()?.table_provider(0)?;
-
-        let name_data = provider.read_table_data(tag::NAME)?;
-        let name_table = ReadScope::new(name_data.as_ref()).read::<NameTable>()?;
-        let name = name_table.string_for_id(1);
-        let family = name_table.string_for_id(16);
+        let (name, family) = names(&provider)?;
 
         let inner = allsorts::Font::new(provider)?;
         let Some(os2) = inner.os2_table()? else {
@@ -141,6 +164,44 @@ impl FontFile {
     }
 }
 
+/// Reads the legacy and the typographic family names from the name table.
+fn names<P: FontTableProvider>(provider: &P) -> Result<(Option<String>, Option<String>)> {
+    let name_data = provider.read_table_data(tag::NAME)?;
+    let name_table = ReadScope::new(name_data.as_ref()).read::<NameTable>()?;
+    Ok((name_table.string_for_id(1), name_table.string_for_id(16)))
+}
+
+/// Describes a font file and the result of loading it, for verbose font listings.
+///
+/// The description includes the resolved location, detected format, weight and style
+/// of the font, and whether it was loaded successfully.
+pub fn describe<E>(file: &str, loaded: &std::result::Result<FontFile, E>) -> String
+where
+    E: std::fmt::Display,
+{
+    let file = match loaded {
+        Ok(file) => file,
+        Err(err) => {
+            return format!(
+                "{location} status=error ({err:#})",
+                location = Location::from(file)
+            );
+        }
+    };
+
+    let location = file.location();
+    let format = file.format().map(|f| f.css()).unwrap_or("unknown");
+
+    match file.font() {
+        Ok(font) => format!(
+            "{location} format={format} weight={weight} style={style} status=ok",
+            weight = font.weight(),
+            style = if font.italic() { "italic" } else { "normal" },
+        ),
+        Err(err) => format!("{location} format={format} status=error ({err:#})"),
+    }
+}
+
 /// Represents the location of a font file, either a file path or a URL.
 #[derive(Debug, Clone)]
 pub enum Location {
@@ -164,8 +225,19 @@ impl Location {
             Self::File(_) => None,
         }
     }
+
+    /// Check if loading the font requires a network request.
+    pub fn is_remote(&self) -> bool {
+        self.url()
+            .is_some_and(|url| !matches!(url.scheme(), "file" | ""))
+    }
 }
 
+/// Error returned when a remote font is requested in offline mode and was not downloaded before.
+#[derive(Debug, thiserror::Error)]
+#[error("font {0} is not available offline")]
+pub struct Unavailable(pub Url);
+
 impl std::fmt::Display for Location {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -219,6 +291,15 @@ impl Font<'_> {
         self.name.as_deref()
     }
 
+    /// Get the metrics of the font used to lay out the terminal grid, in em units.
+    pub fn metrics(&mut self) -> FontMetrics {
+        FontMetrics {
+            width: self.width(),
+            ascender: self.ascender(),
+            descender: self.descender(),
+        }
+    }
+
     /// Get the width of the '0' glyph in the font.
     pub fn width(&mut self) -> f32 {
         let (glyph, _) = self
@@ -230,6 +311,14 @@ impl Font<'_> {
             .unwrap_or(1.0)
     }
 
+    /// Get the horizontal advance of the glyph for a character in em units, if the font has it.
+    pub fn advance(&mut self, ch: char) -> Option<f32> {
+        let glyph = self.glyph_index(ch)?;
+        self.inner
+            .horizontal_advance(glyph)
+            .map(|x| x as f32 / self.em() as f32)
+    }
+
     /// Get the ascender value of the font.
     pub fn ascender(&self) -> f32 {
         self.inner.hhea_table.ascender as f32 / self.em() as f32
@@ -247,7 +336,6 @@ impl Font<'_> {
     }
 
     /// Get the weight class of the font.
-    #[allow(dead_code)]
     pub fn weight(&self) -> u16 {
         self.os2.us_weight_class
     }
@@ -282,7 +370,8 @@ impl Font<'_> {
     where
         C: IntoIterator<Item = char>,
     {
-        let mut glyphs = std::collections::HashSet::new();
+        // Glyphs are ordered to make the subset identical for the same characters.
+        let mut glyphs = std::collections::BTreeSet::new();
         glyphs.insert(0);
 
         for ch in chars {
@@ -326,5 +415,13 @@ impl Font<'_> {
     }
 }
 
+/// Compress TrueType or OpenType font data into the WOFF2 format.
+pub fn woff2(data: &[u8]) -> Result<Vec<u8>> {
+    ttf2woff2::encode(data, ttf2woff2::BrotliQuality::default())
+        .map_err(|err| anyhow!("failed to encode font to woff2: {err:?}"))
+}
+
+pub mod system;
+
 #[cfg(test)]
 mod tests;
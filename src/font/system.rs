@@ -0,0 +1,136 @@
+// std imports
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+// local imports
+use super::FontFile;
+use crate::config::{FontFace, Fonts};
+
+/// File extensions of the font files looked up in the system font directories.
+const EXTENSIONS: &[&str] = &["ttf", "otf", "ttc", "woff", "woff2"];
+
+/// Returns the directories where fonts are installed on this system.
+pub fn font_dirs() -> Vec<PathBuf> {
+    let mut result = Vec::new();
+
+    if let Some(dir) = dirs::font_dir() {
+        result.push(dir);
+    }
+
+    if cfg!(target_os = "macos") {
+        result.extend(["/Library/Fonts", "/System/Library/Fonts"].map(PathBuf::from));
+    } else if cfg!(target_os = "windows") {
+        if let Some(dir) = std::env::var_os("WINDIR") {
+            result.push(PathBuf::from(dir).join("Fonts"));
+        }
+        if let Some(dir) = dirs::data_local_dir() {
+            result.push(dir.join("Microsoft").join("Windows").join("Fonts"));
+        }
+    } else {
+        if let Some(home) = dirs::home_dir() {
+            result.push(home.join(".fonts"));
+        }
+        result.extend(["/usr/local/share/fonts", "/usr/share/fonts"].map(PathBuf::from));
+    }
+
+    result.dedup();
+    result
+}
+
+/// Discovers the fonts installed in the given directories and groups their files by family.
+///
+/// Directories are scanned recursively, files that cannot be loaded are skipped.
+pub fn discover<P: AsRef<Path>>(dirs: &[P]) -> Fonts {
+    let mut families = BTreeMap::<String, Vec<String>>::new();
+
+    for dir in dirs {
+        for path in files(dir.as_ref()) {
+            let family = FontFile::load_file(path.clone()).and_then(|file| file.family());
+            match family {
+                Ok(Some(family)) => families
+                    .entry(family)
+                    .or_default()
+                    .push(path.to_string_lossy().into_owned()),
+                Ok(None) => log::debug!("skip system font {path:?}: no family name"),
+                Err(err) => log::debug!("skip system font {path:?}: {err}"),
+            }
+        }
+    }
+
+    families
+        .into_iter()
+        .map(|(family, mut files)| {
+            files.sort();
+            FontFace {
+                family,
+                files,
+                fallback: None,
+                subset: None,
+            }
+        })
+        .collect()
+}
+
+/// Adds the discovered faces for the requested families that are not configured yet.
+///
+/// Family names are compared case-insensitively, the requested family is replaced with the
+/// name of the discovered one so that it matches the name stored in the font files.
+pub fn merge(families: Vec<String>, fonts: &mut Fonts, discovered: &[FontFace]) -> Vec<String> {
+    families
+        .into_iter()
+        .map(|family| {
+            if fonts.iter().any(|face| face.family == family) {
+                return family;
+            }
+
+            let Some(face) = discovered
+                .iter()
+                .find(|face| face.family.eq_ignore_ascii_case(&family))
+            else {
+                return family;
+            };
+
+            log::debug!(
+                "font family {family:?} found in system fonts: {:?}",
+                face.files
+            );
+            if !fonts
+                .iter()
+                .any(|configured| configured.family == face.family)
+            {
+                fonts.push(face.clone());
+            }
+            face.family.clone()
+        })
+        .collect()
+}
+
+/// Lists the font files in the directory and its subdirectories.
+///
+/// Symbolic links to directories are not followed, so that link cycles do not lead to endless recursion.
+fn files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_type().is_ok_and(|ty| ty.is_dir()) {
+            files.extend(self::files(&path));
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,70 @@
+use super::*;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/test/fonts");
+
+fn face(family: &str, files: &[&str]) -> FontFace {
+    FontFace {
+        family: family.into(),
+        files: files.iter().map(|&file| file.into()).collect(),
+        fallback: None,
+        subset: None,
+    }
+}
+
+#[test]
+fn test_discover_fixture_fonts() {
+    let fonts = discover(&[FIXTURES]);
+
+    let face = fonts
+        .iter()
+        .find(|face| face.family == "Termframe Test")
+        .unwrap();
+    assert_eq!(face.files.len(), 1);
+    assert!(face.files[0].ends_with("metrics.ttf"), "{:?}", face.files);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_discover_symlink_cycle() {
+    let dir = std::env::temp_dir().join(format!("termframe-fonts-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::copy(format!("{FIXTURES}/metrics.ttf"), dir.join("metrics.ttf")).unwrap();
+    std::os::unix::fs::symlink(&dir, dir.join("loop")).unwrap();
+
+    let fonts = discover(&[&dir]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(fonts.len(), 1);
+    assert_eq!(fonts[0].files.len(), 1);
+}
+
+#[test]
+fn test_discover_missing_dir() {
+    assert!(discover(&["/nonexistent/fonts"]).is_empty());
+}
+
+#[test]
+fn test_merge() {
+    let discovered = discover(&[FIXTURES]);
+    let mut fonts = vec![face("Configured", &["configured.ttf"])];
+
+    let families = merge(
+        vec![
+            "termframe test".into(),
+            "Configured".into(),
+            "Unknown".into(),
+        ],
+        &mut fonts,
+        &discovered,
+    );
+
+    assert_eq!(families, ["Termframe Test", "Configured", "Unknown"]);
+    assert_eq!(fonts.len(), 2);
+    assert_eq!(fonts[1].family, "Termframe Test");
+
+    // configured faces take precedence over the discovered ones
+    let mut fonts = vec![face("Termframe Test", &["configured.ttf"])];
+    let families = merge(vec!["Termframe Test".into()], &mut fonts, &discovered);
+    assert_eq!(families, ["Termframe Test"]);
+    assert_eq!(fonts, [face("Termframe Test", &["configured.ttf"])]);
+}
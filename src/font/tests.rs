@@ -63,3 +63,114 @@ fn mock_font_data() -> Vec<u8> {
     // This is not a real font, just a placeholder for testing
     vec![0, 1, 2, 3, 4, 5]
 }
+
+#[test]
+fn test_describe_font_file() {
+    use crate::font::{FontFile, describe};
+
+    // Missing file
+    let file = "/nonexistent/fonts/font.ttf";
+    let description = describe(file, &FontFile::load_file(file.into()));
+    assert!(description.starts_with("/nonexistent/fonts/font.ttf "));
+    assert!(description.contains("status=error"));
+
+    // Loaded file with broken font data
+    let loaded = FontFile::load_bytes(b"OTTO\x00\x00", Location::from("/fonts/broken.otf"));
+    let description = describe("/fonts/broken.otf", &loaded);
+    assert!(description.starts_with("/fonts/broken.otf format=opentype "));
+    assert!(description.contains("status=error"));
+
+    // Loaded file with valid font data
+    let loaded = FontFile::load_bytes(
+        include_bytes!("../../assets/test/fonts/metrics.ttf"),
+        Location::from("/fonts/metrics.ttf"),
+    );
+    let description = describe("/fonts/metrics.ttf", &loaded);
+    assert_eq!(
+        description,
+        format!(
+            "/fonts/metrics.ttf format=truetype weight={weight} style=normal status=ok",
+            weight = loaded.as_ref().unwrap().font().unwrap().weight()
+        )
+    );
+}
+
+#[test]
+fn test_font_file_metrics() {
+    use crate::{font::FontFile, render::FontMetrics};
+
+    let file = FontFile::load_bytes(
+        include_bytes!("../../assets/test/fonts/metrics.ttf"),
+        Location::from("metrics.ttf"),
+    )
+    .unwrap();
+
+    assert_eq!(file.font().unwrap().name(), Some("Termframe Test"));
+    assert_eq!(file.family().unwrap().as_deref(), Some("Termframe Test"));
+    assert_eq!(
+        file.metrics().unwrap(),
+        FontMetrics {
+            width: 0.6,
+            ascender: 0.8,
+            descender: -0.2,
+        }
+    );
+}
+
+#[test]
+fn test_woff2_subset_is_smaller() {
+    use crate::{
+        font::{FontFile, woff2},
+        fontformat::FontFormat,
+    };
+
+    let file = FontFile::load_bytes(
+        include_bytes!("../../assets/test/fonts/metrics.ttf"),
+        Location::from("metrics.ttf"),
+    )
+    .unwrap();
+    let ttf = file.font().unwrap().subset("abc".chars()).unwrap();
+    let compressed = woff2(&ttf).unwrap();
+
+    assert!(
+        compressed.len() < ttf.len(),
+        "{} >= {}",
+        compressed.len(),
+        ttf.len()
+    );
+
+    let file = FontFile::load_bytes(&compressed, Location::from("metrics.woff2")).unwrap();
+    assert_eq!(file.format(), Some(FontFormat::Woff2));
+}
+
+#[test]
+fn test_location_is_remote() {
+    assert!(Location::from("https://example.com/fonts/font.ttf").is_remote());
+    assert!(!Location::from("file:///fonts/font.ttf").is_remote());
+    assert!(!Location::from("/fonts/font.ttf").is_remote());
+}
+
+#[test]
+fn test_load_cached_font() {
+    use crate::font::{FontFile, Unavailable};
+
+    let dir = std::env::temp_dir().join(format!("termframe-font-cache-{}", std::process::id()));
+    std::fs::remove_dir_all(&dir).ok();
+    let url = url::Url::parse("https://fonts.invalid/metrics.ttf").unwrap();
+
+    // Not downloaded before, so it is skipped without a network request.
+    let err = FontFile::load_cached(url.clone(), &dir).err().unwrap();
+    assert!(err.is::<Unavailable>(), "{err:#}");
+
+    cacache::write_sync(
+        &dir,
+        url.as_str(),
+        include_bytes!("../../assets/test/fonts/metrics.ttf"),
+    )
+    .unwrap();
+    let file = FontFile::load_cached(url, &dir).unwrap();
+    assert_eq!(file.font().unwrap().name(), Some("Termframe Test"));
+    assert_eq!(file.family().unwrap().as_deref(), Some("Termframe Test"));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
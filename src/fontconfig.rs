@@ -0,0 +1,149 @@
+// std imports
+use std::str::FromStr;
+
+// third-party imports
+use anyhow::{anyhow, bail};
+
+// local imports
+use crate::config::{FontFace, Fonts};
+
+pub type Result<T> = anyhow::Result<T>;
+
+/// A fontconfig-style font pattern, e.g. `monospace:weight=bold`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    pub family: String,
+    pub properties: Vec<(String, String)>,
+}
+
+impl Pattern {
+    /// Checks whether the given font family value should be treated as a pattern.
+    pub fn is_pattern(value: &str) -> bool {
+        value.contains(':')
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.split(':');
+        let family = parts.next().unwrap_or_default().trim().to_owned();
+
+        let properties = parts
+            .filter(|part| !part.trim().is_empty())
+            .map(|part| match part.split_once('=') {
+                Some((key, value)) => Ok((key.trim().to_owned(), value.trim().to_owned())),
+                None => Err(anyhow!("invalid font pattern property {part:?} in {s:?}")),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if family.is_empty() && properties.is_empty() {
+            bail!("empty font pattern {s:?}");
+        }
+
+        Ok(Self { family, properties })
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.family)?;
+        for (key, value) in &self.properties {
+            write!(f, ":{key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A font matched by a [`Resolver`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub family: String,
+    pub files: Vec<String>,
+}
+
+/// Resolves font patterns to concrete font files.
+pub trait Resolver {
+    fn resolve(&self, pattern: &Pattern) -> Result<Match>;
+}
+
+/// Resolves font patterns using the system fontconfig installation.
+pub struct System;
+
+impl Resolver for System {
+    #[cfg(feature = "fontconfig")]
+    fn resolve(&self, pattern: &Pattern) -> Result<Match> {
+        let output = std::process::Command::new("fc-match")
+            .arg("--format=%{family[0]}\\n%{file}\\n")
+            .arg(pattern.to_string())
+            .output()
+            .map_err(|e| anyhow!("failed to run fc-match: {e}"))?;
+
+        if !output.status.success() {
+            bail!(
+                "fc-match failed for pattern {pattern:?}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let output = String::from_utf8(output.stdout)?;
+        let mut lines = output.lines();
+        match (lines.next(), lines.next()) {
+            (Some(family), Some(file)) if !family.is_empty() && !file.is_empty() => Ok(Match {
+                family: family.to_owned(),
+                files: vec![file.to_owned()],
+            }),
+            _ => bail!("no font found for pattern {:?}", pattern.to_string()),
+        }
+    }
+
+    #[cfg(not(feature = "fontconfig"))]
+    fn resolve(&self, pattern: &Pattern) -> Result<Match> {
+        bail!(
+            "cannot resolve font pattern {:?}: termframe is built without fontconfig support",
+            pattern.to_string()
+        )
+    }
+}
+
+/// Replaces font patterns in the list of families with the names of the matched fonts.
+///
+/// Faces for the matched fonts are added to `fonts` unless a face with the same family
+/// is already configured. Plain family names are kept as is.
+pub fn resolve_families(
+    families: Vec<String>,
+    fonts: &mut Fonts,
+    resolver: &dyn Resolver,
+) -> Result<Vec<String>> {
+    families
+        .into_iter()
+        .map(|family| {
+            if !Pattern::is_pattern(&family) {
+                return Ok(family);
+            }
+
+            let pattern: Pattern = family.parse()?;
+            let matched = resolver.resolve(&pattern)?;
+            log::debug!(
+                "font pattern {family:?} resolved to family {:?} files {:?}",
+                matched.family,
+                matched.files
+            );
+
+            if !fonts.iter().any(|face| face.family == matched.family) {
+                fonts.push(FontFace {
+                    family: matched.family.clone(),
+                    files: matched.files,
+                    fallback: None,
+                    subset: None,
+                });
+            }
+
+            Ok(matched.family)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,48 @@
+use super::*;
+
+struct Stub;
+
+impl Resolver for Stub {
+    fn resolve(&self, pattern: &Pattern) -> Result<Match> {
+        assert_eq!(pattern.family, "monospace");
+        Ok(Match {
+            family: "DejaVu Sans Mono".into(),
+            files: vec!["/usr/share/fonts/DejaVuSansMono-Bold.ttf".into()],
+        })
+    }
+}
+
+#[test]
+fn test_parse_pattern() {
+    let pattern: Pattern = "monospace:weight=bold:slant=italic".parse().unwrap();
+
+    assert_eq!(pattern.family, "monospace");
+    assert_eq!(
+        pattern.properties,
+        vec![
+            ("weight".to_string(), "bold".to_string()),
+            ("slant".to_string(), "italic".to_string()),
+        ]
+    );
+    assert_eq!(pattern.to_string(), "monospace:weight=bold:slant=italic");
+    assert!("monospace:bold".parse::<Pattern>().is_err());
+}
+
+#[test]
+fn test_resolve_families() {
+    let mut fonts = Fonts::new();
+    let families = vec![
+        "JetBrains Mono".to_string(),
+        "monospace:weight=bold".to_string(),
+    ];
+
+    let families = resolve_families(families, &mut fonts, &Stub).unwrap();
+
+    assert_eq!(families, vec!["JetBrains Mono", "DejaVu Sans Mono"]);
+    assert_eq!(fonts.len(), 1);
+    assert_eq!(fonts[0].family, "DejaVu Sans Mono");
+    assert_eq!(
+        fonts[0].files,
+        vec!["/usr/share/fonts/DejaVuSansMono-Bold.ttf"]
+    );
+}
@@ -5,24 +5,32 @@ use csscolorparser::Color;
 use termwiz::color::SrgbaTuple;
 
 // Public exports
+pub mod api;
 pub mod appdirs;
+pub mod capture;
 pub mod cli;
 pub mod command;
 pub mod config;
 pub mod error;
 pub mod font;
+pub mod fontconfig;
 pub mod fontformat;
 pub mod help;
+pub mod preview;
 pub mod render;
 pub mod syntax;
 pub mod term;
 pub mod theme;
+pub mod title;
 pub mod ureqmw;
 pub mod xerr;
 
 // Re-export key types needed for tests
 pub use config::Source;
 
+// Re-export the library entry point
+pub use api::{RenderConfig, render_bytes};
+
 /// Trait for converting between types
 pub trait Convert<T> {
     fn convert(&self) -> T;
@@ -2,14 +2,15 @@
 use std::{
     borrow::Cow,
     collections::HashMap,
-    io::{self, IsTerminal, stdout},
+    io::{self, IsTerminal, Write, stdout},
+    path::PathBuf,
     process,
     rc::Rc,
+    time::Duration,
 };
 
 // third-party imports
 use anyhow::Context;
-use base64::prelude::*;
 use bitvec::prelude::*;
 use clap::{CommandFactory, Parser};
 use csscolorparser::Color;
@@ -21,13 +22,21 @@ use rayon::prelude::*;
 
 // local imports
 use config::{
-    Load, Patch, Settings, app_dirs, load::ItemInfo, theme::ThemeConfig,
+    FontFamilyOption, Load, Patch, Settings, app_dirs, load::ItemInfo, theme::ThemeConfig,
     winstyle::WindowStyleConfig,
 };
 use error::{AppInfoProvider, Result, UsageRequest, UsageResponse};
 use font::FontFile;
-use fontformat::FontFormat;
-use render::{CharSet, CharSetFn, svg::SvgRenderer};
+use render::{
+    CharSet, CharSetFn,
+    ansi::AnsiRenderer,
+    cast::CastRenderer,
+    html::HtmlRenderer,
+    png::PngRenderer,
+    svg::{Frame, SvgRenderer},
+    text::TextRenderer,
+    webp::WebpRenderer,
+};
 use term::Terminal;
 use termframe::syntax;
 use termwiz::color::SrgbaTuple;
@@ -35,24 +44,28 @@ use theme::{AdaptiveTheme, Theme};
 
 // private modules
 mod appdirs;
+mod capture;
 mod cli;
 mod command;
 mod config;
 mod error;
 mod font;
+mod fontconfig;
 mod fontformat;
 mod help;
+mod preview;
 mod render;
 mod term;
 mod theme;
+mod title;
 mod ureqmw;
 mod xerr;
 
 /// Entry point of the application
 fn main() {
-    let app = App::new();
+    let result = bootstrap().and_then(|settings| App::new(&settings).run(settings));
 
-    if let Err(err) = app.run() {
+    if let Err(err) = result {
         err.log(&AppInfo);
         process::exit(1);
     }
@@ -74,29 +87,31 @@ impl AppInfoProvider for AppInfo {
 /// Represents the application
 struct App {
     ua: Option<ureq::Agent>,
+    cache_dir: Option<PathBuf>,
 }
 
 impl App {
     /// Creates a new instance of the application
-    fn new() -> Self {
+    fn new(settings: &Settings) -> Self {
         let mut ua = None;
+        let mut cache_dir = None;
         if let Some(dirs) = app_dirs() {
+            let ttl = Duration::from_secs(settings.font.cache_ttl);
             ua = Some(
                 ureq::Agent::config_builder()
-                    .middleware(ureqmw::cache::new(&dirs.cache_dir))
+                    .middleware(ureqmw::cache::new(&dirs.cache_dir).with_ttl(ttl))
                     .tls_config(tls_config())
                     .build()
                     .into(),
             );
+            cache_dir = Some(dirs.cache_dir);
         }
 
-        Self { ua }
+        Self { ua, cache_dir }
     }
 
     /// Runs the application
-    fn run(&self) -> Result<()> {
-        let settings = bootstrap()?;
-
+    fn run(&self, settings: Settings) -> Result<()> {
         let opt = cli::Opt::parse_from(wild::args());
 
         if let Some(verbosity) = opt.help {
@@ -118,17 +133,53 @@ impl App {
         if let Some(tags) = opt.list_themes {
             return list_themes(tags);
         }
+        if let Some(source) = &opt.import_theme {
+            return import_theme(source);
+        }
+        if let Some(path) = &opt.validate_theme {
+            return validate_theme(path);
+        }
         if let Some(tags) = opt.list_syntax_themes {
             return list_syntax_themes(tags);
         }
         if opt.list_window_styles {
             return list_window_styles();
         }
-        if opt.list_fonts {
-            return list_fonts(&settings);
+        if let Some(verbosity) = opt.list_fonts {
+            return match verbosity {
+                cli::ListVerbosity::Short => list_fonts(&settings),
+                cli::ListVerbosity::Long => self.list_fonts_verbose(&settings),
+            };
         }
 
-        let settings = Rc::new(opt.patch(settings));
+        let mut settings = opt.patch(settings);
+        if let Some(name) = &opt.export_theme {
+            return export_theme(name, settings.mode.into(), opt.output.as_deref());
+        }
+        let format = opt.output_format();
+        if opt.quality.is_some() && format != cli::OutputFormat::Webp {
+            log::warn!("--quality only applies to WebP output");
+        }
+        let families = settings.font.family.resolve();
+        if families
+            .iter()
+            .any(|family| fontconfig::Pattern::is_pattern(family))
+        {
+            let families =
+                fontconfig::resolve_families(families, &mut settings.fonts, &fontconfig::System)?;
+            settings.font.family = FontFamilyOption::Multiple(families);
+        }
+        if opt
+            .font_family
+            .iter()
+            .any(|family| !settings.fonts.iter().any(|face| face.family == *family))
+        {
+            let discovered = font::system::discover(&font::system::font_dirs());
+            let families = settings.font.family.resolve();
+            let families = font::system::merge(families, &mut settings.fonts, &discovered);
+            settings.font.family = FontFamilyOption::Multiple(families);
+        }
+        let settings = Rc::new(settings);
 
         let mode = settings.mode.into();
 
@@ -142,30 +193,67 @@ impl App {
         };
         let window = WindowStyleConfig::load_hybrid(&settings.window.style)?.window;
 
-        let mut terminal = Terminal::new(term::Options {
-            cols: Some(
-                settings
-                    .terminal
-                    .width
-                    .initial_or(opt.width.min().or_else(|| opt.width.max()).unwrap_or(240)),
-            ),
-            rows: Some(
-                settings.terminal.height.initial_or(
-                    opt.height
-                        .min()
-                        .or_else(|| opt.height.max())
-                        .unwrap_or(1024),
-                ),
+        let input = opt
+            .input
+            .as_deref()
+            .map(|path| {
+                std::fs::read(path).with_context(|| format!("failed to read input file {path}"))
+            })
+            .transpose()?;
+        let recording = match (&input, opt.input.as_deref()) {
+            (Some(data), Some(path)) if term::cast::Recording::detect(data) => Some(
+                term::cast::Recording::read(data.as_slice())
+                    .with_context(|| format!("failed to read recording {path}"))?,
             ),
-            background: Some(theme.bg.convert()),
-            foreground: Some(theme.fg.convert()),
-            env: settings.env.clone(),
-        });
+            _ => None,
+        };
+        let recorded = recording.as_ref().map(|recording| &recording.header);
+
+        let new_terminal = || {
+            let mut options = capture::terminal_options(
+                &settings,
+                &theme,
+                recorded.map(|header| (header.width, header.height)),
+            );
+            if let Some(header) = recorded {
+                options.env = header.env.clone().into_iter().chain(options.env).collect();
+            }
+            Terminal::new(options)
+        };
+        let mut terminal = new_terminal();
 
         let timeout = Some(std::time::Duration::from_secs(opt.timeout));
+        let mut frames = Vec::new();
+        let mut status = None;
+
+        if opt.theme_preview {
+            terminal.feed(io::Cursor::new(preview::to_terminal()), io::sink())?;
+        } else if let Some(recording) = &recording {
+            if opt.animate {
+                for event in &recording.events {
+                    terminal.advance(event.data.as_bytes());
+                    frames.push(Frame {
+                        time: event.time,
+                        lines: terminal
+                            .surface()
+                            .screen_lines()
+                            .into_iter()
+                            .map(Cow::into_owned)
+                            .collect(),
+                    });
+                }
+            } else {
+                terminal.feed(io::Cursor::new(recording.output()), io::sink())?;
+            }
+        } else if let Some(data) = &input {
+            if opt.animate {
+                log::warn!("animation requires a recorded session, rendering the final screen");
+            }
+            terminal.feed(data.as_slice(), io::sink())?;
+        } else if let Some(command) = &opt.command {
+            let script = opt.shell.then(|| command::to_script(command, &opt.args));
 
-        if let Some(command) = &opt.command {
-            if opt.show_command {
+            if opt.show_command.enabled() {
                 let theme: Option<syntax::Theme> = settings
                     .syntax
                     .theme
@@ -184,13 +272,38 @@ impl App {
                 if let Some(theme) = &theme {
                     log::debug!("use syntax theme {:?}", theme.display_name());
                 }
-                let command = command::to_terminal(&opt.command_prompt, command, &opt.args, theme);
+                let prompt = command::Prompt {
+                    text: opt.show_command.prompt().unwrap_or(&opt.command_prompt),
+                    style: &settings.command.prompt_style,
+                };
+                let command = match &script {
+                    Some(script) => command::script_to_terminal(prompt, script, theme),
+                    None => command::to_terminal(prompt, command, &opt.args, theme),
+                };
                 terminal.feed(io::Cursor::new(command), io::sink())?;
             }
 
-            let mut command = CommandBuilder::new(command);
-            command.args(&opt.args);
-            terminal.run(command, timeout)?;
+            let mut command = match &script {
+                Some(script) => {
+                    let mut command =
+                        CommandBuilder::new(command::shell(settings.command.shell.as_deref()));
+                    command.args(["-c", script]);
+                    command
+                }
+                None => {
+                    let mut command = CommandBuilder::new(command);
+                    command.args(&opt.args);
+                    command
+                }
+            };
+            if let Some(dir) = &opt.cwd {
+                command.cwd(dir);
+            }
+            status = Some(if opt.hold {
+                terminal.run_and_hold(command, timeout)?
+            } else {
+                terminal.run(command, timeout)?
+            });
         } else {
             if io::stdin().is_terminal() {
                 return Ok(cli::Opt::command().print_help()?);
@@ -199,73 +312,178 @@ impl App {
             terminal.feed(io::BufReader::new(io::stdin()), io::sink())?;
         }
 
-        let mut resized = false;
-        let width = if matches!(opt.width.current, cli::Dimension::Fixed(_)) {
-            terminal.surface().dimensions().0 as u16
-        } else {
-            let width = terminal.recommended_width();
-            log::info!("recommended terminal width: {width}");
-            opt.width.fit(width)
-        };
-        if terminal.surface().dimensions().0 as u16 != width {
-            terminal.set_width(width);
-            resized = true;
+        if opt.fail_on_empty && terminal.is_blank() {
+            return Err(anyhow::anyhow!("captured output is empty").into());
         }
-        let height = if matches!(opt.height.current, cli::Dimension::Fixed(_)) {
-            terminal.surface().dimensions().1 as u16
-        } else {
-            let height = terminal.recommended_height();
-            log::info!("recommended terminal height: {height}");
-            opt.height.fit(height)
-        };
-        if terminal.surface().dimensions().1 as u16 != height {
-            resized = true;
+        if let Some(placeholder) = &opt.placeholder
+            && terminal.fill_if_blank(placeholder)
+        {
+            log::info!("captured output is empty, showing placeholder");
+        }
+
+        let size = terminal.surface().dimensions();
+        if opt.no_wrap_render {
+            let width = terminal.unwrap_lines();
+            log::info!("unwrapped terminal width: {width}");
         }
-        terminal.set_height(height);
-        if resized {
+        // Animation frames are captured at the recorded size, so it is kept as is
+        capture::fit_terminal(
+            &mut terminal,
+            &settings,
+            opt.no_wrap_render || opt.animate,
+            opt.animate,
+        );
+        let (width, height) = terminal.surface().dimensions();
+        if (width, height) != size {
             log::info!("resized terminal to {width}x{height}");
         }
 
-        let content = terminal.surface().screen_chars_to_string();
+        let mut highlights = opt.highlights;
+        if let Some(path) = &opt.diff_against {
+            let mut baseline = new_terminal();
+            baseline.feed(io::BufReader::new(std::fs::File::open(path)?), io::sink())?;
+            baseline.set_width(width as u16);
+            baseline.set_height(height as u16);
+            highlights.extend(render::diff_highlights(
+                terminal.surface(),
+                baseline.surface(),
+                opt.diff_color,
+            ));
+        }
+
+        let cropped = opt.crop.map(|crop| crop.apply(terminal.surface()));
+        let surface = cropped.as_ref().unwrap_or(terminal.surface());
+
+        let mut content = surface.screen_chars_to_string();
+        // Animation frames may show characters that are gone from the final screen.
+        for line in frames.iter().flat_map(|frame| &frame.lines) {
+            content.push_str(&line.as_str());
+        }
+        if settings.rendering.line_numbers.enabled {
+            // The gutter is drawn with the same fonts, any digits may show up in the line numbers.
+            content.push_str("0123456789");
+        }
 
-        let options = render::Options {
-            settings: settings.clone(),
-            font: self.make_font_options(&settings, content.chars().filter(|c| *c != '\n'))?,
+        let title = opt
+            .title
+            .or_else(|| {
+                opt.theme_preview
+                    .then(|| format!("theme: {}", settings.theme.resolve(mode)))
+            })
+            .or_else(|| {
+                if opt.shell {
+                    opt.command
+                        .map(|command| command::to_script(command, &opt.args))
+                } else {
+                    command::to_title(opt.command, &opt.args)
+                }
+            })
+            .or_else(|| recorded.and_then(|header| header.title.clone()))
+            .or_else(|| terminal.title().map(|title| title.to_owned()))
+            .or_else(|| {
+                let template = settings.window.title.as_deref()?;
+                let vars = title::Vars::from_env(&settings.env, terminal.cwd());
+                Some(title::expand(template, &vars))
+            });
+        let mut options = capture::render_options(
+            settings.clone(),
+            self.make_font_options(
+                &settings,
+                content.chars().filter(|c| *c != '\n'),
+                // The rasterizer cannot read WOFF2 fonts.
+                !format.is_raster(),
+            )?,
             theme,
             window,
-            title: opt
-                .title
-                .or_else(|| command::to_title(opt.command, &opt.args)),
+            title,
             mode,
-            background: Some(terminal.background().convert()),
-            foreground: Some(terminal.foreground().convert()),
-        };
+            &terminal,
+        );
+        options.highlights = highlights;
+        if let Some(crop) = opt.crop {
+            let (columns, rows) = crop.area(terminal.surface().dimensions());
+            log::info!("crop columns {columns:?} and rows {rows:?}");
+            options.highlights.retain_mut(|highlight| {
+                let span = highlight.columns.clone().unwrap_or(columns.clone());
+                let span = span.start.max(columns.start)..span.end.min(columns.end);
+                if !rows.contains(&highlight.row) || span.is_empty() {
+                    return false;
+                }
+                highlight.row -= rows.start;
+                highlight.columns = Some(span.start - columns.start..span.end - columns.start);
+                true
+            });
+            crop.apply_to_images(terminal.surface().dimensions(), &mut options.images);
+            options.scrollback = 0;
+        }
+        if settings.window.enabled
+            && let Some(title) = &options.title
+        {
+            options.title_advances =
+                capture::measure_title(&settings, &options.window, title, |file| {
+                    self.load_font(&settings, file)
+                });
+        }
 
-        let output = opt
-            .output
-            .as_deref()
-            .and_then(|s| (!matches!(s, "-" | "")).then_some(s));
+        let mut output = open_output(opt.output.as_deref())?;
 
-        let mut output: Box<dyn io::Write> = if let Some(output) = output {
-            Box::new(std::fs::File::create(output)?)
-        } else {
-            Box::new(stdout())
-        };
+        if let Some(path) = &opt.export_cast {
+            let mut file = std::fs::File::create(path)?;
+            CastRenderer::new(options.title.clone()).render(surface, &mut file)?;
+        }
 
-        let renderer = SvgRenderer::new(options);
-        renderer.render(terminal.surface(), &mut output)?;
+        let renderer = SvgRenderer::new(options.clone());
+        if let Some(path) = &opt.dump_shapes {
+            let mut target: Box<dyn io::Write> = if path == "-" {
+                Box::new(io::stderr())
+            } else {
+                Box::new(std::fs::File::create(path)?)
+            };
+            renderer.dump_shapes(surface, &mut target)?;
+        }
+        if !frames.is_empty() && !matches!(format, cli::OutputFormat::Svg | cli::OutputFormat::Svgz)
+        {
+            log::warn!("animation is only supported for SVG output, rendering the final screen");
+        }
+        match format {
+            cli::OutputFormat::Svg => renderer.render_animation(surface, &frames, &mut output)?,
+            cli::OutputFormat::Svgz => {
+                renderer.render_animation_compressed(surface, &frames, &mut output)?
+            }
+            cli::OutputFormat::Png => {
+                PngRenderer::new(options, opt.scale).render(surface, &mut output)?
+            }
+            cli::OutputFormat::Webp => {
+                WebpRenderer::new(options, opt.scale, opt.quality).render(surface, &mut output)?
+            }
+            cli::OutputFormat::Html => HtmlRenderer::new(options).render(surface, &mut output)?,
+            cli::OutputFormat::Text => TextRenderer.render(surface, &mut output)?,
+            cli::OutputFormat::Ansi => AnsiRenderer.render(surface, &mut output)?,
+        }
+
+        if opt.propagate_exit
+            && let Some(status) = status.filter(|status| !status.success())
+        {
+            output.flush()?;
+            log::debug!("propagate command exit code {}", status.exit_code());
+            process::exit(status.exit_code() as i32);
+        }
 
         Ok(())
     }
 
-    /// Creates font options based on the settings and characters
-    fn make_font_options<C>(&self, settings: &Settings, chars: C) -> Result<render::FontOptions>
+    /// Creates font options based on the settings and characters,
+    /// subset fonts are compressed to WOFF2 if `woff2` is set
+    fn make_font_options<C>(
+        &self,
+        settings: &Settings,
+        chars: C,
+        woff2: bool,
+    ) -> Result<render::FontOptions>
     where
         C: IntoIterator<Item = char>,
     {
-        let mut width: Option<f32> = None;
-        let mut ascender: f32 = 0.0;
-        let mut descender: f32 = 0.0;
+        let mut metrics: Option<render::FontMetrics> = None;
 
         let families = settings.font.family.resolve();
 
@@ -273,32 +491,30 @@ impl App {
             .fonts
             .par_iter()
             .filter(|font| families.contains(&font.family))
-            .flat_map(|font| {
-                font.files
-                    .par_iter()
-                    .rev()
-                    .map(move |file| (&font.family, file))
-            })
-            .map(|(family, file)| {
-                self.load_font(file)
-                    .with_context(|| format!("failed to load font {file}"))
-                    .map(|file| (family, file))
+            .flat_map(|font| font.files.par_iter().rev().map(move |file| (font, file)))
+            .filter_map(|(face, file)| match self.load_font(settings, file) {
+                Ok(loaded) => Some(Ok((face, loaded))),
+                Err(err) if is_unavailable(&err) => {
+                    log::warn!("skip font {file}: {err}");
+                    None
+                }
+                Err(err) => Some(Err(err).with_context(|| format!("failed to load font {file}"))),
             })
             .collect::<Result<Vec<_>, _>>()?;
 
-        files.sort_by_key(|(family, _)| {
+        files.sort_by_key(|(face, _)| {
             families
                 .iter()
-                .position(|f| f == *family)
+                .position(|f| *f == face.family)
                 .map(|i| -(i as i64))
         });
 
         let mut fonts = Vec::new();
 
-        for (family, file) in &files {
+        for (face, file) in &files {
             let font = file.font().unwrap();
             let url = file.location().url().unwrap().to_string();
-            fonts.push((url, family, font));
+            fonts.push((url, &face.family, font));
         }
 
         let mut used: HashMap<char, BitVec> = HashMap::new();
@@ -324,12 +540,10 @@ impl App {
 
         for (i, (url, family, font)) in fonts.iter_mut().enumerate().rev() {
             let mut metrics_match = true;
-            if let Some(width) = &mut width {
-                metrics_match = *width == font.width();
+            if let Some(metrics) = &metrics {
+                metrics_match = metrics.width == font.width();
             } else {
-                width = Some(font.width());
-                ascender = font.ascender();
-                descender = font.descender();
+                metrics = Some(font.metrics());
             };
 
             let used = used.clone();
@@ -339,7 +553,14 @@ impl App {
                     .unwrap_or(false)
             }));
 
-            let face = make_font_face(family, url, font, chars, metrics_match);
+            let face = make_font_face(
+                family,
+                url,
+                font,
+                chars,
+                metrics_match,
+                settings.font.weights.bold,
+            );
 
             log::debug!(
                 "font face #{i:02}: weight={weight:?} style={style:?} url={url:?}",
@@ -361,15 +582,7 @@ impl App {
             );
         }
 
-        let metrics = if let Some(width) = width {
-            render::FontMetrics {
-                width,
-                ascender,
-                descender,
-            }
-        } else {
-            DEFAULT_FONT_METRICS
-        };
+        let metrics = metrics.unwrap_or(DEFAULT_FONT_METRICS);
 
         log::debug!(
             "font metrics: width={width} ascender={ascender} descender={descender}",
@@ -379,32 +592,25 @@ impl App {
         );
 
         log::debug!(
-            "prepare font faces: embed-fonts={e} subset-fonts={s}",
+            "prepare font faces: embed-fonts={e}",
             e = settings.rendering.svg.embed_fonts,
-            s = settings.rendering.svg.subset_fonts,
         );
         if settings.rendering.svg.embed_fonts {
-            for (i, (_, file)) in files.iter().enumerate() {
-                let data = if settings.rendering.svg.subset_fonts {
-                    let chars = used
-                        .iter()
-                        .filter(|x| x.1.get(i).as_deref().copied().unwrap_or(false))
-                        .map(|x| *x.0);
-                    let data = fonts[i].2.subset(chars)?;
-                    faces[i].format = Some(FontFormat::Ttf);
-                    Cow::Owned(data)
-                } else {
-                    Cow::Borrowed(file.data())
-                };
-                log::debug!(
-                    "prepare font face #{i:02} to be embedded: {len} bytes",
-                    len = data.len()
-                );
-                faces[i].url = format!(
-                    "data:{};base64,{}",
-                    file.format().unwrap_or(FontFormat::Ttf).mime(),
-                    BASE64_STANDARD.encode(data)
-                );
+            for (i, (face, file)) in files.iter().enumerate() {
+                let subset = face.should_subset(&settings.rendering.svg);
+                log::debug!("prepare font face #{i:02} to be embedded: subset={subset}");
+                let chars = used
+                    .iter()
+                    .filter(|x| x.1.get(i).as_deref().copied().unwrap_or(false))
+                    .map(|x| *x.0);
+                capture::embed_font_face(
+                    &mut faces[i],
+                    file,
+                    &mut fonts[i].2,
+                    chars,
+                    subset,
+                    woff2,
+                )?;
             }
         }
 
@@ -417,11 +623,35 @@ impl App {
         })
     }
 
-    /// Loads a font file from a given path or URL
-    fn load_font<S: AsRef<str>>(&self, file: S) -> Result<FontFile> {
+    /// Lists configured and system fonts along with their source, files and load status
+    fn list_fonts_verbose(&self, settings: &Settings) -> Result<()> {
+        for (source, fonts) in font_sources(settings) {
+            for font in fonts {
+                println!("{} ({source})", font.family);
+                for file in &font.files {
+                    println!(
+                        "  {}",
+                        font::describe(file, &self.load_font(settings, file))
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads a font file from a given path or URL, in offline mode URLs are only loaded from the cache
+    fn load_font<S: AsRef<str>>(&self, settings: &Settings, file: S) -> Result<FontFile> {
         let file = file.as_ref();
         let location = font::Location::from(file);
 
+        if settings.rendering.offline && location.is_remote() {
+            let url = location.url().unwrap().clone();
+            return match &self.cache_dir {
+                Some(dir) => Ok(FontFile::load_cached(url, dir)?),
+                None => Err(anyhow::Error::from(font::Unavailable(url)).into()),
+            };
+        }
+
         match location {
             font::Location::File(path) => Ok(FontFile::load_file(path)?),
             font::Location::Url(url) => {
@@ -435,6 +665,11 @@ impl App {
     }
 }
 
+/// Checks whether the font could not be loaded because it is not available offline
+fn is_unavailable(err: &error::Error) -> bool {
+    matches!(err, error::Error::Other(err) if err.is::<font::Unavailable>())
+}
+
 /// Prints the manual page
 fn print_man_page() -> Result<()> {
     let man = clap_mangen::Man::new(cli::Opt::command());
@@ -454,14 +689,28 @@ fn list_window_styles() -> Result<()> {
     list_assets(WindowStyleConfig::list()?)
 }
 
-/// Lists available fonts
+/// Lists available fonts grouped by source
 fn list_fonts(settings: &Settings) -> Result<()> {
-    for font in &settings.fonts {
-        println!("{}", font.family);
-    }
+    let mut formatter = help::Formatter::new(stdout());
+
+    formatter.format_grouped_list(
+        font_sources(settings)
+            .into_iter()
+            .map(|(source, fonts)| (source, fonts.into_iter().map(|font| font.family))),
+    )?;
     Ok(())
 }
 
+/// Returns the configured fonts followed by the system fonts not configured explicitly
+fn font_sources(settings: &Settings) -> [(&'static str, Vec<config::FontFace>); 2] {
+    let system = font::system::discover(&font::system::font_dirs())
+        .into_iter()
+        .filter(|face| !settings.fonts.iter().any(|font| font.family == face.family))
+        .collect();
+
+    [("configured", settings.fonts.clone()), ("system", system)]
+}
+
 /// Lists available themes based on the provided tags
 fn list_themes(tags: Option<cli::ThemeTagSet>) -> Result<()> {
     let items = ThemeConfig::list()?;
@@ -488,6 +737,58 @@ fn list_themes(tags: Option<cli::ThemeTagSet>) -> Result<()> {
     Ok(())
 }
 
+/// Imports a color scheme of another terminal emulator as a custom theme
+fn import_theme(source: &theme::import::Source) -> Result<()> {
+    let cfg = source
+        .import()
+        .map_err(|e| anyhow::anyhow!("failed to import theme {source}: {e}"))?;
+
+    let dir = ThemeConfig::dir();
+    let path = dir.join(format!("{}.toml", source.name()));
+    if path.exists() {
+        return Err(anyhow::anyhow!("theme file {} already exists", path.display()).into());
+    }
+
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(&path, cfg.to_toml())?;
+    println!("{}", path.display());
+    Ok(())
+}
+
+/// Validates the theme and reports all problems found
+fn validate_theme(path: &str) -> Result<()> {
+    let cfg = ThemeConfig::load_hybrid(path)?;
+    let mut problems = cfg.validate(path);
+
+    let Some(last) = problems.pop() else {
+        println!("theme {path} is valid");
+        return Ok(());
+    };
+    for problem in problems {
+        error::Error::from(problem).log(&AppInfo);
+    }
+    Err(last.into())
+}
+
+/// Prints the theme resolved for the given mode as a TOML document
+fn export_theme(name: &str, mode: config::mode::Mode, output: Option<&str>) -> Result<()> {
+    let cfg = ThemeConfig::load_hybrid(name)?;
+    let toml = theme::export::export(&cfg, mode).to_toml();
+    open_output(output)?.write_all(toml.as_bytes())?;
+    Ok(())
+}
+
+/// Opens the output file, or stdout if the output is not set or is '-'
+fn open_output(output: Option<&str>) -> Result<Box<dyn io::Write>> {
+    let output = output.and_then(|s| (!matches!(s, "-" | "")).then_some(s));
+
+    Ok(if let Some(output) = output {
+        Box::new(std::fs::File::create(output)?)
+    } else {
+        Box::new(stdout())
+    })
+}
+
 /// Lists available syntax highlighting themes optionally filtered by tags
 fn list_syntax_themes(tags: Option<cli::ThemeTagSet>) -> Result<()> {
     let mut formatter = help::Formatter::new(stdout());
@@ -581,13 +882,15 @@ fn bootstrap() -> Result<Settings> {
     Ok(settings)
 }
 
-/// Creates a font face based on the provided parameters
+/// Creates a font face based on the provided parameters,
+/// bold faces keep their numeric weight if the configured bold weight is numeric
 fn make_font_face(
     family: &str,
     url: &mut String,
     font: &mut font::Font,
     chars: Rc<dyn CharSet>,
     metrics_match: bool,
+    bold: config::FontWeight,
 ) -> render::FontFace {
     if let Some(ff) = font.family()
         && ff != family
@@ -599,7 +902,7 @@ fn make_font_face(
         family: family.to_owned(),
         weight: if let Some((min, max)) = font.weight_axis() {
             render::FontWeight::Variable(f32::from(min) as u16, f32::from(max) as u16)
-        } else if font.bold() {
+        } else if font.bold() && !matches!(bold, config::FontWeight::Fixed(_)) {
             render::FontWeight::Bold
         } else if font.weight() == 400 {
             render::FontWeight::Normal
@@ -617,6 +920,7 @@ fn make_font_face(
         url: url.clone(),
         chars,
         metrics_match,
+        unicode_range: Vec::new(),
     }
 }
 
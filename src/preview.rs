@@ -0,0 +1,71 @@
+/// Names of the basic ANSI colors in palette order.
+const COLOR_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// Width of a single color swatch in columns.
+const SWATCH_WIDTH: usize = 9;
+
+/// Builds a standardized theme preview card for display in the terminal surface.
+///
+/// The card shows the 16 ANSI palette colors as background swatches with their names,
+/// the same colors applied to text, the default foreground and background colors,
+/// and sample text in the supported styles.
+pub fn to_terminal() -> Vec<u8> {
+    let mut output = Vec::new();
+
+    let label = |output: &mut Vec<u8>, text: &str| {
+        output.extend(format!("{text:<8}").as_bytes());
+    };
+
+    // Color names header.
+    label(&mut output, "");
+    for name in COLOR_NAMES {
+        output.extend(format!("{name:<w$}", w = SWATCH_WIDTH).as_bytes());
+    }
+    output.push(b'\n');
+
+    // Background swatches and foreground samples for normal and bright colors.
+    for (title, bg, fg) in [("normal", 40, 30), ("bright", 100, 90)] {
+        label(&mut output, title);
+        for i in 0..COLOR_NAMES.len() {
+            output.extend(format!("\x1b[{}m", bg + i).as_bytes());
+            output.extend(" ".repeat(SWATCH_WIDTH - 1).as_bytes());
+            output.extend(b"\x1b[0m ");
+        }
+        output.push(b'\n');
+
+        label(&mut output, "");
+        for i in 0..COLOR_NAMES.len() {
+            output.extend(format!("\x1b[{}m", fg + i).as_bytes());
+            output.extend(format!("{:<w$}", "Text", w = SWATCH_WIDTH - 1).as_bytes());
+            output.extend(b"\x1b[0m ");
+        }
+        output.push(b'\n');
+    }
+
+    output.push(b'\n');
+
+    // Default foreground and background colors.
+    label(&mut output, "default");
+    output.extend(b"Default foreground on default background\n");
+
+    // Text styles.
+    label(&mut output, "styles");
+    for (sgr, name) in [
+        ("1", "bold"),
+        ("2", "faint"),
+        ("3", "italic"),
+        ("4", "underline"),
+        ("9", "strikethrough"),
+        ("7", "reverse"),
+    ] {
+        output.extend(format!("\x1b[{sgr}m{name}\x1b[0m ").as_bytes());
+    }
+    output.push(b'\n');
+
+    output
+}
+
+#[cfg(test)]
+mod tests;
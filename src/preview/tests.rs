@@ -0,0 +1,57 @@
+use std::collections::BTreeSet;
+use std::io::Cursor;
+
+use termwiz::{cell::Intensity, color::ColorAttribute};
+
+use super::*;
+use crate::term::{Options, Terminal};
+
+fn make_preview_term() -> Terminal {
+    let mut term = Terminal::new(Options {
+        cols: Some(120),
+        rows: Some(12),
+        ..Default::default()
+    });
+    term.feed(Cursor::new(to_terminal()), std::io::sink())
+        .unwrap();
+    term
+}
+
+#[test]
+fn test_preview_contains_all_palette_swatches() {
+    let term = make_preview_term();
+
+    let swatches: BTreeSet<u8> = term
+        .surface()
+        .screen_lines()
+        .iter()
+        .flat_map(|line| {
+            line.visible_cells()
+                .filter_map(|cell| match cell.attrs().background() {
+                    ColorAttribute::PaletteIndex(i) => Some(i),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    assert_eq!(swatches, (0..16).collect());
+}
+
+#[test]
+fn test_preview_contains_styled_text() {
+    let term = make_preview_term();
+    let content = term.surface().screen_chars_to_string();
+
+    for name in COLOR_NAMES {
+        assert!(content.contains(name), "missing color name {name:?}");
+    }
+    assert!(content.contains("Default foreground on default background"));
+    assert!(content.contains("underline"));
+
+    let bold = term.surface().screen_lines().iter().any(|line| {
+        line.visible_cells()
+            .any(|cell| cell.str() == "b" && cell.attrs().intensity() == Intensity::Bold)
+    });
+    assert!(bold);
+}
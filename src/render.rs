@@ -1,20 +1,41 @@
 // std imports
-use std::{collections::HashSet, io, rc::Rc};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, HashSet},
+    io,
+    ops::{Range, RangeInclusive},
+    rc::Rc,
+    str::FromStr,
+};
 
 // third-party imports
 use csscolorparser::Color;
-use termwiz::surface::Surface;
+use termwiz::{
+    cell::CellAttributes,
+    surface::{Change, CursorVisibility, Line, Position, Surface},
+};
 
 // local imports
 use crate::{
     config::{Padding, Settings, mode::Mode, winstyle::Window},
     fontformat::FontFormat,
+    term::sixel,
     theme::Theme,
 };
 
 // modules
+pub mod ansi;
+pub mod cast;
+pub mod css;
+pub mod html;
+pub mod png;
+mod quantize;
+#[cfg(any(feature = "png", feature = "webp"))]
+mod raster;
 pub mod svg;
+pub mod text;
 mod tracing;
+pub mod webp;
 
 // re-exports
 pub type Result<T> = anyhow::Result<T>;
@@ -37,6 +58,18 @@ pub struct Options {
     pub mode: Mode,
     pub background: Option<Color>,
     pub foreground: Option<Color>,
+    /// Cursor color set by the program, overriding the theme's cursor color.
+    pub cursor: Option<Color>,
+    /// Number of transcript lines scrolled out above the rendered surface.
+    pub scrollback: usize,
+    /// Translucent highlights drawn over parts of the screen.
+    pub highlights: Vec<Highlight>,
+    /// Raster images drawn over the screen cells.
+    pub images: Vec<Image>,
+    /// Palette colors set by the program, taking precedence over the theme palette.
+    pub palette: BTreeMap<u8, Color>,
+    /// Advance widths of the title characters in em, measured with the title font.
+    pub title_advances: HashMap<char, f32>,
 }
 
 impl Options {
@@ -49,6 +82,323 @@ impl Options {
     pub fn fg(&self) -> &Color {
         self.foreground.as_ref().unwrap_or(&self.theme.fg)
     }
+
+    /// Get the cursor color, falling back to the theme's cursor color if not set.
+    pub fn cursor(&self) -> Option<&Color> {
+        self.cursor.as_ref().or(self.theme.cursor.as_ref())
+    }
+}
+
+#[cfg(test)]
+impl Options {
+    /// Sample options shared by the renderer tests.
+    pub fn sample() -> Self {
+        Options {
+            settings: Default::default(),
+            font: FontOptions {
+                family: vec!["Monospace".to_string()],
+                size: 12.0,
+                metrics: FontMetrics {
+                    width: 0.6,
+                    ascender: 0.8,
+                    descender: -0.2,
+                },
+                faces: vec![],
+                weights: FontWeights::default(),
+            },
+            theme: Rc::new(Theme {
+                bg: Color::from_rgba8(255, 255, 255, 255),
+                fg: Color::from_rgba8(0, 0, 0, 255),
+                bright_fg: None,
+                cursor: None,
+                palette: Default::default(),
+            }),
+            window: crate::config::winstyle::WindowStyleConfig::default().window,
+            title: Some("Sample Title".to_string()),
+            mode: Mode::Light,
+            background: None,
+            foreground: None,
+            cursor: None,
+            scrollback: 0,
+            highlights: Vec::new(),
+            images: Vec::new(),
+            palette: Default::default(),
+            title_advances: Default::default(),
+        }
+    }
+}
+
+/// A raster image placed over the screen cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    /// Zero-based screen row of the top edge, negative if the image is partially scrolled or cropped out.
+    pub row: isize,
+    /// Zero-based column of the left edge, negative if the image is partially cropped out.
+    pub column: isize,
+    /// Width and height in cells.
+    pub size: (f32, f32),
+    /// Image data in PNG format.
+    pub png: Vec<u8>,
+}
+
+impl From<&sixel::Placement> for Image {
+    fn from(placement: &sixel::Placement) -> Self {
+        let image = &placement.image;
+        Self {
+            row: placement.row,
+            column: placement.column as isize,
+            size: (
+                image.width as f32 / sixel::CELL_WIDTH as f32,
+                image.height as f32 / sixel::CELL_HEIGHT as f32,
+            ),
+            png: image.to_png(),
+        }
+    }
+}
+
+/// A translucent rectangle emphasizing a part of the screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Highlight {
+    /// Zero-based screen row.
+    pub row: usize,
+    /// Half-open range of columns, the whole row if not set.
+    pub columns: Option<Range<usize>>,
+    /// Fill color, a default translucent color is used if not set.
+    pub color: Option<Color>,
+}
+
+impl FromStr for Highlight {
+    type Err = String;
+
+    /// Parses a highlight in `ROW[:START..END][=COLOR]` format.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (area, color) = match s.split_once('=') {
+            Some((area, color)) => (area, Some(color)),
+            None => (s, None),
+        };
+        let (row, columns) = match area.split_once(':') {
+            Some((row, columns)) => (row, Some(columns)),
+            None => (area, None),
+        };
+
+        let row = row
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid highlight row: {row:?}"))?;
+
+        let columns = columns
+            .map(|columns| {
+                let invalid = || format!("invalid highlight columns: {columns:?}");
+                let (start, end) = columns.split_once("..").ok_or_else(invalid)?;
+                let start: usize = start.trim().parse().map_err(|_| invalid())?;
+                let end: usize = end.trim().parse().map_err(|_| invalid())?;
+                if start >= end {
+                    return Err(invalid());
+                }
+                Ok(start..end)
+            })
+            .transpose()?;
+
+        let color = color
+            .map(|color| {
+                color
+                    .trim()
+                    .parse::<Color>()
+                    .map_err(|e| format!("invalid highlight color {color:?}: {e}"))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            row,
+            columns,
+            color,
+        })
+    }
+}
+
+/// Builds highlights for the cells of the `current` surface that differ from the `baseline` surface.
+///
+/// Cells are compared by their text and attributes, cells missing in one of the surfaces
+/// are compared as blank ones. Adjacent differing cells within a row are merged into a single highlight.
+pub fn diff_highlights(
+    current: &Surface,
+    baseline: &Surface,
+    color: Option<Color>,
+) -> Vec<Highlight> {
+    let current = current.screen_lines();
+    let baseline = baseline.screen_lines();
+    let blank = (" ".to_owned(), CellAttributes::default());
+
+    let cell = |lines: &[Cow<Line>], row: usize, col: usize| {
+        lines
+            .get(row)
+            .and_then(|line| line.get_cell(col))
+            .map(|cell| (cell.str().to_owned(), cell.attrs().clone()))
+            .unwrap_or_else(|| blank.clone())
+    };
+
+    let mut highlights = Vec::new();
+
+    for (row, line) in current.iter().enumerate() {
+        let mut start = None;
+        for col in 0..=line.len() {
+            let differs = col < line.len() && cell(&current, row, col) != cell(&baseline, row, col);
+            match (differs, start) {
+                (true, None) => start = Some(col),
+                (false, Some(first)) => {
+                    highlights.push(Highlight {
+                        row,
+                        columns: Some(first..col),
+                        color: color.clone(),
+                    });
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    highlights
+}
+
+/// Returns the screen text like [`Surface::screen_chars_to_string`],
+/// but with the characters of concealed cells replaced by spaces.
+pub fn visible_text(surface: &Surface) -> String {
+    let mut text = String::new();
+    for line in surface.screen_lines() {
+        for cell in line.visible_cells() {
+            if cell.attrs().invisible() {
+                text.extend(std::iter::repeat_n(' ', cell.width()));
+            } else {
+                text.push_str(cell.str());
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// A rectangular area of the screen to render instead of the whole screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crop {
+    /// Number of rows.
+    pub rows: usize,
+    /// Number of columns, all columns if not set.
+    pub columns: Option<usize>,
+    /// Zero-based column and row of the top left corner.
+    /// If not set, the area is aligned to the bottom left corner of the screen.
+    pub origin: Option<(usize, usize)>,
+}
+
+impl Crop {
+    /// Returns the column and row ranges of the area within a screen of the given dimensions.
+    ///
+    /// The area is clamped to the screen, but keeps at least one row and one column.
+    pub fn area(&self, (width, height): (usize, usize)) -> (Range<usize>, Range<usize>) {
+        let (x, y) = self.origin.unwrap_or((0, height.saturating_sub(self.rows)));
+        let x = x.min(width.saturating_sub(1));
+        let y = y.min(height.saturating_sub(1));
+        let columns = self.columns.unwrap_or(width).clamp(1, (width - x).max(1));
+        let rows = self.rows.clamp(1, (height - y).max(1));
+
+        (x..x + columns, y..y + rows)
+    }
+
+    /// Copies the area of the surface into a new surface of the area size.
+    ///
+    /// The cursor keeps its place relative to the area, and is hidden if it is outside of the area.
+    pub fn apply(&self, surface: &Surface) -> Surface {
+        let (columns, rows) = self.area(surface.dimensions());
+
+        let mut cropped = Surface::new(columns.len(), rows.len());
+        let changes = cropped.diff_region(
+            0,
+            0,
+            columns.len(),
+            rows.len(),
+            surface,
+            columns.start,
+            rows.start,
+        );
+        cropped.add_changes(changes);
+
+        let (x, y) = surface.cursor_position();
+        let visibility = if columns.contains(&x) && rows.contains(&y) {
+            surface.cursor_visibility()
+        } else {
+            CursorVisibility::Hidden
+        };
+        cropped.add_change(Change::CursorPosition {
+            x: Position::Absolute(x.clamp(columns.start, columns.end - 1) - columns.start),
+            y: Position::Absolute(y.clamp(rows.start, rows.end - 1) - rows.start),
+        });
+        cropped.add_change(Change::CursorVisibility(visibility));
+        cropped
+    }
+
+    /// Moves the images into the area of a screen of the given dimensions.
+    ///
+    /// Images entirely outside of the area are dropped, the others are clipped when rendered.
+    pub fn apply_to_images(&self, dimensions: (usize, usize), images: &mut Vec<Image>) {
+        let (columns, rows) = self.area(dimensions);
+
+        images.retain_mut(|image| {
+            let overlaps = |start: isize, size: f32, area: &Range<usize>| {
+                start < area.end as isize && start as f32 + size > area.start as f32
+            };
+            if !overlaps(image.column, image.size.0, &columns)
+                || !overlaps(image.row, image.size.1, &rows)
+            {
+                return false;
+            }
+            image.column -= columns.start as isize;
+            image.row -= rows.start as isize;
+            true
+        });
+    }
+}
+
+impl FromStr for Crop {
+    type Err = String;
+
+    /// Parses a crop area in `ROWS[,COLUMNS][@X,Y]` format.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (size, origin) = match s.split_once('@') {
+            Some((size, origin)) => (size, Some(origin)),
+            None => (s, None),
+        };
+        let (rows, columns) = match size.split_once(',') {
+            Some((rows, columns)) => (rows, Some(columns)),
+            None => (size, None),
+        };
+
+        let count = |value: &str| {
+            value
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|&n| n != 0)
+                .ok_or_else(|| format!("invalid crop size: {size:?}"))
+        };
+        let rows = count(rows)?;
+        let columns = columns.map(count).transpose()?;
+
+        let origin = origin
+            .map(|origin| {
+                let invalid = || format!("invalid crop origin: {origin:?}");
+                let (x, y) = origin.split_once(',').ok_or_else(invalid)?;
+                let x = x.trim().parse().map_err(|_| invalid())?;
+                let y = y.trim().parse().map_err(|_| invalid())?;
+                Ok((x, y))
+            })
+            .transpose()?;
+
+        Ok(Self {
+            rows,
+            columns,
+            origin,
+        })
+    }
 }
 
 /// Options for configuring font properties.
@@ -62,7 +412,7 @@ pub struct FontOptions {
 }
 
 /// Metrics for font dimensions.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FontMetrics {
     pub width: f32,
     pub ascender: f32,
@@ -97,6 +447,30 @@ pub struct FontFace {
     pub format: Option<FontFormat>,
     pub chars: Rc<dyn CharSet>,
     pub metrics_match: bool,
+    /// Ranges of the characters kept in an embedded subset of the face, emitted as the `unicode-range` descriptor.
+    /// Empty for faces that are linked or embedded whole.
+    pub unicode_range: Vec<RangeInclusive<char>>,
+}
+
+/// Collects the characters into sorted ranges of consecutive code points.
+pub fn char_ranges<I>(chars: I) -> Vec<RangeInclusive<char>>
+where
+    I: IntoIterator<Item = char>,
+{
+    let mut chars = chars.into_iter().collect::<Vec<_>>();
+    chars.sort_unstable();
+    chars.dedup();
+
+    let mut ranges: Vec<RangeInclusive<char>> = Vec::new();
+    for ch in chars {
+        match ranges.last_mut() {
+            Some(range) if *range.end() as u32 + 1 == ch as u32 => {
+                *range = *range.start()..=ch;
+            }
+            _ => ranges.push(ch..=ch),
+        }
+    }
+    ranges
 }
 
 /// Enum representing different font styles.
@@ -0,0 +1,177 @@
+// std imports
+use std::io;
+
+// third-party imports
+use termwiz::{
+    cell::{Blink, CellAttributes, Intensity, Underline},
+    color::ColorAttribute,
+    surface::{Line, Surface},
+};
+
+// local imports
+use super::{Render, Result};
+
+/// A renderer re-emitting the screen as text with SGR escape sequences reconstructing the cell attributes.
+pub struct AnsiRenderer;
+
+impl AnsiRenderer {
+    /// Renders the given terminal surface to the specified target as text with SGR escape sequences.
+    ///
+    /// Rows are terminated by LF, so that the output can be piped to other tools.
+    pub fn render(&self, surface: &Surface, target: &mut dyn io::Write) -> Result<()> {
+        let mut result = encode_rows(surface, "\n");
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        target.write_all(result.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl Render for AnsiRenderer {
+    fn render(&self, surface: &Surface, target: &mut dyn io::Write) -> Result<()> {
+        Self::render(self, surface, target)
+    }
+}
+
+/// Encodes the visible screen of the surface as text with SGR escape sequences.
+///
+/// Rows are separated by CRLF so that the output can be replayed by a terminal as is.
+/// Trailing blank cells with default attributes are omitted.
+pub fn encode(surface: &Surface) -> String {
+    encode_rows(surface, "\r\n")
+}
+
+/// Encodes the visible screen of the surface, separating rows by the given separator.
+fn encode_rows(surface: &Surface, separator: &str) -> String {
+    let lines = surface.screen_lines();
+
+    // Skip trailing empty rows.
+    let rows = lines
+        .iter()
+        .rposition(|line| last_visible_cell(line).is_some())
+        .map_or(0, |i| i + 1);
+
+    let mut result = String::new();
+    for (row, line) in lines[..rows].iter().enumerate() {
+        if row != 0 {
+            result.push_str(separator);
+        }
+        encode_line(line, &mut result);
+    }
+
+    result
+}
+
+/// Encodes a single line, leaving the attributes reset at the end of it.
+fn encode_line(line: &Line, result: &mut String) {
+    let Some(end) = last_visible_cell(line) else {
+        return;
+    };
+
+    let mut current = Vec::new();
+    let mut pos = 0;
+
+    for cell in line.visible_cells() {
+        if cell.cell_index() > end {
+            break;
+        }
+
+        // Fill the gaps left by unset cells.
+        if cell.cell_index() > pos {
+            if !current.is_empty() {
+                result.push_str(RESET);
+                current.clear();
+            }
+            result.extend(std::iter::repeat_n(' ', cell.cell_index() - pos));
+        }
+
+        let params = sgr_params(cell.attrs());
+        if params != current {
+            if params.is_empty() {
+                result.push_str(RESET);
+            } else {
+                result.push_str(&format!("\x1b[0;{}m", params.join(";")));
+            }
+            current = params;
+        }
+        result.push_str(cell.str());
+        pos = cell.cell_index() + cell.width();
+    }
+
+    if !current.is_empty() {
+        result.push_str(RESET);
+    }
+}
+
+/// Returns the index of the last cell that has visible content or visible attributes.
+fn last_visible_cell(line: &Line) -> Option<usize> {
+    line.visible_cells()
+        .filter(|cell| cell.str() != " " || !sgr_params(cell.attrs()).is_empty())
+        .last()
+        .map(|cell| cell.cell_index())
+}
+
+/// Builds SGR parameters applying the given attributes on top of the default ones.
+fn sgr_params(attrs: &CellAttributes) -> Vec<String> {
+    let mut params = Vec::new();
+
+    match attrs.intensity() {
+        Intensity::Normal => {}
+        Intensity::Bold => params.push("1".into()),
+        Intensity::Half => params.push("2".into()),
+    }
+    if attrs.italic() {
+        params.push("3".into());
+    }
+    match attrs.underline() {
+        Underline::None => {}
+        Underline::Single => params.push("4".into()),
+        Underline::Double => params.push("21".into()),
+        Underline::Curly => params.push("4:3".into()),
+        Underline::Dotted => params.push("4:4".into()),
+        Underline::Dashed => params.push("4:5".into()),
+    }
+    match attrs.blink() {
+        Blink::None => {}
+        Blink::Slow => params.push("5".into()),
+        Blink::Rapid => params.push("6".into()),
+    }
+    if attrs.reverse() {
+        params.push("7".into());
+    }
+    if attrs.invisible() {
+        params.push("8".into());
+    }
+    if attrs.strikethrough() {
+        params.push("9".into());
+    }
+    if attrs.overline() {
+        params.push("53".into());
+    }
+    params.extend(color(attrs.foreground(), 30));
+    params.extend(color(attrs.background(), 40));
+
+    params
+}
+
+/// Builds SGR parameters for a color, `base` is 30 for foreground and 40 for background.
+fn color(color: ColorAttribute, base: u8) -> Option<String> {
+    match color {
+        ColorAttribute::Default => None,
+        ColorAttribute::PaletteIndex(i @ 0..8) => Some((base + i).to_string()),
+        ColorAttribute::PaletteIndex(i @ 8..16) => Some((base + 60 + i - 8).to_string()),
+        ColorAttribute::PaletteIndex(i) => Some(format!("{};5;{i}", base + 8)),
+        ColorAttribute::TrueColorWithPaletteFallback(c, _)
+        | ColorAttribute::TrueColorWithDefaultFallback(c) => {
+            let (r, g, b, _) = c.as_rgba_u8();
+            Some(format!("{};2;{r};{g};{b}", base + 8))
+        }
+    }
+}
+
+/// Sequence resetting all attributes to their defaults.
+const RESET: &str = "\x1b[0m";
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,87 @@
+use super::*;
+
+use termwiz::{cell::AttributeChange, color::AnsiColor, surface::Change};
+
+#[test]
+fn test_encode_plain_text() {
+    let mut surface = Surface::new(10, 3);
+    surface.add_change(Change::Text("hello\r\nworld".into()));
+
+    assert_eq!(encode(&surface), "hello\r\nworld");
+}
+
+#[test]
+fn test_encode_attributes() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Attribute(AttributeChange::Intensity(
+        Intensity::Bold,
+    )));
+    surface.add_change(Change::Attribute(AttributeChange::Foreground(
+        AnsiColor::Red.into(),
+    )));
+    surface.add_change(Change::Text("ab".into()));
+    surface.add_change(Change::AllAttributes(CellAttributes::default()));
+    surface.add_change(Change::Text("c".into()));
+
+    assert_eq!(encode(&surface), "\x1b[0;1;91mab\x1b[0mc");
+}
+
+#[test]
+fn test_encode_colored_trailing_spaces() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Text("a".into()));
+    surface.add_change(Change::Attribute(AttributeChange::Background(
+        AnsiColor::Navy.into(),
+    )));
+    surface.add_change(Change::Text("  ".into()));
+
+    assert_eq!(encode(&surface), "a\x1b[0;44m  \x1b[0m");
+}
+
+#[test]
+fn test_encode_true_color() {
+    assert_eq!(
+        color(
+            ColorAttribute::TrueColorWithDefaultFallback((1u8, 2u8, 3u8, 255u8).into()),
+            30
+        ),
+        Some("38;2;1;2;3".to_string())
+    );
+    assert_eq!(
+        color(ColorAttribute::PaletteIndex(200), 40),
+        Some("48;5;200".to_string())
+    );
+    assert_eq!(color(ColorAttribute::Default, 30), None);
+}
+
+#[test]
+fn test_render_round_trip() {
+    let terminal = |input: &[u8]| {
+        let mut terminal = crate::term::Terminal::new(crate::term::Options {
+            cols: Some(20),
+            rows: Some(2),
+            ..Default::default()
+        });
+        terminal.advance(input);
+        terminal
+    };
+
+    let original = terminal(b"\x1b[1;3;31;48;2;10;20;30mred\x1b[0m \x1b[4;38;5;200mpink\x1b[0m");
+
+    let mut output = Vec::new();
+    AnsiRenderer
+        .render(original.surface(), &mut output)
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.ends_with("\x1b[0m\n"));
+
+    let restored = terminal(output.trim_end().as_bytes());
+
+    let cells = |terminal: &crate::term::Terminal| {
+        terminal.surface().screen_lines()[0]
+            .visible_cells()
+            .map(|cell| (cell.str().to_owned(), cell.attrs().clone()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(cells(&restored), cells(&original));
+}
@@ -0,0 +1,51 @@
+// std imports
+use std::io;
+
+// third-party imports
+use serde_json::json;
+use termwiz::surface::Surface;
+
+// local imports
+use super::{Render, Result, ansi};
+
+/// A renderer producing an asciicast v2 recording with a single frame printing the whole screen.
+pub struct CastRenderer {
+    title: Option<String>,
+}
+
+impl CastRenderer {
+    /// Creates a new `CastRenderer` with an optional recording title.
+    pub fn new(title: Option<String>) -> Self {
+        Self { title }
+    }
+
+    /// Renders the given terminal surface to the specified target as an asciicast v2 recording.
+    pub fn render(&self, surface: &Surface, target: &mut dyn io::Write) -> Result<()> {
+        let (width, height) = surface.dimensions();
+
+        let mut header = json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+        });
+        if let Some(title) = &self.title {
+            header["title"] = title.as_str().into();
+        }
+
+        let event = json!([0.0, "o", ansi::encode(surface)]);
+
+        writeln!(target, "{header}")?;
+        writeln!(target, "{event}")?;
+
+        Ok(())
+    }
+}
+
+impl Render for CastRenderer {
+    fn render(&self, surface: &Surface, target: &mut dyn io::Write) -> Result<()> {
+        Self::render(self, surface, target)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,46 @@
+use super::*;
+
+use termwiz::{cell::AttributeChange, color::AnsiColor, surface::Change};
+
+use crate::term::{self, Terminal};
+
+#[test]
+fn test_render_cast() {
+    let mut surface = Surface::new(12, 4);
+    surface.add_change(Change::Text("first line\r\n".into()));
+    surface.add_change(Change::Attribute(AttributeChange::Foreground(
+        AnsiColor::Green.into(),
+    )));
+    surface.add_change(Change::Text("second".into()));
+
+    let mut output = Vec::new();
+    CastRenderer::new(Some("demo".into()))
+        .render(&surface, &mut output)
+        .unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<_> = output.lines().collect();
+    assert_eq!(lines.len(), 2);
+
+    let header: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(header["version"], 2);
+    assert_eq!(header["width"], 12);
+    assert_eq!(header["height"], 4);
+    assert_eq!(header["title"], "demo");
+
+    let event: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+    assert_eq!(event[0], 0.0);
+    assert_eq!(event[1], "o");
+
+    let mut terminal = Terminal::new(term::Options {
+        cols: Some(12),
+        rows: Some(4),
+        ..Default::default()
+    });
+    terminal.advance(event[2].as_str().unwrap().as_bytes());
+
+    assert_eq!(
+        terminal.surface().screen_chars_to_string(),
+        surface.screen_chars_to_string()
+    );
+}
@@ -0,0 +1,217 @@
+// std imports
+use std::{collections::BTreeSet, ops::RangeInclusive};
+
+// third-party imports
+use askama::Template;
+
+// local imports
+use super::{FontStyle, FontWeight, Options, Result};
+
+// re-exports
+pub use styles::{Blink, FontFace, Theme};
+
+/// Builds the combined stylesheet from the given theme template and the used font faces.
+///
+/// # Arguments
+///
+/// * `opt` - A reference to the `Options` struct containing configuration settings.
+/// * `theme` - The theme template holding the color variables, if any.
+/// * `used_font_faces` - A `BTreeSet` containing the indices of the used font faces.
+///
+/// # Returns
+///
+/// A `Result` containing the stylesheet, which is empty if there is nothing to style.
+pub fn stylesheet(
+    opt: &Options,
+    theme: Option<&Theme>,
+    used_font_faces: &BTreeSet<usize>,
+) -> Result<String> {
+    let mut ss = String::new();
+
+    if let Some(theme) = theme
+        && !theme.vars.is_empty()
+    {
+        ss = theme.render()?;
+    }
+
+    let faces = font_faces(opt, used_font_faces)?;
+    if !faces.is_empty() {
+        if !ss.is_empty() {
+            ss += "\n";
+        }
+        ss += &faces.join("\n");
+    }
+
+    Ok(ss)
+}
+
+/// Collects the `@font-face` rules for the used font faces.
+///
+/// # Arguments
+///
+/// * `opt` - A reference to the `Options` struct containing configuration settings.
+/// * `used_font_faces` - A `BTreeSet` containing the indices of the used font faces.
+///
+/// # Returns
+///
+/// A `Result` containing a vector of strings representing the font faces.
+pub fn font_faces(opt: &Options, used_font_faces: &BTreeSet<usize>) -> Result<Vec<String>> {
+    let faces = &opt
+        .font
+        .faces
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| used_font_faces.contains(i))
+        .map(|(_, face)| FontFace {
+            font_family: face.family.clone(),
+            font_weight: match face.weight {
+                FontWeight::Normal => "normal".into(),
+                FontWeight::Bold => "bold".into(),
+                FontWeight::Fixed(w) => w.to_string(),
+                FontWeight::Variable(min, max) => {
+                    format!("{min} {max}", min = f32::from(min), max = f32::from(max))
+                }
+            },
+            font_style: face.style.map(|style| match style {
+                FontStyle::Normal => "normal".into(),
+                FontStyle::Italic => "italic".into(),
+                FontStyle::Oblique => "oblique".into(),
+            }),
+            src_url: face.url.to_string(),
+            format: face.format.map(|f| f.css()),
+            unicode_range: (!face.unicode_range.is_empty())
+                .then(|| unicode_range(&face.unicode_range)),
+        })
+        .collect::<Vec<_>>();
+
+    let faces = faces
+        .iter()
+        .map(|face| {
+            face.render()
+                .map_err(Into::into)
+                .map(|x| x.trim().to_owned())
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(faces)
+}
+
+/// Builds the rules animating the blinking text.
+///
+/// # Arguments
+///
+/// * `prefix` - The prefix of the class and animation names.
+///
+/// # Returns
+///
+/// A `Result` containing the `@keyframes` rule and the `blink` and `blink-rapid` class rules.
+pub fn blink(prefix: &str) -> Result<String> {
+    let rules = Blink {
+        prefix: prefix.into(),
+        slow: BLINK_SLOW_DURATION,
+        rapid: BLINK_RAPID_DURATION,
+    };
+    Ok(rules.render()?.trim().to_owned())
+}
+
+/// Removes insignificant whitespace and redundant semicolons from the stylesheet.
+///
+/// Quoted strings are kept as is.
+pub fn minify(css: &str) -> String {
+    let separator = |ch: char| matches!(ch, '{' | '}' | ';' | ':' | ',');
+
+    let mut result = String::with_capacity(css.len());
+    let mut quote = None;
+    let mut space = false;
+
+    for ch in css.chars() {
+        if let Some(q) = quote {
+            result.push(ch);
+            if ch == q {
+                quote = None;
+            }
+            continue;
+        }
+
+        if ch.is_whitespace() {
+            space = true;
+            continue;
+        }
+
+        if space && !separator(ch) && result.chars().last().is_some_and(|last| !separator(last)) {
+            result.push(' ');
+        }
+        space = false;
+
+        if ch == '}' && result.ends_with(';') {
+            result.pop();
+        }
+        if matches!(ch, '"' | '\'') {
+            quote = Some(ch);
+        }
+        result.push(ch);
+    }
+
+    result
+}
+
+/// Formats the character ranges as a value of the `unicode-range` descriptor, e.g. `U+61-63, U+78`.
+fn unicode_range(ranges: &[RangeInclusive<char>]) -> String {
+    ranges
+        .iter()
+        .map(|range| {
+            let (start, end) = (*range.start() as u32, *range.end() as u32);
+            if start == end {
+                format!("U+{start:X}")
+            } else {
+                format!("U+{start:X}-{end:X}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Blink cycle duration of the slowly blinking text.
+const BLINK_SLOW_DURATION: &str = "1s";
+/// Blink cycle duration of the rapidly blinking text.
+const BLINK_RAPID_DURATION: &str = "0.4s";
+
+// ---
+
+mod styles {
+    // third-party imports
+    use askama::Template;
+
+    /// Template for a single `@font-face` rule.
+    #[derive(Template)]
+    #[template(path = "styles/font-face.css")]
+    pub struct FontFace {
+        pub font_family: String,
+        pub font_weight: String,
+        pub font_style: Option<String>,
+        pub src_url: String,
+        pub format: Option<&'static str>,
+        pub unicode_range: Option<String>,
+    }
+
+    /// Template for the rules animating the blinking text.
+    #[derive(Template)]
+    #[template(path = "styles/blink.css")]
+    pub struct Blink {
+        pub prefix: String,
+        pub slow: &'static str,
+        pub rapid: &'static str,
+    }
+
+    /// Template for a rule defining the theme color variables.
+    #[derive(Template)]
+    #[template(path = "styles/theme.css")]
+    pub struct Theme {
+        pub name: String,
+        pub vars: Vec<(String, String)>,
+        pub classes: Vec<(String, String)>,
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,88 @@
+use super::*;
+
+use std::rc::Rc;
+
+use crate::{
+    fontformat::FontFormat,
+    render::{self, CharSetFn},
+};
+
+fn face(url: &str, format: Option<FontFormat>) -> render::FontFace {
+    render::FontFace {
+        family: "Monospace".to_string(),
+        weight: FontWeight::Normal,
+        style: None,
+        url: url.to_string(),
+        format,
+        chars: Rc::new(CharSetFn::new(|_| true)),
+        metrics_match: true,
+        unicode_range: Vec::new(),
+    }
+}
+
+#[test]
+fn test_font_faces_woff2_format() {
+    let mut options = Options::sample();
+    options.font.faces = vec![face(
+        &format!("data:{};base64,d09GMg==", FontFormat::Woff2.mime()),
+        Some(FontFormat::Woff2),
+    )];
+
+    let faces = font_faces(&options, &BTreeSet::from([0])).unwrap();
+    assert_eq!(faces.len(), 1);
+    assert!(
+        faces[0].contains("src: url(data:font/woff2;base64,d09GMg==) format('woff2');"),
+        "{}",
+        faces[0]
+    );
+}
+
+#[test]
+fn test_font_faces_unicode_range() {
+    let mut options = Options::sample();
+    options.font.faces = vec![face("monospace.ttf", None)];
+    options.font.faces[0].unicode_range = render::char_ranges("xcab─".chars());
+
+    let faces = font_faces(&options, &BTreeSet::from([0])).unwrap();
+    assert_eq!(faces.len(), 1);
+    assert!(
+        faces[0].contains("\tunicode-range: U+61-63, U+78, U+2500;\n"),
+        "{}",
+        faces[0]
+    );
+
+    options.font.faces[0].unicode_range.clear();
+    let faces = font_faces(&options, &BTreeSet::from([0])).unwrap();
+    assert!(!faces[0].contains("unicode-range"), "{}", faces[0]);
+}
+
+#[test]
+fn test_font_faces_unused() {
+    let mut options = Options::sample();
+    options.font.faces = vec![face("regular.ttf", None), face("bold.ttf", None)];
+
+    let faces = font_faces(&options, &BTreeSet::from([1])).unwrap();
+    assert_eq!(faces.len(), 1);
+    assert!(faces[0].contains("url(bold.ttf)"), "{}", faces[0]);
+
+    assert_eq!(stylesheet(&options, None, &BTreeSet::new()).unwrap(), "");
+}
+
+#[test]
+fn test_unicode_range() {
+    assert_eq!(unicode_range(&[]), "");
+    assert_eq!(unicode_range(&['a'..='a']), "U+61");
+    assert_eq!(
+        unicode_range(&['a'..='c', 'x'..='x', '─'..='─']),
+        "U+61-63, U+78, U+2500"
+    );
+}
+
+#[test]
+fn test_minify() {
+    let ss = ".terminal {\n\t--c-1: #ff0000;\n\tfont-family: \"Fira  Code\", monospace;\n}\n.terminal .c-1 { fill: var(--c-1); }\n";
+    assert_eq!(
+        minify(ss),
+        ".terminal{--c-1:#ff0000;font-family:\"Fira  Code\",monospace}.terminal .c-1{fill:var(--c-1)}"
+    );
+}
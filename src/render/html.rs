@@ -0,0 +1,221 @@
+// std imports
+use std::{fmt::Write as _, io};
+
+// third-party imports
+use termwiz::{
+    cell::{Intensity, Underline},
+    color::ColorAttribute,
+    surface::Surface,
+};
+
+// local imports
+use super::{
+    FontStyle, FontWeight, Options, Render, Result, css,
+    svg::{
+        ColorStyle, ColorStyleId, font_params, make_palette, resolve_bg, resolve_fg, subdivide,
+        svg_weight, text_decoration,
+    },
+};
+
+/// A renderer producing a self-contained HTML `<pre>` block with inline styled spans.
+///
+/// The text is kept as is, so it can be selected and copied from the page.
+pub struct HtmlRenderer {
+    options: Options,
+}
+
+impl HtmlRenderer {
+    /// Creates a new `HtmlRenderer` with the given options.
+    pub fn new(options: Options) -> Self {
+        Self { options }
+    }
+
+    /// Renders the given terminal surface to the specified target as an HTML fragment.
+    pub fn render(&self, surface: &Surface, target: &mut dyn io::Write) -> Result<()> {
+        let opt = &self.options;
+        let cfg = &opt.settings;
+        let prefix = cfg.rendering.svg.id_prefix.as_str();
+        let class = format!("{prefix}terminal");
+
+        let lines = surface.screen_lines();
+        let mut palette = make_palette(opt, &lines);
+        let default_weight = opt.font.weights.normal;
+        let default_fg = ColorStyle::Custom(opt.fg().clone());
+
+        let mut body = String::new();
+
+        for (row, line) in lines.iter().enumerate() {
+            if row != 0 {
+                body.push('\n');
+            }
+
+            // Blank text without a background is only written if something follows it on the line.
+            let mut pending = String::new();
+
+            for cluster in line.cluster(None) {
+                let attrs = &cluster.attrs;
+                let bg = resolve_bg(cfg, &mut palette, attrs);
+
+                // Concealed text keeps its place and background, but not its characters.
+                if attrs.invisible() {
+                    let blank = " ".repeat(cluster.width);
+                    if let Some(bg) = &bg {
+                        body.push_str(&escape(&pending));
+                        pending.clear();
+                        write!(
+                            body,
+                            "<span style=\"background-color:{}\">{blank}</span>",
+                            bg.render(prefix)
+                        )?;
+                    } else {
+                        pending.push_str(&blank);
+                    }
+                    continue;
+                }
+
+                if bg.is_none() && cluster.text.trim().is_empty() {
+                    pending.push_str(&cluster.text);
+                    continue;
+                }
+
+                body.push_str(&escape(&pending));
+                pending.clear();
+
+                let color = if attrs.reverse() {
+                    palette.bg(attrs.background())
+                } else {
+                    resolve_fg(cfg, &mut palette, attrs)
+                };
+
+                let color = if let Some(min) = cfg.rendering.min_contrast {
+                    palette.ensure_contrast(color, bg.as_ref(), min.f32())
+                } else {
+                    color
+                };
+
+                let mut style = String::new();
+
+                if color != ColorStyleId::DefaultForeground && color != default_fg {
+                    write!(style, "color:{};", color.render(prefix))?;
+                }
+
+                if let Some(bg) = &bg {
+                    write!(style, "background-color:{};", bg.render(prefix))?;
+                }
+
+                if attrs.intensity() == Intensity::Half && cfg.rendering.faint_opacity.f32() < 1.0 {
+                    write!(style, "opacity:{};", cfg.rendering.faint_opacity)?;
+                }
+
+                let decoration = text_decoration(attrs);
+                if !decoration.is_empty() {
+                    write!(style, "text-decoration:{decoration};")?;
+                }
+
+                match attrs.underline() {
+                    Underline::None | Underline::Single => {}
+                    Underline::Double => style.push_str("text-decoration-style:double;"),
+                    Underline::Curly => style.push_str("text-decoration-style:wavy;"),
+                    Underline::Dotted => style.push_str("text-decoration-style:dotted;"),
+                    Underline::Dashed => style.push_str("text-decoration-style:dashed;"),
+                }
+
+                if attrs.underline_color() != ColorAttribute::Default
+                    && let Some(mut color) = opt.theme.resolve(attrs.underline_color())
+                {
+                    color.a = 1.0;
+                    write!(style, "text-decoration-color:{};", color.to_css_hex())?;
+                }
+
+                let link = attrs.hyperlink().filter(|_| cfg.rendering.svg.hyperlinks);
+                if let Some(link) = link {
+                    write!(body, "<a href=\"{}\">", escape(link.uri()))?;
+                }
+
+                let (weight, font_style) = font_params(attrs, opt);
+                if weight != default_weight {
+                    write!(style, "font-weight:{};", svg_weight(weight))?;
+                }
+                match font_style {
+                    FontStyle::Normal => {}
+                    FontStyle::Italic => style.push_str("font-style:italic;"),
+                    FontStyle::Oblique => style.push_str("font-style:oblique;"),
+                }
+
+                for (text, _) in subdivide(line, &cluster, opt) {
+                    if style.is_empty() {
+                        body.push_str(&escape(text));
+                    } else {
+                        write!(
+                            body,
+                            "<span style=\"{}\">{}</span>",
+                            style.trim_end_matches(';'),
+                            escape(text)
+                        )?;
+                    }
+                }
+
+                if link.is_some() {
+                    body.push_str("</a>");
+                }
+            }
+        }
+
+        let pad = cfg.padding.resolve();
+        let mut style = format!(
+            "margin:0;padding:{}em {}em {}em {}em;font-family:{};font-size:{}px;line-height:{};background-color:{};color:{}",
+            pad.top,
+            pad.right,
+            pad.bottom,
+            pad.left,
+            opt.font.family.join(", "),
+            opt.font.size,
+            cfg.rendering.line_height,
+            palette.bg(ColorAttribute::Default).render(prefix),
+            palette.fg(ColorAttribute::Default).render(prefix),
+        );
+        if default_weight != FontWeight::Normal {
+            write!(style, ";font-weight:{}", svg_weight(default_weight))?;
+        }
+
+        if cfg.rendering.svg.var_palette || cfg.rendering.svg.color_classes {
+            let ss = css::stylesheet(opt, Some(&palette.template(&class)), &Default::default())?;
+            if !ss.is_empty() {
+                writeln!(target, "<style>\n{ss}\n</style>")?;
+            }
+        }
+
+        writeln!(
+            target,
+            "<pre class=\"{class}\" style=\"{}\">{body}</pre>",
+            escape(&style)
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Render for HtmlRenderer {
+    fn render(&self, surface: &Surface, target: &mut dyn io::Write) -> Result<()> {
+        Self::render(self, surface, target)
+    }
+}
+
+/// Escapes the characters having a special meaning in HTML text and attribute values.
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            _ => result.push(ch),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,67 @@
+use super::*;
+
+use std::rc::Rc;
+
+use crate::config::Settings;
+
+fn options(settings: Settings) -> Options {
+    Options {
+        settings: Rc::new(settings),
+        title: None,
+        ..Options::sample()
+    }
+}
+
+fn render(settings: Settings, input: &[u8]) -> String {
+    let mut terminal = crate::term::Terminal::new(crate::term::Options {
+        cols: Some(20),
+        rows: Some(2),
+        ..Default::default()
+    });
+    terminal.advance(input);
+
+    let mut output = Vec::new();
+    HtmlRenderer::new(options(settings))
+        .render(terminal.surface(), &mut output)
+        .unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn test_render_html() {
+    let html = render(
+        Settings::default(),
+        b"\x1b[38;2;255;0;0mred\x1b[0m <b>\r\n\x1b[1;48;2;0;0;255mbold\x1b[0m",
+    );
+
+    assert!(html.starts_with("<pre class=\"terminal\""));
+    assert!(html.trim_end().ends_with("</pre>"));
+    assert!(html.contains("<span style=\"color:#ff0000\">red</span> &lt;b&gt;\n"));
+    assert!(html.contains("<span style=\"background-color:#0000ff;font-weight:bold\">bold</span>"));
+    assert!(!html.contains("<style>"));
+}
+
+#[test]
+fn test_render_html_var_palette() {
+    let mut settings = Settings::default();
+    settings.rendering.svg.var_palette = true;
+
+    let html = render(settings, b"\x1b[31mred\x1b[0m");
+
+    assert!(html.starts_with("<style>\n.terminal {"));
+    assert!(html.contains("--c-1: "));
+    assert!(html.contains("<span style=\"color:var(--c-1)\">red</span>"));
+}
+
+#[test]
+fn test_render_html_invisible() {
+    let html = render(
+        Settings::default(),
+        b"pw: \x1b[8msecret\x1b[0m!\r\n\x1b[8;48;2;0;0;255mhidden\x1b[0m",
+    );
+
+    assert!(!html.contains("secret"));
+    assert!(!html.contains("hidden"));
+    assert!(html.contains("pw:       !\n"));
+    assert!(html.contains("<span style=\"background-color:#0000ff\">      </span>"));
+}
@@ -0,0 +1,47 @@
+// std imports
+use std::io;
+
+// third-party imports
+use termwiz::surface::Surface;
+
+// local imports
+use super::{Options, Render, Result};
+
+/// A renderer rasterizing the SVG representation of terminal surfaces to PNG.
+#[cfg_attr(not(feature = "png"), allow(dead_code))]
+pub struct PngRenderer {
+    options: Options,
+    scale: f32,
+}
+
+impl PngRenderer {
+    /// Creates a new `PngRenderer` with the given options and scale factor for DPI.
+    pub fn new(options: Options, scale: f32) -> Self {
+        Self { options, scale }
+    }
+
+    /// Renders the given terminal surface to the specified target as a PNG image.
+    #[cfg(feature = "png")]
+    pub fn render(&self, surface: &Surface, target: &mut dyn io::Write) -> Result<()> {
+        let pixmap = super::raster::rasterize(&self.options, self.scale, surface)?;
+
+        target.write_all(&pixmap.encode_png()?)?;
+
+        Ok(())
+    }
+
+    /// Fails because termframe is built without the `png` feature.
+    #[cfg(not(feature = "png"))]
+    pub fn render(&self, _surface: &Surface, _target: &mut dyn io::Write) -> Result<()> {
+        anyhow::bail!("cannot render PNG output: termframe is built without png support")
+    }
+}
+
+impl Render for PngRenderer {
+    fn render(&self, surface: &Surface, target: &mut dyn io::Write) -> Result<()> {
+        Self::render(self, surface, target)
+    }
+}
+
+#[cfg(all(test, feature = "png"))]
+mod tests;
@@ -0,0 +1,51 @@
+use super::*;
+
+use std::rc::Rc;
+
+use termwiz::surface::Change;
+
+use crate::config::Settings;
+
+fn options() -> Options {
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+
+    Options {
+        settings: Rc::new(settings),
+        title: None,
+        ..Options::sample()
+    }
+}
+
+/// Returns the image width and height from the PNG header.
+fn png_size(data: &[u8]) -> (u32, u32) {
+    assert_eq!(&data[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&data[12..16], b"IHDR");
+    let width = u32::from_be_bytes(data[16..20].try_into().unwrap());
+    let height = u32::from_be_bytes(data[20..24].try_into().unwrap());
+    (width, height)
+}
+
+#[test]
+fn test_render_png() {
+    let mut surface = Surface::new(4, 2);
+    surface.add_change(Change::Text("ab".into()));
+
+    let mut output = Vec::new();
+    PngRenderer::new(options(), 1.0)
+        .render(&surface, &mut output)
+        .unwrap();
+    let (width, height) = png_size(&output);
+    assert!(width > 0 && height > 0);
+
+    let mut output = Vec::new();
+    PngRenderer::new(options(), 2.0)
+        .render(&surface, &mut output)
+        .unwrap();
+    let (width2, height2) = png_size(&output);
+    assert!(width2.abs_diff(width * 2) <= 1, "{width2} != 2 * {width}");
+    assert!(
+        height2.abs_diff(height * 2) <= 1,
+        "{height2} != 2 * {height}"
+    );
+}
@@ -0,0 +1,79 @@
+// std imports
+use std::collections::{BTreeSet, HashMap};
+
+pub type Rgba = [u8; 4];
+
+/// Reduces the given colors to at most `max` representative colors using the median cut algorithm.
+///
+/// Returns a map from each of the given colors to its representative color,
+/// which is the average of the colors falling into the same box.
+pub fn median_cut<I>(colors: I, max: usize) -> HashMap<Rgba, Rgba>
+where
+    I: IntoIterator<Item = Rgba>,
+{
+    let colors = colors.into_iter().collect::<BTreeSet<_>>();
+    if colors.len() <= max {
+        return colors.into_iter().map(|c| (c, c)).collect();
+    }
+
+    let mut boxes = vec![colors.into_iter().collect::<Vec<_>>()];
+
+    while boxes.len() < max.max(1) {
+        // Split the box with the widest channel range.
+        let Some((i, channel, _)) = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, colors)| colors.len() > 1)
+            .map(|(i, colors)| {
+                let (channel, range) = widest_channel(colors);
+                (i, channel, range)
+            })
+            .max_by_key(|&(_, _, range)| range)
+        else {
+            break;
+        };
+
+        let mut colors = boxes.swap_remove(i);
+        colors.sort_by_key(|c| c[channel]);
+        let upper = colors.split_off(colors.len() / 2);
+        boxes.push(colors);
+        boxes.push(upper);
+    }
+
+    let mut result = HashMap::new();
+    for colors in boxes {
+        let representative = average(&colors);
+        result.extend(colors.into_iter().map(|c| (c, representative)));
+    }
+
+    result
+}
+
+/// Returns the index of the channel with the widest range of values and the range itself.
+fn widest_channel(colors: &[Rgba]) -> (usize, u8) {
+    (0..4)
+        .map(|channel| {
+            let values = colors.iter().map(|c| c[channel]);
+            let min = values.clone().min().unwrap_or_default();
+            let max = values.max().unwrap_or_default();
+            (channel, max - min)
+        })
+        .max_by_key(|&(_, range)| range)
+        .unwrap_or_default()
+}
+
+/// Returns the average of the colors.
+fn average(colors: &[Rgba]) -> Rgba {
+    let mut sum = [0usize; 4];
+    for color in colors {
+        for (s, v) in sum.iter_mut().zip(color) {
+            *s += *v as usize;
+        }
+    }
+
+    let n = colors.len().max(1);
+    sum.map(|s| ((s + n / 2) / n) as u8)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,23 @@
+use super::*;
+
+#[test]
+fn test_median_cut_keeps_few_colors() {
+    let colors = [[0, 0, 0, 255], [255, 0, 0, 255]];
+    let map = median_cut(colors, 4);
+    assert_eq!(map.len(), 2);
+    assert!(map.iter().all(|(k, v)| k == v));
+}
+
+#[test]
+fn test_median_cut_limits_colors() {
+    let colors = (0..=255).map(|i| [i, 0, 255 - i, 255]).collect::<Vec<_>>();
+    let map = median_cut(colors.iter().copied(), 4);
+
+    assert_eq!(map.len(), 256);
+    let representatives = map.values().collect::<BTreeSet<_>>();
+    assert_eq!(representatives.len(), 4);
+
+    // Similar colors share the representative, distant ones do not.
+    assert_eq!(map[&[0, 0, 255, 255]], map[&[1, 0, 254, 255]]);
+    assert_ne!(map[&[0, 0, 255, 255]], map[&[255, 0, 0, 255]]);
+}
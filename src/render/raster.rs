@@ -0,0 +1,57 @@
+// std imports
+use std::sync::Arc;
+
+// third-party imports
+use anyhow::Context;
+use resvg::{tiny_skia, usvg};
+use termwiz::surface::Surface;
+
+// local imports
+use super::{Options, Result, svg::SvgRenderer};
+
+/// Rasterizes the SVG representation of the terminal surface, scaled by the given factor.
+///
+/// Only fonts embedded into the SVG document are available to the rasterizer besides the system ones,
+/// so the fonts should be embedded and subset to TTF for the output to match the SVG.
+pub fn rasterize(options: &Options, scale: f32, surface: &Surface) -> Result<tiny_skia::Pixmap> {
+    let mut svg = Vec::new();
+    SvgRenderer::new(options.clone()).render(surface, &mut svg)?;
+
+    let mut fonts = usvg::fontdb::Database::new();
+    fonts.load_system_fonts();
+    for face in &options.font.faces {
+        if let Some(data) = embedded_font_data(&face.url) {
+            fonts.load_font_data(data);
+        }
+    }
+
+    let usvg_options = usvg::Options {
+        fontdb: Arc::new(fonts),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_data(&svg, &usvg_options)?;
+
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .with_context(|| format!("invalid scale {scale}"))?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .with_context(|| format!("invalid image size {}x{}", size.width(), size.height()))?;
+
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    Ok(pixmap)
+}
+
+/// Decodes the font data embedded into a `data:` URL.
+fn embedded_font_data(url: &str) -> Option<Vec<u8>> {
+    use base64::prelude::*;
+
+    let (_, data) = url.strip_prefix("data:")?.split_once(";base64,")?;
+    BASE64_STANDARD.decode(data).ok()
+}
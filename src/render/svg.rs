@@ -1,33 +1,51 @@
 use std::{
     borrow::Cow,
     cmp::{max, min},
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap},
     ops::{Range, RangeInclusive},
     rc::Rc,
 };
 
-use askama::Template;
 use csscolorparser::Color;
+use flate2::{Compression, write::GzEncoder};
 use indexmap::IndexSet;
+use serde_json::json;
 use svg::{Document, Node, node::element};
 use termwiz::{
-    cell::{CellAttributes, Intensity, Underline},
+    cell::{Blink, CellAttributes, Intensity, Underline},
     cellcluster::CellCluster,
     color::{ColorAttribute, SrgbaTuple},
-    surface::{Line, Surface, line::CellRef},
+    surface::{CursorVisibility, Line, SEQ_ZERO, Surface, line::CellRef},
 };
 
-use super::{FontFace, FontStyle, FontWeight, Padding, Render, Theme};
+use super::{
+    FontFace, FontStyle, FontWeight, Padding, Render, Theme, css, quantize,
+    tracing::{Connectivity, Shape},
+    visible_text,
+};
 use crate::config::{
+    CursorStyle, HighlightLayer, Settings,
     types::Number,
     winstyle::{
-        LineCap, WindowButton, WindowButtonIconKind, WindowButtonShape, WindowButtonsPosition,
+        LineCap, TitleAlign, WindowButton, WindowButtonIconKind, WindowButtonShape,
+        WindowButtonsPosition, WindowShadowLayer,
     },
 };
 
 pub use super::{Options, Result};
 
+/// A snapshot of the screen rows shown from the given time on in an animation.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    /// Time in seconds since the beginning of the animation.
+    pub time: f64,
+    pub lines: Vec<Line>,
+}
+
 /// A renderer for generating SVG representations of terminal surfaces.
+///
+/// The output is deterministic: the same surface and options always produce the same bytes,
+/// so it can be used for snapshot testing.
 pub struct SvgRenderer {
     options: Options,
 }
@@ -38,14 +56,59 @@ impl SvgRenderer {
         Self { options }
     }
 
+    /// Writes the background shapes traced from the given terminal surface to the specified target as JSON.
+    ///
+    /// Each shape is written with its color key and contours of cell boundary coordinates.
+    /// This is a diagnostics aid for inspecting the geometry behind the background paths.
+    pub fn dump_shapes(&self, surface: &Surface, target: &mut dyn std::io::Write) -> Result<()> {
+        let opt = &self.options;
+        let lines = surface.screen_lines();
+        let mut palette = make_palette(opt, &lines);
+
+        let prefix = opt.settings.rendering.svg.id_prefix.as_str();
+
+        let shapes = trace_background(&opt.settings, &lines, surface.dimensions(), &mut palette)
+            .into_iter()
+            .map(|shape| json!({ "key": shape.key.render(prefix), "contours": shape.path }))
+            .collect::<Vec<_>>();
+
+        serde_json::to_writer(&mut *target, &shapes)?;
+        writeln!(target)?;
+
+        Ok(())
+    }
+
     /// Renders the given terminal surface to the specified target as an SVG.
     pub fn render(&self, surface: &Surface, target: &mut dyn std::io::Write) -> Result<()> {
+        self.render_animation(surface, &[], target)
+    }
+
+    /// Renders the given frames to the specified target as a gzip-compressed SVG (SVGZ).
+    pub fn render_animation_compressed(
+        &self,
+        surface: &Surface,
+        frames: &[Frame],
+        target: &mut dyn std::io::Write,
+    ) -> Result<()> {
+        let mut encoder = GzEncoder::new(target, Compression::best());
+        self.render_animation(surface, frames, &mut encoder)?;
+        encoder.finish()?;
+        Ok(())
+    }
+
+    /// Renders the given frames to the specified target as an animated SVG.
+    ///
+    /// Each frame only contains the rows that differ from the previous one and is revealed on top of it
+    /// at its time. The terminal surface provides the cursor and the rows to render if there are no frames.
+    pub fn render_animation(
+        &self,
+        surface: &Surface,
+        frames: &[Frame],
+        target: &mut dyn std::io::Write,
+    ) -> Result<()> {
         let opt = &self.options;
         let cfg = &opt.settings;
 
-        let bg = opt.bg();
-        let fg = opt.fg();
-
         let fp = cfg.rendering.svg.precision; // floating point precision
         let lh = cfg.rendering.line_height.r2p(fp); // line height in em
         let lh_p = (lh * opt.font.size).r2p(fp); // line height in pixels
@@ -62,21 +125,24 @@ impl SvgRenderer {
             (size.1 * opt.font.size).r2p(fp),
         );
         let pad = (cfg.padding.resolve() * opt.font.size).r2p(fp); // padding in pixels
-        let tyo = ((lh + opt.font.metrics.descender + opt.font.metrics.ascender) / 2.0).r2p(fp); // text y-offset in em
+        let tyo = ((lh + opt.font.metrics.descender + opt.font.metrics.ascender) / 2.0
+            + cfg.font.baseline_offset)
+            .r2p(fp); // text y-offset in em
 
-        let mut palette = PaletteBuilder::new(
-            bg.clone(),
-            fg.clone(),
-            opt.theme.clone(),
-            cfg.rendering.svg.var_palette,
-        );
+        let lines = surface.screen_lines();
+        let mut palette = make_palette(opt, &lines);
+        let classes = cfg.rendering.svg.color_classes;
+        let prefix = cfg.rendering.svg.id_prefix.as_str();
 
-        let background = element::Rectangle::new()
+        let mut background = element::Rectangle::new()
             .set("width", "100%")
-            .set("height", "100%")
-            .set("fill", palette.bg(ColorAttribute::Default));
-
-        let mut used_font_faces = HashSet::new();
+            .set("height", "100%");
+        assign_fill(
+            &mut background,
+            palette.bg(ColorAttribute::Default),
+            classes,
+            prefix,
+        );
 
         let mut group = element::Group::new();
 
@@ -85,68 +151,327 @@ impl SvgRenderer {
             group = group.set("font-weight", svg_weight(default_weight));
         }
 
-        let resolve_fg = |palette: &mut PaletteBuilder, attrs: &CellAttributes| {
-            let color = attrs.foreground();
-            if cfg.rendering.bold_is_bright && attrs.intensity() == Intensity::Bold {
-                palette.bright_fg(color)
-            } else {
-                palette.fg(color)
+        let make_bg_group = |lines: &[Cow<'_, Line>], palette: &mut PaletteBuilder| {
+            let shapes = trace_background(cfg, lines, dimensions, palette);
+
+            let mut bg_group = element::Group::new();
+            if let Some(stroke) = opt.settings.rendering.svg.stroke {
+                bg_group = bg_group.set("stroke-width", stroke.r2p(fp));
             }
-        };
 
-        let resolve_bg = |palette: &mut PaletteBuilder, attrs: &CellAttributes| {
-            if attrs.reverse() {
-                Some(resolve_fg(palette, attrs))
-            } else {
-                let bg = attrs.background();
-                if bg == ColorAttribute::Default {
-                    None
-                } else {
-                    Some(palette.bg(bg))
+            for shape in shapes {
+                let mut d = String::new();
+
+                for contour in &shape.path {
+                    if !d.is_empty() {
+                        d.push(' ');
+                    }
+
+                    build_svg_path(&mut d, contour, lh, fw, fp);
+                }
+
+                let color = shape.key;
+                let mut path = element::Path::new().set("d", d);
+                assign_fill(&mut path, color.clone(), classes, prefix);
+                if cfg.rendering.svg.stroke.is_some() {
+                    path = path.set("stroke", color.render(prefix));
                 }
+
+                bg_group = bg_group.add(path);
             }
+
+            bg_group
         };
 
-        let lines = surface.screen_lines();
+        let mut bg_container = container()
+            .set("viewBox", format!("0 0 {w} {h}", w = size.0, h = size.1))
+            .set("width", format!("{}", size_p.0))
+            .set("height", format!("{}", size_p.1));
+        if frames.is_empty() {
+            bg_container = bg_container.add(make_bg_group(&lines, &mut palette));
+        }
 
-        let shapes = super::tracing::trace(dimensions.0, dimensions.1, |x, y| {
-            resolve_bg(&mut palette, lines[y].get_cell(x)?.attrs())
-        });
+        if cfg.rendering.indent_guides
+            && let Some(guides) = make_indent_guides(opt, &lines, lh, fw)
+        {
+            bg_container = bg_container.add(
+                guides
+                    .set("stroke", palette.fg(ColorAttribute::Default).render(prefix))
+                    .set("stroke-opacity", INDENT_GUIDE_OPACITY),
+            );
+        }
 
-        let mut bg_group = element::Group::new();
-        if let Some(stroke) = opt.settings.rendering.svg.stroke {
-            bg_group = bg_group.set("stroke-width", stroke.r2p(fp));
+        let highlights = make_highlights(opt, dimensions, lh, fw);
+        if cfg.rendering.highlight_layer == HighlightLayer::Underlay
+            && let Some(highlights) = &highlights
+        {
+            bg_container = bg_container.add(highlights.clone());
         }
 
-        for shape in shapes {
-            let mut d = String::new();
+        group = group.add(bg_container);
 
-            for contour in &shape.path {
-                if !d.is_empty() {
-                    d.push(' ');
+        let mut rows = RowContext {
+            lh_p,
+            width_p: size_p.0,
+            tyo,
+            used_font_faces: BTreeSet::new(),
+            unresolved: IndexSet::new(),
+            linked: false,
+            blinking: false,
+        };
+
+        let animation = &cfg.rendering.svg.animation;
+        let speed = animation.speed.f32().max(f32::EPSILON);
+        let last = frames.last().map_or(0.0, |frame| frame.time as f32 / speed);
+        let duration = (last + ANIMATION_HOLD_DURATION).r2p(fp);
+        let repeat = if animation.r#loop { "indefinite" } else { "1" };
+
+        if frames.is_empty() {
+            self.add_rows(&mut rows, &mut group, &lines, &mut palette, prefix);
+        } else {
+            let mut previous: Option<&[Line]> = None;
+
+            for (index, frame) in frames.iter().enumerate() {
+                let changed = (0..dimensions.1)
+                    .map(|row| match previous {
+                        Some(previous) => !same_line(previous.get(row), frame.lines.get(row)),
+                        None => true,
+                    })
+                    .collect::<Vec<_>>();
+                if !changed.contains(&true) {
+                    continue;
                 }
 
-                build_svg_path(&mut d, contour, lh, fw, fp);
-            }
+                let lines = frame
+                    .lines
+                    .iter()
+                    .zip(&changed)
+                    .map(|(line, &changed)| {
+                        if changed {
+                            Cow::Borrowed(line)
+                        } else {
+                            Cow::Owned(Line::new(SEQ_ZERO))
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                let mut layer = element::Group::new().set("data-time", (frame.time as f32).r2p(fp));
+                let mut bg = container()
+                    .set("viewBox", format!("0 0 {w} {h}", w = size.0, h = size.1))
+                    .set("width", format!("{}", size_p.0))
+                    .set("height", format!("{}", size_p.1));
+
+                if previous.is_some() {
+                    let at = (frame.time as f32 / speed / duration).r2p(fp);
+                    layer = layer.add(reveal(at, duration, repeat));
+
+                    // Cover the changed rows of the previous frames.
+                    let mut row = 0;
+                    while row < changed.len() {
+                        let end = changed[row..]
+                            .iter()
+                            .position(|changed| !changed)
+                            .map_or(changed.len(), |n| row + n);
+                        if end > row {
+                            let mut cover = element::Rectangle::new()
+                                .set("y", (row as f32 * lh).r2p(fp))
+                                .set("width", size.0)
+                                .set("height", ((end - row) as f32 * lh).r2p(fp));
+                            assign_fill(
+                                &mut cover,
+                                palette.bg(ColorAttribute::Default),
+                                classes,
+                                prefix,
+                            );
+                            bg = bg.add(cover);
+                        }
+                        row = end + 1;
+                    }
+                }
+
+                layer = layer.add(bg.add(make_bg_group(&lines, &mut palette)));
+                self.add_rows(
+                    &mut rows,
+                    &mut layer,
+                    &lines,
+                    &mut palette,
+                    &format!("{prefix}frame-{index}-"),
+                );
+                group = group.add(layer);
 
-            let color = shape.key;
-            let mut path = element::Path::new().set("fill", color.clone()).set("d", d);
-            if cfg.rendering.svg.stroke.is_some() {
-                path = path.set("stroke", color);
+                previous = Some(&frame.lines);
             }
+        }
+
+        let RowContext {
+            used_font_faces,
+            unresolved,
+            mut linked,
+            blinking,
+            ..
+        } = rows;
 
-            bg_group = bg_group.add(path);
+        for ch in unresolved {
+            log::warn!("font not found for character {ch:2} ({ch:?})");
         }
 
-        group = group.add(
-            container()
+        if let Some(images) = make_images(opt, lh, fw) {
+            group = group.add(
+                container()
+                    .set("viewBox", format!("0 0 {w} {h}", w = size.0, h = size.1))
+                    .set("width", format!("{}", size_p.0))
+                    .set("height", format!("{}", size_p.1))
+                    .add(images),
+            );
+            linked = true;
+        }
+
+        if let Some(cursor) = make_cursor(opt, surface, &lines, lh, fw) {
+            let mut cursor = container()
                 .set("viewBox", format!("0 0 {w} {h}", w = size.0, h = size.1))
                 .set("width", format!("{}", size_p.0))
                 .set("height", format!("{}", size_p.1))
-                .add(bg_group),
+                .add(cursor);
+            if !frames.is_empty() {
+                // Only the final cursor position is known, so the cursor shows up with the last frame.
+                cursor = cursor.add(reveal((last / duration).r2p(fp), duration, repeat));
+            }
+            group = group.add(cursor);
+        }
+
+        if cfg.rendering.highlight_layer == HighlightLayer::Overlay
+            && let Some(highlights) = highlights
+        {
+            group = group.add(
+                container()
+                    .set("viewBox", format!("0 0 {w} {h}", w = size.0, h = size.1))
+                    .set("width", format!("{}", size_p.0))
+                    .set("height", format!("{}", size_p.1))
+                    .add(highlights),
+            );
+        }
+
+        let mut content = container()
+            .set("x", format!("{}", pad.left))
+            .set("y", format!("{}", pad.top));
+        assign_fill(
+            &mut content,
+            palette.fg(ColorAttribute::Default),
+            classes,
+            prefix,
         );
 
-        let mut unresolved = IndexSet::new();
+        // The text is shifted right by the width of the line number gutter.
+        let mut gutter = 0.0;
+        if let Some((numbers, cells)) = make_line_numbers(opt, &lines, lh, tyo) {
+            gutter = (cells as f32 * fw * opt.font.size).r2p(fp);
+            content = content
+                .add(numbers)
+                .add(container().set("x", format!("{gutter}")).add(group));
+        } else {
+            content = content.add(group);
+        }
+
+        if cfg.rendering.scrollbar && opt.scrollback != 0 {
+            let size_p = ((size_p.0 + gutter).r2p(fp), size_p.1);
+            content = content.add(make_scrollbar(opt, dimensions.1, size_p, pad.right.f32()));
+        }
+
+        let width = (size_p.0 + gutter + pad.left + pad.right).r2p(fp);
+        let height = (size_p.1 + pad.top + pad.bottom).r2p(fp);
+
+        let font_family_list = opt.font.family.join(", ");
+
+        let class = format!("{prefix}terminal");
+        let mut screen = element::SVG::new()
+            .set("width", format!("{width}"))
+            .set("height", format!("{height}"))
+            .set("font-size", opt.font.size.r2p(fp))
+            .set("font-family", font_family_list);
+        if !cfg.window.enabled {
+            if cfg.rendering.opacity.f32() < 1.0 {
+                screen = screen.add(
+                    element::Group::new()
+                        .set("opacity", cfg.rendering.opacity.r2p(fp))
+                        .add(background)
+                        .add(content),
+                );
+            } else {
+                screen = screen.add(background).add(content);
+            }
+        } else {
+            screen = screen.add(content);
+        }
+        screen = screen.set("class", class.as_str());
+
+        let mut doc = if cfg.window.enabled {
+            let mut screen = screen.set("y", opt.window.header.height.r2p(fp));
+            screen.unassign("xmlns");
+
+            make_window(opt, width, height, screen)
+        } else {
+            screen
+        };
+        if linked {
+            doc = doc.set("xmlns:xlink", "http://www.w3.org/1999/xlink");
+        }
+
+        let palette = if cfg.rendering.svg.color_classes {
+            palette.template_with_classes(&class)
+        } else {
+            palette.template(&class)
+        };
+        let mut ss = css::stylesheet(opt, Some(&palette), &used_font_faces)?;
+        if blinking {
+            if !ss.is_empty() {
+                ss += "\n";
+            }
+            ss += &css::blink(&cfg.rendering.svg.id_prefix)?;
+        }
+        if cfg.rendering.svg.minify {
+            ss = css::minify(&ss);
+        }
+
+        let style = element::Style::new(ss);
+        doc = doc.add(style);
+
+        if cfg.rendering.svg.embed_text {
+            let mut metadata = element::Element::new("metadata");
+            metadata.assign("xml:space", "preserve");
+            metadata.append(svg::node::Text::new(visible_text(surface)));
+            doc = doc.add(metadata);
+        }
+
+        if cfg.rendering.svg.minify {
+            let mut output = String::new();
+            write_minified(&doc, &mut output);
+            target.write_all(output.as_bytes())?;
+            return Ok(());
+        }
+
+        Ok(svg::write(target, &doc)?)
+    }
+
+    /// Adds the text of the non-blank rows to the group.
+    ///
+    /// Row ids are scoped by `ids` to keep them unique across animation frames.
+    fn add_rows(
+        &self,
+        rows: &mut RowContext,
+        group: &mut element::Group,
+        lines: &[Cow<'_, Line>],
+        palette: &mut PaletteBuilder,
+        ids: &str,
+    ) {
+        let opt = &self.options;
+        let cfg = &opt.settings;
+
+        let fp = cfg.rendering.svg.precision; // floating point precision
+        let fw = opt.font.metrics.width.r2p(fp); // font width in em
+        let (lh_p, width_p, tyo) = (rows.lh_p, rows.width_p, rows.tyo);
+        let classes = cfg.rendering.svg.color_classes;
+        let prefix = cfg.rendering.svg.id_prefix.as_str();
+        let default_weight = opt.font.weights.normal;
 
         for (row, line) in lines.iter().enumerate() {
             if line.is_whitespace() {
@@ -155,7 +480,7 @@ impl SvgRenderer {
 
             let mut sl = container()
                 .set("y", format!("{}", (row as f32 * lh_p).r2p(fp)))
-                .set("width", format!("{}", size_p.0))
+                .set("width", format!("{width_p}"))
                 .set("height", format!("{lh_p}"))
                 .set("overflow", "hidden");
 
@@ -164,19 +489,40 @@ impl SvgRenderer {
                 .set("xml:space", "preserve");
 
             let mut cursor = SpanCursor::new();
+            let mut word = None; // index and end cell of the last word
 
             for cluster in line.cluster(None) {
-                if cluster.text.trim().is_empty() {
+                // Concealed text is not drawn, only its background is.
+                if cluster.text.trim().is_empty() || cluster.attrs.invisible() {
                     continue;
                 }
 
-                for (text, mut range) in subdivide(line, &cluster, opt) {
+                let segments = subdivide(line, &cluster, opt).flat_map(|(text, range)| {
+                    if cfg.rendering.svg.word_ids {
+                        split_words(line, text, range)
+                    } else {
+                        vec![(text, range)]
+                    }
+                });
+
+                for (text, mut range) in segments {
                     if text.trim().is_empty() {
                         continue;
                     }
 
                     let mut span = element::TSpan::new(text);
 
+                    if cfg.rendering.svg.word_ids {
+                        // Segments adjacent to the previous one continue the same word.
+                        let index = match word {
+                            Some((index, end)) if end == range.start => index,
+                            Some((index, _)) => index + 1,
+                            None => 0,
+                        };
+                        span.assign("data-word", index);
+                        word = Some((index, range.end));
+                    }
+
                     let x = range.start;
                     let padding = cursor.padding(x);
                     if padding > 0 {
@@ -193,7 +539,14 @@ impl SvgRenderer {
                     let color = if cluster.attrs.reverse() {
                         palette.bg(cluster.attrs.background())
                     } else {
-                        resolve_fg(&mut palette, &cluster.attrs)
+                        resolve_fg(cfg, palette, &cluster.attrs)
+                    };
+
+                    let color = if let Some(min) = cfg.rendering.min_contrast {
+                        let bg = resolve_bg(cfg, palette, &cluster.attrs);
+                        palette.ensure_contrast(color, bg.as_ref(), min.f32())
+                    } else {
+                        color
                     };
 
                     if cluster.attrs.intensity() == Intensity::Half
@@ -203,7 +556,7 @@ impl SvgRenderer {
                     }
 
                     if color != ColorStyleId::DefaultForeground {
-                        span.assign("fill", color);
+                        assign_fill(&mut span, color.clone(), classes, prefix);
                     }
 
                     let (weight, style) = font_params(&cluster.attrs, opt);
@@ -212,20 +565,9 @@ impl SvgRenderer {
                         span.assign("font-weight", svg_weight(weight));
                     }
 
-                    match style {
-                        FontStyle::Normal => {}
-                        FontStyle::Italic => {
-                            span.assign("font-style", "italic");
-                        }
-                        FontStyle::Oblique => {
-                            span.assign("font-style", "oblique");
-                        }
-                    }
-
-                    if cluster.attrs.underline() != Underline::None {
-                        span.assign("text-decoration", "underline");
-                    } else if cluster.attrs.strikethrough() {
-                        span.assign("text-decoration", "line-through");
+                    let decoration = text_decoration(&cluster.attrs);
+                    if !decoration.is_empty() {
+                        span.assign("text-decoration", decoration);
                     }
 
                     if cluster.attrs.underline_color() != ColorAttribute::Default
@@ -249,14 +591,34 @@ impl SvgRenderer {
                         );
                     }
 
+                    if cfg.rendering.svg.blink {
+                        let class = match cluster.attrs.blink() {
+                            Blink::None => None,
+                            Blink::Slow => Some("blink"),
+                            Blink::Rapid => Some("blink-rapid"),
+                        };
+                        if let Some(class) = class {
+                            add_class(
+                                &mut span,
+                                &format!("{}{class}", cfg.rendering.svg.id_prefix),
+                            );
+                            rows.blinking = true;
+                        }
+                    }
+
                     let mut text_length_needed = false;
+                    let mut weight_fallback = false;
+                    let mut style_fallback = false;
 
                     for ch in text.chars() {
-                        if let Some(i) = find_matching_font(ch, weight, style, opt) {
+                        if let Some(m) = find_matching_font(ch, weight, style, opt) {
+                            let i = m.index;
+                            weight_fallback |= m.weight_fallback;
+                            style_fallback |= m.style_fallback;
                             log::trace!(
                                 "character {ch:>8?} with weight={weight:>8?} style={style:>8?}: requires font #{i:02}"
                             );
-                            if used_font_faces.insert(i) {
+                            if rows.used_font_faces.insert(i) {
                                 log::debug!(
                                     "using font face #{i:02} because it is required at least by character {ch:?} with weight={weight:?} style={style:?}",
                                 );
@@ -265,24 +627,75 @@ impl SvgRenderer {
                                 text_length_needed = true;
                             }
                         } else {
-                            unresolved.insert(ch);
+                            rows.unresolved.insert(ch);
                             text_length_needed = true;
                         }
                     }
 
-                    if text_length_needed {
-                        sl.append(tl);
-                        sl.append(
-                            element::Text::new("")
-                                .set("x", format!("{}em", (x as f32 * fw).r2p(fp)))
-                                .set("y", format!("{tyo}em"))
-                                .set("xml:space", "preserve")
-                                .set(
-                                    "textLength",
-                                    format!("{}em", (range.len() as f32 * fw).r2p(fp)),
-                                )
-                                .add(span),
+                    if cfg.rendering.synthetic_bold
+                        && weight_fallback
+                        && cluster.attrs.intensity() == Intensity::Bold
+                    {
+                        // Emulate bold by outlining glyphs with the fill color,
+                        // painting the outline under the fill to keep counters open.
+                        span.assign("paint-order", "stroke");
+                        span.assign("stroke", color.render(prefix));
+                        span.assign(
+                            "stroke-width",
+                            (SYNTHETIC_BOLD_STROKE_WIDTH * opt.font.size).r2p(fp),
                         );
+                    }
+
+                    // Emulate italic text by slanting the upright glyphs of the text element
+                    // around the start of its baseline, instead of letting the viewer do it.
+                    let oblique = cfg.rendering.svg.synthetic_oblique
+                        && style_fallback
+                        && style != FontStyle::Normal;
+
+                    match style {
+                        _ if oblique => {}
+                        FontStyle::Normal => {}
+                        FontStyle::Italic => {
+                            span.assign("font-style", "italic");
+                        }
+                        FontStyle::Oblique => {
+                            span.assign("font-style", "oblique");
+                        }
+                    }
+
+                    let span: Box<dyn Node> = match cluster.attrs.hyperlink() {
+                        Some(link) if cfg.rendering.svg.hyperlinks => {
+                            let mut anchor = element::Element::new("a");
+                            anchor.assign("xlink:href", link.uri());
+                            anchor.append(span);
+                            rows.linked = true;
+                            Box::new(anchor)
+                        }
+                        _ => Box::new(span),
+                    };
+
+                    if text_length_needed || oblique {
+                        if cfg.rendering.svg.merge_spans {
+                            merge_spans(&mut tl);
+                        }
+                        sl.append(tl);
+                        let tx = (x as f32 * fw).r2p(fp);
+                        let mut text = element::Text::new("")
+                            .set("x", format!("{tx}em"))
+                            .set("y", format!("{tyo}em"))
+                            .set("xml:space", "preserve");
+                        if text_length_needed {
+                            text = text.set(
+                                "textLength",
+                                format!("{}em", (range.len() as f32 * fw).r2p(fp)),
+                            );
+                        }
+                        if oblique {
+                            text = text
+                                .set("transform", format!("skewX({})", -SYNTHETIC_OBLIQUE_ANGLE))
+                                .set("transform-origin", format!("{tx}em {tyo}em"));
+                        }
+                        sl.append(text.add(span));
                         // Reset to 0 so space padding accounts for the full offset from
                         // the new text element's implicit x=0 start.
                         cursor.reset();
@@ -296,64 +709,105 @@ impl SvgRenderer {
                 }
             }
 
+            if cfg.rendering.svg.merge_spans {
+                merge_spans(&mut tl);
+            }
             sl = sl.add(tl);
-            group = group.add(sl);
-        }
-
-        for ch in unresolved {
-            log::warn!("font not found for character {ch:2} ({ch:?})");
+            if cfg.rendering.svg.row_ids {
+                group.append(
+                    element::Group::new()
+                        .set("id", format!("{ids}row-{row}"))
+                        .add(sl),
+                );
+            } else {
+                group.append(sl);
+            }
         }
+    }
+}
 
-        let content = container()
-            .set("x", format!("{}", pad.left))
-            .set("y", format!("{}", pad.top))
-            .set("fill", palette.fg(ColorAttribute::Default))
-            .add(group);
-
-        let width = (size_p.0 + pad.left + pad.right).r2p(fp);
-        let height = (size_p.1 + pad.top + pad.bottom).r2p(fp);
-
-        let font_family_list = opt.font.family.join(", ");
-
-        let class = "terminal";
-        let mut screen = element::SVG::new()
-            .set("width", format!("{width}"))
-            .set("height", format!("{height}"))
-            .set("font-size", opt.font.size.r2p(fp))
-            .set("font-family", font_family_list);
-        if !cfg.window.enabled {
-            screen = screen.add(background)
-        }
-        screen = screen.add(content).set("class", class);
+/// Creates an animation revealing the element at the given fraction of the animation duration in seconds.
+fn reveal(at: f32, duration: f32, repeat: &str) -> element::Animate {
+    element::Animate::new()
+        .set("attributeName", "visibility")
+        .set("values", "hidden;visible")
+        .set("keyTimes", format!("0;{at}"))
+        .set("dur", format!("{duration}s"))
+        .set("calcMode", "discrete")
+        .set("repeatCount", repeat)
+        .set("fill", "freeze")
+}
 
-        let mut doc = if cfg.window.enabled {
-            let mut screen = screen.set("y", opt.window.header.height.r2p(fp));
-            screen.unassign("xmlns");
+/// Text layout of the rows and the state collected while adding them.
+struct RowContext {
+    /// Line height in pixels.
+    lh_p: f32,
+    /// Row width in pixels.
+    width_p: f32,
+    /// Text y-offset in em.
+    tyo: f32,
+    used_font_faces: BTreeSet<usize>,
+    unresolved: IndexSet<char>,
+    linked: bool,
+    blinking: bool,
+}
 
-            make_window(opt, width, height, screen)
-        } else {
-            screen
-        };
+/// Serializes the node without whitespace between elements and without attributes having default values.
+///
+/// Text nodes are written as is, so the rendered result is the same as for the regular output.
+fn write_minified(node: &dyn Node, output: &mut String) {
+    let Some(attributes) = node.get_attributes() else {
+        output.push_str(&node.to_string());
+        return;
+    };
 
-        let mut ss = Default::default();
+    let name = node.get_name();
+    output.push('<');
+    output.push_str(name);
 
-        let palette = palette.template(class);
-        if !palette.vars.is_empty() {
-            ss = palette.render()?;
-        }
+    let mut attributes = attributes
+        .iter()
+        .filter(|(attr, value)| !is_default_attribute(name, attr, &value.to_string()))
+        .collect::<Vec<_>>();
+    attributes.sort_by_key(|(attr, _)| attr.as_str());
+    for (attr, value) in attributes {
+        output.push_str(&format!(" {attr}=\"{}\"", escape_attribute(value)));
+    }
 
-        let faces = collect_font_faces(opt, used_font_faces)?;
-        if !faces.is_empty() {
-            if !ss.is_empty() {
-                ss += "\n";
+    match node.get_children() {
+        Some(children) if !children.is_empty() => {
+            output.push('>');
+            for child in children {
+                write_minified(child.as_ref(), output);
             }
-            ss += &faces.join("\n");
+            output.push_str(&format!("</{name}>"));
         }
+        _ => output.push_str("/>"),
+    }
+}
 
-        let style = element::Style::new(ss);
-        doc = doc.add(style);
+/// Escapes the attribute value the same way the `svg` crate serializer does.
+fn escape_attribute(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(ch),
+        }
+    }
+    result
+}
 
-        Ok(svg::write(target, &doc)?)
+/// Checks whether the attribute value is the initial one, so the attribute can be omitted.
+fn is_default_attribute(element: &str, attr: &str, value: &str) -> bool {
+    match attr {
+        "x" | "y" => matches!(element, "svg" | "rect" | "image" | "use") && value == "0",
+        "opacity" | "fill-opacity" | "stroke-opacity" => value == "1",
+        _ => false,
     }
 }
 
@@ -390,6 +844,351 @@ fn container() -> element::SVG {
     container
 }
 
+/// Merges adjacent spans with identical attributes within a text element.
+///
+/// Spans inside a single text element flow one after another without explicit positions,
+/// so joining their text does not change the layout. Spans placed at explicit positions
+/// live in separate text elements and are never merged.
+fn merge_spans(text: &mut element::Text) {
+    let Some(children) = text.get_children_mut() else {
+        return;
+    };
+
+    let mut merged: Vec<Box<dyn Node>> = Vec::with_capacity(children.len());
+    for mut child in children.drain(..) {
+        if let Some(last) = merged.last_mut()
+            && last.get_name() == "tspan"
+            && child.get_name() == "tspan"
+            && last.get_attributes() == child.get_attributes()
+            && let Some(content) = child.get_children_mut()
+            && let Some(target) = last.get_children_mut()
+        {
+            target.append(content);
+            continue;
+        }
+        merged.push(child);
+    }
+
+    *children = merged;
+}
+
+/// Creates a scrollbar showing the position of the screen within the transcript.
+///
+/// The scrollbar is placed in the middle of the right padding if it fits there,
+/// otherwise it overlays the right edge of the screen.
+///
+/// # Arguments
+///
+/// * `opt` - Rendering options
+/// * `rows` - Number of rows on the screen
+/// * `size` - Screen size in pixels
+/// * `space` - Right padding in pixels
+fn make_scrollbar(opt: &Options, rows: usize, size: (f32, f32), space: f32) -> element::Group {
+    let fp = opt.settings.rendering.svg.precision;
+
+    let width = (SCROLLBAR_WIDTH * opt.font.size).r2p(fp);
+    let x = if space >= width {
+        size.0 + (space - width) / 2.0
+    } else {
+        size.0 - width
+    }
+    .r2p(fp);
+    let radius = (width / 2.0).r2p(fp);
+
+    // The screen always shows the end of the transcript.
+    let thumb = (size.1 * rows as f32 / (rows + opt.scrollback) as f32).r2p(fp);
+
+    let rect = |y: f32, height: f32, opacity: f32| {
+        element::Rectangle::new()
+            .set("x", x)
+            .set("y", y)
+            .set("width", width)
+            .set("height", height)
+            .set("rx", radius)
+            .set("opacity", opacity)
+    };
+
+    let y = (size.1 - thumb).r2p(fp);
+
+    element::Group::new()
+        .add(rect(0.0, size.1, SCROLLBAR_TRACK_OPACITY))
+        .add(rect(y, thumb, SCROLLBAR_THUMB_OPACITY))
+}
+
+/// Creates a right-aligned column of faint line numbers for the rows up to the last non-blank one.
+///
+/// Rows scrolled out of the screen are counted, so that the numbers match the position
+/// of the rows within the transcript.
+///
+/// # Arguments
+///
+/// * `opt` - Rendering options
+/// * `lines` - Screen lines
+/// * `lh` - Line height in em
+/// * `tyo` - Text y-offset in em
+///
+/// # Returns
+///
+/// The group with the line numbers and the width of the gutter in cells,
+/// or `None` if line numbers are disabled.
+fn make_line_numbers(
+    opt: &Options,
+    lines: &[Cow<'_, Line>],
+    lh: f32,
+    tyo: f32,
+) -> Option<(element::Group, usize)> {
+    let cfg = &opt.settings.rendering.line_numbers;
+    if !cfg.enabled {
+        return None;
+    }
+
+    let fp = opt.settings.rendering.svg.precision;
+    let rows = lines
+        .iter()
+        .rposition(|line| !line.is_whitespace())
+        .map_or(0, |row| row + 1);
+    let first = cfg.start + opt.scrollback;
+    let digits = (first + rows.saturating_sub(1)).to_string().len();
+    let x = (digits as f32 * opt.font.metrics.width).r2p(fp);
+
+    let mut group = element::Group::new()
+        .set("text-anchor", "end")
+        .set("opacity", LINE_NUMBER_OPACITY);
+    for row in 0..rows {
+        group = group.add(
+            element::Text::new((first + row).to_string())
+                .set("x", format!("{x}em"))
+                .set("y", format!("{}em", (row as f32 * lh + tyo).r2p(fp))),
+        );
+    }
+
+    // One more cell separates the numbers from the text.
+    Some((group, digits + 1))
+}
+
+/// Creates faint vertical guide lines at each indentation level of indented lines.
+///
+/// Guides are placed every `tab-width` columns within the leading whitespace of a logical line,
+/// continuation rows of wrapped lines do not get guides. Adjacent rows are joined into a single segment.
+///
+/// # Arguments
+///
+/// * `opt` - Rendering options
+/// * `lines` - Screen lines
+/// * `lh` - Line height in em
+/// * `fw` - Font width in em
+fn make_indent_guides(
+    opt: &Options,
+    lines: &[Cow<Line>],
+    lh: f32,
+    fw: f32,
+) -> Option<element::Path> {
+    let fp = opt.settings.rendering.svg.precision;
+    let step = opt.settings.terminal.tab_width.max(1);
+
+    // Rows covered by guides for each column.
+    let mut columns: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+
+    for (row, line) in lines.iter().enumerate() {
+        if line.is_whitespace() || (row != 0 && lines[row - 1].last_cell_was_wrapped()) {
+            continue;
+        }
+
+        let indent = line
+            .visible_cells()
+            .take_while(|cell| cell.str() == " ")
+            .count();
+
+        for column in (0..indent).step_by(step) {
+            columns.entry(column).or_default().push(row);
+        }
+    }
+
+    let mut d = String::new();
+    for (column, rows) in columns {
+        let x = ((column as f32 + 0.5) * fw).r2p(fp);
+        for run in rows.chunk_by(|a, b| a + 1 == *b) {
+            if !d.is_empty() {
+                d.push(' ');
+            }
+            let y0 = (run[0] as f32 * lh).r2p(fp);
+            let y1 = ((run[run.len() - 1] + 1) as f32 * lh).r2p(fp);
+            d.push_str(&format!("M{x},{y0} V{y1}"));
+        }
+    }
+
+    if d.is_empty() {
+        return None;
+    }
+
+    Some(
+        element::Path::new()
+            .set("fill", "none")
+            .set("stroke-width", (1.0 / opt.font.size).r2p(fp))
+            .set("d", d),
+    )
+}
+
+/// Creates translucent rectangles for the requested highlights.
+///
+/// Highlights outside of the screen are ignored, column ranges are clipped to the screen width.
+///
+/// # Arguments
+///
+/// * `opt` - Rendering options
+/// * `dimensions` - Screen dimensions in cells
+/// * `lh` - Line height in em
+/// * `fw` - Font width in em
+fn make_highlights(
+    opt: &Options,
+    dimensions: (usize, usize),
+    lh: f32,
+    fw: f32,
+) -> Option<element::Group> {
+    let fp = opt.settings.rendering.svg.precision;
+    let (cols, rows) = dimensions;
+
+    let mut group = element::Group::new();
+    let mut empty = true;
+
+    for highlight in opt.highlights.iter().filter(|h| h.row < rows) {
+        let columns = highlight.columns.clone().unwrap_or(0..cols);
+        let columns = columns.start.min(cols)..columns.end.min(cols);
+        if columns.is_empty() {
+            continue;
+        }
+
+        let fill = highlight
+            .color
+            .as_ref()
+            .map(|color| color.to_css_hex())
+            .unwrap_or_else(|| DEFAULT_HIGHLIGHT_COLOR.into());
+
+        group.append(
+            element::Rectangle::new()
+                .set("x", (columns.start as f32 * fw).r2p(fp))
+                .set("y", (highlight.row as f32 * lh).r2p(fp))
+                .set("width", (columns.len() as f32 * fw).r2p(fp))
+                .set("height", lh)
+                .set("fill", fill),
+        );
+        empty = false;
+    }
+
+    (!empty).then_some(group)
+}
+
+/// Creates the raster images placed over the screen cells.
+///
+/// Images are embedded as PNG data URIs and stretched to the cells they cover.
+fn make_images(opt: &Options, lh: f32, fw: f32) -> Option<element::Group> {
+    use base64::prelude::*;
+
+    if !opt.settings.rendering.svg.images || opt.images.is_empty() {
+        return None;
+    }
+
+    let fp = opt.settings.rendering.svg.precision;
+    let mut group = element::Group::new();
+
+    for image in &opt.images {
+        group.append(
+            element::Image::new()
+                .set("x", (image.column as f32 * fw).r2p(fp))
+                .set("y", (image.row as f32 * lh).r2p(fp))
+                .set("width", (image.size.0 * fw).r2p(fp))
+                .set("height", (image.size.1 * lh).r2p(fp))
+                .set("preserveAspectRatio", "none")
+                .set(
+                    "xlink:href",
+                    format!(
+                        "data:image/png;base64,{}",
+                        BASE64_STANDARD.encode(&image.png)
+                    ),
+                ),
+        );
+    }
+
+    Some(group)
+}
+
+/// Creates the cursor shape at the cursor position.
+///
+/// Nothing is drawn if the cursor is disabled, hidden by the program or outside of the screen.
+/// Without a configured color, the one set by the program or the theme is used, falling back to the foreground.
+///
+/// # Arguments
+///
+/// * `opt` - Rendering options
+/// * `surface` - Terminal surface
+/// * `lines` - Screen lines
+/// * `lh` - Line height in em
+/// * `fw` - Font width in em
+fn make_cursor(
+    opt: &Options,
+    surface: &Surface,
+    lines: &[Cow<Line>],
+    lh: f32,
+    fw: f32,
+) -> Option<element::Rectangle> {
+    let cfg = &opt.settings.rendering.cursor;
+    if !cfg.enabled || surface.cursor_visibility() == CursorVisibility::Hidden {
+        return None;
+    }
+
+    let fp = opt.settings.rendering.svg.precision;
+    let (cols, _) = surface.dimensions();
+    let (x, y) = surface.cursor_position();
+    let line = lines.get(y).filter(|_| x < cols)?;
+
+    // Cover both cells of a wide character.
+    let cells = line
+        .get_cell(x)
+        .map(|cell| cell.width())
+        .unwrap_or(1)
+        .max(1);
+
+    let left = x as f32 * fw;
+    let top = y as f32 * lh;
+    let (top, width, height) = match cfg.style {
+        CursorStyle::Block => (top, cells as f32 * fw, lh),
+        CursorStyle::Bar => (top, CURSOR_LINE_WIDTH, lh),
+        CursorStyle::Underline => (
+            top + lh - CURSOR_LINE_WIDTH,
+            cells as f32 * fw,
+            CURSOR_LINE_WIDTH,
+        ),
+    };
+
+    let mut cursor = element::Rectangle::new()
+        .set("x", left.r2p(fp))
+        .set("y", top.r2p(fp))
+        .set("width", width.r2p(fp))
+        .set("height", height.r2p(fp));
+
+    if cfg.style == CursorStyle::Block {
+        // Keep the text under the block readable.
+        cursor = cursor.set("opacity", CURSOR_BLOCK_OPACITY);
+    }
+
+    if let Some(color) = cfg.color.as_ref().or(opt.cursor()) {
+        cursor = cursor.set("fill", color.to_css_hex());
+    }
+
+    if cfg.blink {
+        cursor = cursor.add(
+            element::Animate::new()
+                .set("attributeName", "visibility")
+                .set("values", "visible;hidden")
+                .set("dur", CURSOR_BLINK_DURATION)
+                .set("calcMode", "discrete")
+                .set("repeatCount", "indefinite"),
+        );
+    }
+
+    Some(cursor)
+}
+
 /// Calculates available width for centered text by accounting for button extents.
 ///
 /// # Arguments
@@ -408,6 +1207,19 @@ fn calculate_available_width_for_centered_text(
     font_size: f32,
     fp: u8,
 ) -> f32 {
+    let (left_extent, right_extent) = calculate_button_extents(button_cfg, font_size, fp);
+    let max_extent: f32 = left_extent.max(right_extent);
+    (width - 2.0 * max_extent).max(0.0)
+}
+
+/// Calculates how far the buttons reach into the header from the left and right window edges.
+///
+/// Each extent includes a small margin so that text placed next to the buttons does not touch them.
+fn calculate_button_extents(
+    button_cfg: &crate::config::winstyle::WindowButtons,
+    font_size: f32,
+    fp: u8,
+) -> (f32, f32) {
     let mut left_extent: f32 = 0.0;
     let mut right_extent: f32 = 0.0;
     let button_size_px: f32 = button_cfg.size.f32().r2p(fp);
@@ -426,8 +1238,60 @@ fn calculate_available_width_for_centered_text(
         }
     }
 
-    let max_extent: f32 = left_extent.max(right_extent);
-    (width - 2.0 * max_extent).max(0.0)
+    (left_extent, right_extent)
+}
+
+/// Placement of the window title in the header.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TitlePlacement {
+    /// Horizontal position of the title anchor.
+    x: f32,
+    /// Width the title may occupy without overlapping the buttons.
+    available_width: f32,
+    /// Value of the `text-anchor` attribute.
+    anchor: &'static str,
+}
+
+/// Places the window title in the header according to the alignment.
+///
+/// A centered title keeps the same distance to both window edges, so the buttons on either side
+/// limit its width on both sides. A left or right aligned title starts at the `inset` from its edge
+/// or right after the buttons on that side, whichever is farther, and ends before the opposite side.
+fn place_title(
+    width: f32,
+    button_cfg: &crate::config::winstyle::WindowButtons,
+    font_size: f32,
+    align: TitleAlign,
+    inset: f32,
+    fp: u8,
+) -> TitlePlacement {
+    if align == TitleAlign::Center {
+        return TitlePlacement {
+            x: width / 2.0,
+            available_width: calculate_available_width_for_centered_text(
+                width, button_cfg, font_size, fp,
+            ),
+            anchor: "middle",
+        };
+    }
+
+    let (left_extent, right_extent) = calculate_button_extents(button_cfg, font_size, fp);
+    let start = left_extent.max(inset);
+    let end = (width - right_extent.max(inset)).max(start);
+    let available_width = end - start;
+
+    match align {
+        TitleAlign::Right => TitlePlacement {
+            x: end,
+            available_width,
+            anchor: "end",
+        },
+        _ => TitlePlacement {
+            x: start,
+            available_width,
+            anchor: "start",
+        },
+    }
 }
 
 /// Estimates the display width of a character for proportional fonts.
@@ -461,6 +1325,25 @@ fn estimate_char_width(ch: char) -> f32 {
 /// The original text if it fits, or a truncated version with ellipsis if it doesn't.
 /// Returns empty string if available_width is too small.
 fn trim_text_to_width(text: &str, available_width: f32, char_width: f32, ellipsis: &str) -> String {
+    trim_text_to_width_with(text, available_width, char_width, ellipsis, |ch| {
+        char_width * estimate_char_width(ch)
+    })
+}
+
+/// Trims text to fit within available width like [`trim_text_to_width`],
+/// measuring each character with the given function.
+///
+/// The `char_width` is still used for the safety gaps around the text.
+fn trim_text_to_width_with<F>(
+    text: &str,
+    available_width: f32,
+    char_width: f32,
+    ellipsis: &str,
+    measure: F,
+) -> String
+where
+    F: Fn(char) -> f32,
+{
     if available_width <= 0.0 || char_width <= 0.0 {
         return String::new();
     }
@@ -480,7 +1363,7 @@ fn trim_text_to_width(text: &str, available_width: f32, char_width: f32, ellipsi
     let mut fits_until = 0;
 
     for (i, &ch) in chars.iter().enumerate() {
-        let ch_width = char_width * estimate_char_width(ch);
+        let ch_width = measure(ch);
         if current_width + ch_width > usable_width {
             break;
         }
@@ -493,10 +1376,7 @@ fn trim_text_to_width(text: &str, available_width: f32, char_width: f32, ellipsi
     }
 
     // Calculate how much space the ellipsis takes
-    let ellipsis_width: f32 = ellipsis
-        .chars()
-        .map(|ch| char_width * estimate_char_width(ch))
-        .sum();
+    let ellipsis_width: f32 = ellipsis.chars().map(&measure).sum();
 
     if ellipsis_width > usable_width {
         return String::new();
@@ -508,7 +1388,7 @@ fn trim_text_to_width(text: &str, available_width: f32, char_width: f32, ellipsi
     let mut trim_count = 0;
 
     for &ch in chars.iter() {
-        let ch_width = char_width * estimate_char_width(ch);
+        let ch_width = measure(ch);
         if current_width + ch_width > available_for_text {
             break;
         }
@@ -524,6 +1404,205 @@ fn trim_text_to_width(text: &str, available_width: f32, char_width: f32, ellipsi
     }
 }
 
+/// Creates a palette builder for the given lines.
+///
+/// If the number of colors is limited, the true colors used in the lines are quantized.
+pub(super) fn make_palette(opt: &Options, lines: &[Cow<'_, Line>]) -> PaletteBuilder {
+    let cfg = &opt.settings;
+
+    let mut palette = PaletteBuilder::new(
+        opt.bg(),
+        opt.fg(),
+        opt.theme.clone(),
+        opt.palette.clone(),
+        cfg.rendering.svg.var_palette || cfg.rendering.svg.color_classes,
+        &cfg.rendering.svg.id_prefix,
+    );
+
+    if let Some(max) = cfg.rendering.max_colors {
+        let colors = lines
+            .iter()
+            .flat_map(|line| line.visible_cells())
+            .flat_map(|cell| [cell.attrs().foreground(), cell.attrs().background()])
+            .filter_map(|attr| match attr {
+                ColorAttribute::TrueColorWithDefaultFallback(c)
+                | ColorAttribute::TrueColorWithPaletteFallback(c, _) => Some(c),
+                _ => None,
+            });
+        palette.quantize(colors, max);
+    }
+
+    palette
+}
+
+/// Traces the shapes of the cell backgrounds painted with the same color.
+fn trace_background(
+    cfg: &Settings,
+    lines: &[Cow<'_, Line>],
+    dimensions: (usize, usize),
+    palette: &mut PaletteBuilder,
+) -> Vec<Shape<ColorStyle>> {
+    let connectivity = if cfg.rendering.svg.merge_diagonals {
+        Connectivity::Eight
+    } else {
+        Connectivity::Four
+    };
+
+    // Background extending from the end of each line to the right edge, if any.
+    let mut fills = vec![None; lines.len()];
+    if cfg.rendering.full_width_bg {
+        for (fill, line) in fills.iter_mut().zip(lines.iter()) {
+            let last = line
+                .visible_cells()
+                .filter(|cell| {
+                    let attrs = cell.attrs();
+                    cell.str() != " "
+                        || attrs.reverse()
+                        || attrs.background() != ColorAttribute::Default
+                })
+                .last();
+            if let Some(last) = last {
+                *fill = resolve_bg(cfg, palette, last.attrs())
+                    .map(|color| (last.cell_index() + last.width(), color));
+            }
+        }
+    }
+
+    super::tracing::trace(dimensions.0, dimensions.1, connectivity, |x, y| {
+        lines[y]
+            .get_cell(x)
+            .and_then(|cell| resolve_bg(cfg, palette, cell.attrs()))
+            .or_else(|| match &fills[y] {
+                Some((start, color)) if x >= *start => Some(color.clone()),
+                _ => None,
+            })
+    })
+}
+
+/// Resolves the text color style of a cell.
+pub(super) fn resolve_fg(
+    cfg: &Settings,
+    palette: &mut PaletteBuilder,
+    attrs: &CellAttributes,
+) -> ColorStyle {
+    let color = attrs.foreground();
+    if cfg.rendering.bold_is_bright && attrs.intensity() == Intensity::Bold {
+        palette.bright_fg(color)
+    } else {
+        palette.fg(color)
+    }
+}
+
+/// Resolves the background color style of a cell, if it has a visible background.
+pub(super) fn resolve_bg(
+    cfg: &Settings,
+    palette: &mut PaletteBuilder,
+    attrs: &CellAttributes,
+) -> Option<ColorStyle> {
+    if attrs.reverse() {
+        Some(resolve_fg(cfg, palette, attrs))
+    } else {
+        let bg = attrs.background();
+        if bg == ColorAttribute::Default {
+            None
+        } else {
+            Some(palette.bg(bg))
+        }
+    }
+}
+
+/// Adds a shadow layer of the window to the given group.
+///
+/// Drop shadows are blurred copies of the window shape grown by the spread,
+/// inset shadows are blurred frames around the window shape shrunk by the spread,
+/// clipped to the window.
+fn add_shadow(
+    window: element::Group,
+    opt: &Options,
+    index: usize,
+    layer: &WindowShadowLayer,
+    width: f32,
+    height: f32,
+) -> element::Group {
+    let fp = opt.settings.rendering.svg.precision; // floating point precision
+    let radius = opt.window.border.radius.resolve();
+    let prefix = &opt.settings.rendering.svg.id_prefix;
+    let id = match index {
+        0 => format!("{prefix}shadow"),
+        _ => format!("{prefix}shadow-{index}"),
+    };
+
+    let (x, y) = (layer.x.f32(), layer.y.f32());
+    let blur = layer.blur.f32();
+    let spread = layer.spread.f32();
+    let fill = layer.color.resolve(opt.mode).to_css_hex();
+
+    if !layer.inset {
+        // extent of the blurred rect beyond its bounds
+        let extent = spread + 3.0 * blur;
+
+        return window
+            .add(
+                element::Filter::new()
+                    .set("id", id.clone())
+                    .set("filterUnits", "userSpaceOnUse")
+                    .set("x", (x - extent).r2p(fp))
+                    .set("y", (y - extent).r2p(fp))
+                    .set("width", (width + 2.0 * extent).r2p(fp))
+                    .set("height", (height + 2.0 * extent).r2p(fp))
+                    .add(
+                        element::FilterEffectGaussianBlur::new().set("stdDeviation", blur.r2p(fp)),
+                    ),
+            )
+            .add({
+                let mut shape = rounded_rect(
+                    (x - spread, y - spread),
+                    (width + 2.0 * spread, height + 2.0 * spread),
+                    radius.map(|r| (r + spread).max(0.0)),
+                    fp,
+                );
+                shape.assign("fill", fill);
+                shape.assign("filter", format!("url(#{id})"));
+                shape
+            });
+    }
+
+    // thickness of the frame, enough to cover the window after the offset and blur
+    let m = (x.abs() + y.abs() + spread.abs() + 3.0 * blur).r2p(fp);
+    let (ox1, oy1, ox2, oy2) = (-m, -m, width + m, height + m).r2p(fp);
+    let (ix1, iy1) = (x + spread, y + spread).r2p(fp);
+    let (ix2, iy2) = (x + width - spread, y + height - spread).r2p(fp);
+    let d = format!("M{ox1},{oy1} H{ox2} V{oy2} H{ox1} Z M{ix1},{iy1} H{ix2} V{iy2} H{ix1} Z");
+
+    window
+        .add(
+            element::ClipPath::new()
+                .set("id", format!("{id}-clip"))
+                .add(rounded_rect((0.0, 0.0), (width, height), radius, fp)),
+        )
+        .add(
+            element::Filter::new()
+                .set("id", id.clone())
+                .set("filterUnits", "userSpaceOnUse")
+                .set("x", -m)
+                .set("y", -m)
+                .set("width", (width + 2.0 * m).r2p(fp))
+                .set("height", (height + 2.0 * m).r2p(fp))
+                .add(element::FilterEffectGaussianBlur::new().set("stdDeviation", blur.r2p(fp))),
+        )
+        .add(
+            element::Group::new()
+                .set("clip-path", format!("url(#{id}-clip)"))
+                .add(
+                    element::Path::new()
+                        .set("d", d)
+                        .set("fill", fill)
+                        .set("fill-rule", "evenodd")
+                        .set("filter", format!("url(#{id})")),
+                ),
+        )
+}
+
 /// Creates an SVG representation of a window with the given options.
 fn make_window(opt: &Options, width: f32, height: f32, screen: element::SVG) -> element::SVG {
     let cfg = &opt.settings;
@@ -536,71 +1615,43 @@ fn make_window(opt: &Options, width: f32, height: f32, screen: element::SVG) ->
         .r2p(fp); // margin in pixels
     let height = (height + opt.window.header.height).r2p(fp);
     let border = &opt.window.border;
+    let radius = border.radius.resolve();
 
     let mut window = element::Group::new().set(
         "transform",
         format!("translate({mx},{my})", mx = margin.left, my = margin.top),
     );
 
-    // shadow
-    if cfg.window.shadow && opt.window.shadow.enabled {
-        let shadow = &opt.window.shadow;
-        window = window
-            .add(
-                element::Filter::new()
-                    .set("id", "shadow")
-                    .set("filterUnits", "userSpaceOnUse")
-                    .set("x", "-32")
-                    .set("y", "-24")
-                    .set("width", (width + 72.0).r2p(fp))
-                    .set("height", (height + 72.0).r2p(fp))
-                    .add(
-                        element::FilterEffectGaussianBlur::new()
-                            .set("stdDeviation", shadow.blur.r2p(fp)),
-                    ),
-            )
-            .add(
-                element::Rectangle::new()
-                    .set("width", width)
-                    .set("height", height)
-                    .set("x", (shadow.x).r2p(fp))
-                    .set("y", (shadow.y).r2p(fp))
-                    .set("fill", shadow.color.resolve(opt.mode).to_css_hex())
-                    .set("rx", border.radius.r2p(fp))
-                    .set("ry", border.radius.r2p(fp))
-                    .set("filter", "url(#shadow)"),
-            )
+    // shadows
+    let shadows: Vec<_> = if cfg.window.shadow && opt.window.shadow.enabled {
+        opt.window.shadow.all_layers().collect()
+    } else {
+        Vec::new()
+    };
+    for (i, layer) in shadows.iter().enumerate().filter(|(_, layer)| !layer.inset) {
+        window = add_shadow(window, opt, i, layer, width, height);
     }
 
     // background
-    window = window.add(
-        element::Rectangle::new()
-            .set("fill", opt.bg().to_css_hex())
-            .set("rx", border.radius.r2p(fp))
-            .set("ry", border.radius.r2p(fp))
-            .set("width", width)
-            .set("height", height),
-    );
+    let mut background = rounded_rect((0.0, 0.0), (width, height), radius, fp);
+    background.assign("fill", opt.bg().to_css_hex());
+    window = window.add(background);
 
     // header
     let header = &opt.window.header;
+    let mut header_bg = rounded_rect((0.0, 0.0), (width, 2.0 * header.height.f32()), radius, fp);
+    header_bg.assign("fill", header.color.resolve(opt.mode).to_css_hex());
+    let clip = format!("{}header", opt.settings.rendering.svg.id_prefix);
+    header_bg.assign("clip-path", format!("url(#{clip})"));
     window = window
         .add(
-            element::ClipPath::new().set("id", "header").add(
+            element::ClipPath::new().set("id", clip).add(
                 element::Rectangle::new()
                     .set("width", width)
                     .set("height", header.height.r2p(fp)),
             ),
         )
-        .add(
-            element::Rectangle::new()
-                .set("fill", header.color.resolve(opt.mode).to_css_hex())
-                .set("rx", border.radius.r2p(fp))
-                .set("ry", border.radius.r2p(fp))
-                .set("width", width)
-                .set("height", 2.0 * header.height.r2p(fp))
-                .set("clip-path", "url(#header)"),
-        );
+        .add(header_bg);
     if let Some(border) = &header.border {
         window = window.add(
             element::Line::new()
@@ -613,27 +1664,47 @@ fn make_window(opt: &Options, width: f32, height: f32, screen: element::SVG) ->
         );
     }
 
+    // inset shadows
+    for (i, layer) in shadows.iter().enumerate().filter(|(_, layer)| layer.inset) {
+        window = add_shadow(window, opt, i, layer, width, height);
+    }
+
     let hh2 = (opt.window.header.height / 2.0).r2p(fp);
 
     // title
     if let Some(title) = &opt.title {
         let cfg = &opt.window.title;
-        let available_width = calculate_available_width_for_centered_text(
+        let placement = place_title(
             width,
             &opt.window.buttons,
             opt.font.size,
+            cfg.align,
+            hh2,
             fp,
         );
         let char_width: f32 = opt.font.size * opt.font.metrics.width;
-        let title = trim_text_to_width(title, available_width, char_width, "…");
+        let font_size = cfg.font.size.f32();
+        // Glyphs missing in the title font fall back to the estimated width.
+        let measure = |ch: char| match opt.title_advances.get(&ch) {
+            Some(advance) => advance * font_size,
+            None => char_width * estimate_char_width(ch),
+        };
+        let ellipsis = cfg.ellipsis.as_deref().unwrap_or(DEFAULT_TITLE_ELLIPSIS);
+        let title = trim_text_to_width_with(
+            title,
+            placement.available_width,
+            char_width,
+            ellipsis,
+            measure,
+        );
         if !title.is_empty() {
             let mut title_elem = element::Text::new(&title)
-                .set("x", (width / 2.0).r2p(fp))
+                .set("x", placement.x.r2p(fp))
                 .set("y", (hh2).r2p(fp))
                 .set("fill", cfg.color.resolve(opt.mode).to_css_hex())
                 .set("font-size", cfg.font.size.r2p(fp))
                 .set("font-family", cfg.font.family.join(", "))
-                .set("text-anchor", "middle")
+                .set("text-anchor", placement.anchor)
                 .set("dominant-baseline", "central");
             if let Some(weight) = &cfg.font.weight {
                 title_elem = title_elem.set("font-weight", weight.as_str())
@@ -649,30 +1720,25 @@ fn make_window(opt: &Options, width: f32, height: f32, screen: element::SVG) ->
     window = window.add(screen);
 
     // frame border
-    let gap = border.width + border.gap.unwrap_or_default();
-    window = window
-        .add(
-            element::Rectangle::new()
-                .set("width", (width + 0.0).r2p(fp))
-                .set("height", (height + 0.0).r2p(fp))
-                .set("fill", "none")
-                .set("stroke", border.colors.outer.resolve(opt.mode).to_css_hex())
-                .set("stroke-width", border.width.r2p(fp))
-                .set("rx", (border.radius + 0.0).r2p(fp))
-                .set("ry", (border.radius + 0.0).r2p(fp)),
-        )
-        .add(
-            element::Rectangle::new()
-                .set("width", (width - gap * 2.0).r2p(fp))
-                .set("height", (height - gap * 2.0).r2p(fp))
-                .set("x", gap.r2p(fp))
-                .set("y", gap.r2p(fp))
-                .set("fill", "none")
-                .set("stroke", border.colors.inner.resolve(opt.mode).to_css_hex())
-                .set("stroke-width", border.width.r2p(fp))
-                .set("rx", (border.radius - gap).r2p(fp))
-                .set("ry", (border.radius - gap).r2p(fp)),
-        );
+    let gap = (border.width + border.gap.unwrap_or_default()).f32();
+    let mut outer = rounded_rect((0.0, 0.0), (width, height), radius, fp);
+    outer.assign("fill", "none");
+    outer.assign("stroke", border.colors.outer.resolve(opt.mode).to_css_hex());
+    outer.assign("stroke-width", border.width.r2p(fp));
+    let mut inner = rounded_rect(
+        (gap, gap),
+        (width - gap * 2.0, height - gap * 2.0),
+        radius.map(|r| r - gap),
+        fp,
+    );
+    inner.assign("fill", "none");
+    inner.assign("stroke", border.colors.inner.resolve(opt.mode).to_css_hex());
+    inner.assign("stroke-width", border.width.r2p(fp));
+    window = window.add(outer).add(inner);
+
+    if cfg.rendering.opacity.f32() < 1.0 {
+        window.assign("opacity", cfg.rendering.opacity.r2p(fp));
+    }
 
     Document::new()
         .set("width", (width + margin.left + margin.right).r2p(fp))
@@ -680,6 +1746,54 @@ fn make_window(opt: &Options, width: f32, height: f32, screen: element::SVG) ->
         .add(window)
 }
 
+/// Creates a rectangle with rounded corners at the given position and of the given size.
+///
+/// The radii are given for the top-left, top-right, bottom-right and bottom-left corners.
+/// A `rect` element is used if they are all the same, otherwise a path with an arc per corner.
+fn rounded_rect(
+    (x, y): (f32, f32),
+    (width, height): (f32, f32),
+    radii: [f32; 4],
+    fp: u8,
+) -> element::Element {
+    if radii.iter().all(|r| *r == radii[0]) {
+        let mut rect = element::Element::new("rect");
+        rect.assign("width", width.r2p(fp));
+        rect.assign("height", height.r2p(fp));
+        rect.assign("rx", radii[0].r2p(fp));
+        rect.assign("ry", radii[0].r2p(fp));
+        if x != 0.0 || y != 0.0 {
+            rect.assign("x", x.r2p(fp));
+            rect.assign("y", y.r2p(fp));
+        }
+        return rect;
+    }
+
+    let max = (width.min(height) / 2.0).max(0.0);
+    let [tl, tr, br, bl] = radii.map(|r| r.clamp(0.0, max).r2p(fp));
+    let (x1, y1, x2, y2) = (x, y, x + width, y + height).r2p(fp);
+    let arc =
+        |r: f32, x: f32, y: f32| format!("A{r},{r} 0 0 1 {x},{y}", x = x.r2p(fp), y = y.r2p(fp));
+
+    let d = [
+        format!("M{},{y1}", (x1 + tl).r2p(fp)),
+        format!("H{}", (x2 - tr).r2p(fp)),
+        arc(tr, x2, y1 + tr),
+        format!("V{}", (y2 - br).r2p(fp)),
+        arc(br, x2 - br, y2),
+        format!("H{}", (x1 + bl).r2p(fp)),
+        arc(bl, x1, y2 - bl),
+        format!("V{}", (y1 + tl).r2p(fp)),
+        arc(tl, x1 + tl, y1),
+        "Z".into(),
+    ]
+    .join(" ");
+
+    let mut path = element::Element::new("path");
+    path.assign("d", d);
+    path
+}
+
 /// Creates the window buttons for the SVG representation.
 ///
 /// # Arguments
@@ -726,11 +1840,39 @@ fn make_buttons(opt: &Options, width: f32) -> element::Group {
                 set_button_style(opt, button, &mut shape);
                 group.append(shape);
             }
+            Some(WindowButtonShape::Triangle) => {
+                let mut shape = element::Path::new().set(
+                    "d",
+                    format!(
+                        "M{x},{y1} L{x2},{y2} L{x1},{y2} Z",
+                        x1 = (x - cfg.size / 2.0).r2p(fp),
+                        y1 = (y - cfg.size / 2.0).r2p(fp),
+                        x2 = (x + cfg.size / 2.0).r2p(fp),
+                        y2 = (y + cfg.size / 2.0).r2p(fp),
+                    ),
+                );
+                set_button_style(opt, button, &mut shape);
+                group.append(shape);
+            }
+            Some(WindowButtonShape::Diamond) => {
+                let mut shape = element::Path::new().set(
+                    "d",
+                    format!(
+                        "M{x},{y1} L{x2},{y} L{x},{y2} L{x1},{y} Z",
+                        x1 = (x - cfg.size / 2.0).r2p(fp),
+                        y1 = (y - cfg.size / 2.0).r2p(fp),
+                        x2 = (x + cfg.size / 2.0).r2p(fp),
+                        y2 = (y + cfg.size / 2.0).r2p(fp),
+                    ),
+                );
+                set_button_style(opt, button, &mut shape);
+                group.append(shape);
+            }
             None => {}
         }
 
         if let Some(icon) = &button.icon {
-            let mut path = match icon.kind {
+            let mut path = match &icon.kind {
                 WindowButtonIconKind::Close => element::Path::new().set(
                     "d",
                     format!(
@@ -763,6 +1905,18 @@ fn make_buttons(opt: &Options, width: f32) -> element::Group {
 
                     element::Path::new().set("d",format!("M{x2},{y1} L{x3},{y1} Q{x4},{y1},{x4},{y2} L{x4},{y3} Q{x4},{y4},{x3},{y4} L{x2},{y4} Q{x1},{y4},{x1},{y3} L{x1},{y2} Q{x1},{y1},{x2},{y1}"))
                 }
+                WindowButtonIconKind::Custom(d) => element::Path::new()
+                    .set("d", d.as_str())
+                    .set(
+                        "transform",
+                        format!(
+                            "translate({x1},{y1}) scale({size})",
+                            x1 = (x - icon.size / 2.0).r2p(fp),
+                            y1 = (y - icon.size / 2.0).r2p(fp),
+                            size = icon.size.r2p(fp),
+                        ),
+                    )
+                    .set("vector-effect", "non-scaling-stroke"),
             };
 
             path.assign("fill", "none");
@@ -813,55 +1967,6 @@ fn set_button_style<N: svg::Node>(opt: &Options, cfg: &WindowButton, node: &mut
     }
 }
 
-/// Collects the font faces used in the SVG representation.
-///
-/// # Arguments
-///
-/// * `opt` - A reference to the `Options` struct containing configuration settings.
-/// * `used_font_faces` - A `HashSet` containing the indices of the used font faces.
-///
-/// # Returns
-///
-/// A `Result` containing a vector of strings representing the font faces.
-fn collect_font_faces(opt: &Options, used_font_faces: HashSet<usize>) -> Result<Vec<String>> {
-    let faces = &opt
-        .font
-        .faces
-        .iter()
-        .enumerate()
-        .filter(|(i, _)| used_font_faces.contains(i))
-        .map(|(_, face)| styles::FontFace {
-            font_family: face.family.clone(),
-            font_weight: match face.weight {
-                FontWeight::Normal => "normal".into(),
-                FontWeight::Bold => "bold".into(),
-                FontWeight::Fixed(w) => w.to_string(),
-                FontWeight::Variable(min, max) => {
-                    format!("{min} {max}", min = f32::from(min), max = f32::from(max))
-                }
-            },
-            font_style: face.style.map(|style| match style {
-                FontStyle::Normal => "normal".into(),
-                FontStyle::Italic => "italic".into(),
-                FontStyle::Oblique => "oblique".into(),
-            }),
-            src_url: face.url.to_string(),
-            format: face.format.map(|f| f.css()),
-        })
-        .collect::<Vec<_>>();
-
-    let faces = faces
-        .iter()
-        .map(|face| {
-            face.render()
-                .map_err(Into::into)
-                .map(|x| x.trim().to_owned())
-        })
-        .collect::<Result<Vec<_>>>()?;
-
-    Ok(faces)
-}
-
 impl Render for SvgRenderer {
     fn render(&self, surface: &Surface, target: &mut dyn std::io::Write) -> Result<()> {
         Self::render(self, surface, target)
@@ -955,6 +2060,57 @@ impl RoundToPrecision for Padding {
 
 // ---
 
+/// Stroke width in em used to emulate bold text when no bold font face is available.
+const SYNTHETIC_BOLD_STROKE_WIDTH: f32 = 0.04;
+
+/// Slant angle in degrees used to emulate italic text when no italic font face is available.
+const SYNTHETIC_OBLIQUE_ANGLE: f32 = 12.0;
+
+/// Fill color of highlights without an explicit color.
+const DEFAULT_HIGHLIGHT_COLOR: &str = "#ffd70059";
+
+/// Opacity of the indent guide lines.
+const INDENT_GUIDE_OPACITY: f32 = 0.2;
+
+/// Opacity of the line numbers.
+const LINE_NUMBER_OPACITY: f32 = 0.5;
+
+/// Scrollbar width in em.
+const SCROLLBAR_WIDTH: f32 = 0.3;
+/// Opacity of the scrollbar track.
+const SCROLLBAR_TRACK_OPACITY: f32 = 0.1;
+/// Opacity of the scrollbar thumb.
+const SCROLLBAR_THUMB_OPACITY: f32 = 0.4;
+
+/// Marker appended to a trimmed window title by default.
+const DEFAULT_TITLE_ELLIPSIS: &str = "…";
+/// Thickness of the bar and underline cursor shapes in em.
+const CURSOR_LINE_WIDTH: f32 = 0.1;
+/// Opacity of the block cursor shape.
+const CURSOR_BLOCK_OPACITY: f32 = 0.5;
+/// Blink cycle duration of the cursor.
+const CURSOR_BLINK_DURATION: &str = "1s";
+/// Time in seconds the last frame of an animation is shown before it restarts.
+const ANIMATION_HOLD_DURATION: f32 = 1.0;
+
+/// Checks whether two screen rows have the same text and attributes, missing rows are considered blank.
+fn same_line(a: Option<&Line>, b: Option<&Line>) -> bool {
+    let cells = |line: Option<&Line>| {
+        line.into_iter()
+            .flat_map(|line| line.visible_cells())
+            .filter(|cell| cell.str() != " " || cell.attrs() != &CellAttributes::default())
+            .map(|cell| {
+                (
+                    cell.cell_index(),
+                    cell.str().to_owned(),
+                    cell.attrs().clone(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    cells(a) == cells(b)
+}
+
 /// Determines the font weight and style based on cell attributes.
 ///
 /// # Arguments
@@ -965,7 +2121,7 @@ impl RoundToPrecision for Padding {
 /// # Returns
 ///
 /// A tuple containing the font weight and style.
-fn font_params(attrs: &CellAttributes, opt: &Options) -> (FontWeight, FontStyle) {
+pub(super) fn font_params(attrs: &CellAttributes, opt: &Options) -> (FontWeight, FontStyle) {
     let weight = match attrs.intensity() {
         Intensity::Normal => opt.font.weights.normal,
         Intensity::Bold => opt.font.weights.bold,
@@ -992,34 +2148,89 @@ fn font_params(attrs: &CellAttributes, opt: &Options) -> (FontWeight, FontStyle)
 ///
 /// # Returns
 ///
-/// An `Option` containing the index of the matching font face, or `None` if no match is found.
+/// An `Option` containing the matching font face, or `None` if no match is found.
 fn find_matching_font(
     ch: char,
     weight: FontWeight,
     style: FontStyle,
     opt: &Options,
-) -> Option<usize> {
+) -> Option<FontMatch> {
     for (i, font) in opt.font.faces.iter().enumerate().rev() {
         if match_font_face(font, Some(weight), Some(style), ch) {
-            return Some(i);
+            return Some(FontMatch::exact(i));
         }
     }
 
     for (i, font) in opt.font.faces.iter().enumerate().rev() {
         if match_font_face(font, None, Some(style), ch) {
-            return Some(i);
+            return Some(FontMatch::weight_fallback(i));
+        }
+    }
+
+    for (i, font) in opt.font.faces.iter().enumerate().rev() {
+        if match_font_face(font, Some(weight), None, ch) {
+            return Some(FontMatch::style_fallback(i));
         }
     }
 
     for (i, font) in opt.font.faces.iter().enumerate().rev() {
         if match_font_face(font, None, None, ch) {
-            return Some(i);
+            return Some(FontMatch::fallback(i));
         }
     }
 
     None
 }
 
+/// Result of a font face lookup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FontMatch {
+    /// Index of the matching font face.
+    index: usize,
+    /// Whether the font face was selected without matching the requested weight.
+    weight_fallback: bool,
+    /// Whether the font face was selected without matching the requested style.
+    style_fallback: bool,
+}
+
+impl FontMatch {
+    /// Creates a match for a font face that satisfies the requested weight.
+    fn exact(index: usize) -> Self {
+        Self {
+            index,
+            weight_fallback: false,
+            style_fallback: false,
+        }
+    }
+
+    /// Creates a match for a font face that does not satisfy the requested weight.
+    fn weight_fallback(index: usize) -> Self {
+        Self {
+            index,
+            weight_fallback: true,
+            style_fallback: false,
+        }
+    }
+
+    /// Creates a match for a font face that satisfies the requested weight but not the style.
+    fn style_fallback(index: usize) -> Self {
+        Self {
+            index,
+            weight_fallback: false,
+            style_fallback: true,
+        }
+    }
+
+    /// Creates a match for a font face that satisfies neither the requested style nor weight.
+    fn fallback(index: usize) -> Self {
+        Self {
+            index,
+            weight_fallback: true,
+            style_fallback: true,
+        }
+    }
+}
+
 // ---
 
 /// Subdivides a cell cluster into subclusters based on font parameters.
@@ -1033,7 +2244,11 @@ fn find_matching_font(
 /// # Returns
 ///
 /// A `Subclusters` iterator for iterating over the subclusters.
-fn subdivide<'a>(line: &'a Line, cluster: &'a CellCluster, opt: &'a Options) -> Subclusters<'a> {
+pub(super) fn subdivide<'a>(
+    line: &'a Line,
+    cluster: &'a CellCluster,
+    opt: &'a Options,
+) -> Subclusters<'a> {
     let (weight, style) = font_params(&cluster.attrs, opt);
 
     Subclusters {
@@ -1050,8 +2265,48 @@ fn subdivide<'a>(line: &'a Line, cluster: &'a CellCluster, opt: &'a Options) ->
     }
 }
 
+/// Splits a segment of text occupying the given cell range of the line into words.
+///
+/// Whitespace between the words is dropped, each word is returned with its own cell range.
+fn split_words<'a>(
+    line: &Line,
+    text: &'a str,
+    range: Range<usize>,
+) -> Vec<(&'a str, Range<usize>)> {
+    let mut words = Vec::new();
+    let mut start: Option<(usize, usize)> = None; // byte offset and cell index of the current word
+    let mut offset = 0;
+    let mut x = range.start;
+
+    while x < range.end && offset < text.len() {
+        let Some(cell) = line.get_cell(x) else {
+            break;
+        };
+        let len = cell.str().len();
+        let blank = cell.str().trim().is_empty();
+
+        match (start, blank) {
+            (None, false) => start = Some((offset, x)),
+            (Some((o, c)), true) => {
+                words.push((&text[o..offset], c..x));
+                start = None;
+            }
+            _ => {}
+        }
+
+        offset += len;
+        x += cell.width().max(1);
+    }
+
+    if let Some((o, c)) = start {
+        words.push((&text[o..], c..range.end));
+    }
+
+    words
+}
+
 /// An iterator for iterating over subclusters of a cell cluster.
-struct Subclusters<'a> {
+pub(super) struct Subclusters<'a> {
     line: &'a Line,
     cluster: &'a CellCluster,
     opt: &'a Options,
@@ -1124,7 +2379,9 @@ impl<'a> Iterator for Subclusters<'a> {
             };
 
             let ch = next.str().chars().next();
-            let font = ch.and_then(|ch| find_matching_font(ch, self.weight, self.style, self.opt));
+            let font = ch
+                .and_then(|ch| find_matching_font(ch, self.weight, self.style, self.opt))
+                .map(|m| m.index);
             let old_font = std::mem::replace(&mut self.font, font);
 
             let old_mm = old_font
@@ -1191,15 +2448,18 @@ where
 
 // ---
 
-struct PaletteBuilder {
+pub(super) struct PaletteBuilder {
     bg: Color,
     fg: Color,
     theme: Rc<Theme>,
+    overrides: BTreeMap<u8, Color>,
     has_bg: bool,
     has_fg: bool,
     has_br_fg: bool,
     palette: BTreeMap<u8, Color>,
     var_palette: bool,
+    prefix: String,
+    quantized: HashMap<quantize::Rgba, Color>,
 }
 
 impl PaletteBuilder {
@@ -1210,17 +2470,29 @@ impl PaletteBuilder {
     /// * `bg` - The background color.
     /// * `fg` - The foreground color.
     /// * `theme` - The theme to use.
+    /// * `overrides` - Palette colors set by the program, taking precedence over the theme.
     /// * `var_palette` - Whether to use a variable palette.
-    fn new(bg: Color, fg: Color, theme: Rc<Theme>, var_palette: bool) -> Self {
+    /// * `prefix` - The prefix of the CSS variable names.
+    fn new(
+        bg: Color,
+        fg: Color,
+        theme: Rc<Theme>,
+        overrides: BTreeMap<u8, Color>,
+        var_palette: bool,
+        prefix: &str,
+    ) -> Self {
         Self {
             bg,
             fg,
             theme,
+            overrides,
             has_bg: false,
             has_fg: false,
             has_br_fg: false,
             palette: BTreeMap::new(),
             var_palette,
+            prefix: prefix.into(),
+            quantized: HashMap::new(),
         }
     }
 
@@ -1233,7 +2505,7 @@ impl PaletteBuilder {
     /// # Returns
     ///
     /// The resolved background color style.
-    fn bg(&mut self, attr: ColorAttribute) -> ColorStyle {
+    pub(super) fn bg(&mut self, attr: ColorAttribute) -> ColorStyle {
         match attr {
             ColorAttribute::Default => {
                 if !self.var_palette {
@@ -1244,7 +2516,7 @@ impl PaletteBuilder {
             }
             ColorAttribute::PaletteIndex(i) => {
                 let bg = self.bg.clone();
-                let color = || self.theme.resolve(attr).unwrap_or(bg);
+                let color = || self.resolve(attr).unwrap_or(bg);
                 if !self.var_palette {
                     return ColorStyle::Custom(color());
                 }
@@ -1252,7 +2524,7 @@ impl PaletteBuilder {
                 ColorStyleId::Palette(i).into()
             }
             ColorAttribute::TrueColorWithDefaultFallback(c)
-            | ColorAttribute::TrueColorWithPaletteFallback(c, _) => Self::custom(c),
+            | ColorAttribute::TrueColorWithPaletteFallback(c, _) => self.custom(c),
         }
     }
 
@@ -1265,7 +2537,7 @@ impl PaletteBuilder {
     /// # Returns
     ///
     /// The resolved foreground color style.
-    fn fg(&mut self, attr: ColorAttribute) -> ColorStyle {
+    pub(super) fn fg(&mut self, attr: ColorAttribute) -> ColorStyle {
         match attr {
             ColorAttribute::Default => {
                 if !self.var_palette {
@@ -1276,7 +2548,7 @@ impl PaletteBuilder {
             }
             ColorAttribute::PaletteIndex(i) => {
                 let fg = self.fg.clone();
-                let color = || self.theme.resolve(attr).unwrap_or(fg);
+                let color = || self.resolve(attr).unwrap_or(fg);
                 if !self.var_palette {
                     return ColorStyle::Custom(color());
                 }
@@ -1284,10 +2556,20 @@ impl PaletteBuilder {
                 ColorStyleId::Palette(i).into()
             }
             ColorAttribute::TrueColorWithDefaultFallback(c)
-            | ColorAttribute::TrueColorWithPaletteFallback(c, _) => Self::custom(c),
+            | ColorAttribute::TrueColorWithPaletteFallback(c, _) => self.custom(c),
         }
     }
 
+    /// Resolves the color attribute, preferring the palette overrides over the theme.
+    fn resolve(&self, attr: ColorAttribute) -> Option<Color> {
+        if let ColorAttribute::PaletteIndex(i) = attr
+            && let Some(color) = self.overrides.get(&i)
+        {
+            return Some(color.clone());
+        }
+        self.theme.resolve(attr)
+    }
+
     /// Resolves the bright foreground color style and adds it to the palette.
     ///
     /// # Arguments
@@ -1323,23 +2605,23 @@ impl PaletteBuilder {
     /// # Returns
     ///
     /// The generated CSS template.
-    fn template(&self, name: &str) -> styles::Theme {
+    pub(super) fn template(&self, name: &str) -> css::Theme {
         let mut vars = Vec::new();
         if self.has_bg {
             vars.push((
-                ColorStyleId::DefaultBackground.name().into(),
+                ColorStyleId::DefaultBackground.name(&self.prefix),
                 self.bg.to_css_hex(),
             ));
         }
         if self.has_fg {
             vars.push((
-                ColorStyleId::DefaultForeground.name().into(),
+                ColorStyleId::DefaultForeground.name(&self.prefix),
                 self.fg.to_css_hex(),
             ));
         }
         if self.has_br_fg {
             vars.push((
-                ColorStyleId::BrightForeground.name().into(),
+                ColorStyleId::BrightForeground.name(&self.prefix),
                 self.theme
                     .bright_fg
                     .as_ref()
@@ -1348,15 +2630,60 @@ impl PaletteBuilder {
             ));
         }
         for (i, color) in &self.palette {
-            vars.push((ColorStyleId::Palette(*i).name().into(), color.to_css_hex()));
+            vars.push((
+                ColorStyleId::Palette(*i).name(&self.prefix),
+                color.to_css_hex(),
+            ));
         }
 
-        styles::Theme {
+        css::Theme {
             name: name.into(),
             vars,
+            classes: Vec::new(),
         }
     }
 
+    /// Generates the CSS template with the color variables and a class rule for each of them.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the theme.
+    ///
+    /// # Returns
+    ///
+    /// The generated CSS template.
+    fn template_with_classes(&self, name: &str) -> css::Theme {
+        let mut theme = self.template(name);
+        theme.classes = theme
+            .vars
+            .iter()
+            .map(|(var, _)| {
+                (
+                    var.trim_start_matches("--").to_owned(),
+                    format!("var({var})"),
+                )
+            })
+            .collect();
+        theme
+    }
+
+    /// Limits the number of distinct true colors to `max`.
+    ///
+    /// Each of the given colors is replaced by a representative color found
+    /// using the median cut algorithm whenever it is resolved later on.
+    ///
+    /// # Arguments
+    ///
+    /// * `colors` - The true colors used in the document.
+    /// * `max` - The maximum number of distinct true colors.
+    fn quantize(&mut self, colors: impl IntoIterator<Item = SrgbaTuple>, max: usize) {
+        let colors = colors.into_iter().map(|c| c.as_rgba_u8().into());
+        self.quantized = quantize::median_cut(colors, max)
+            .into_iter()
+            .map(|(color, [r, g, b, a])| (color, Color::from_rgba8(r, g, b, a)))
+            .collect();
+    }
+
     /// Creates a custom color style.
     ///
     /// # Arguments
@@ -1365,16 +2692,130 @@ impl PaletteBuilder {
     ///
     /// # Returns
     ///
-    /// The custom color style.
-    fn custom(c: SrgbaTuple) -> ColorStyle {
-        ColorStyle::Custom(Color::new(c.0, c.1, c.2, c.3))
+    /// The custom color style, using the representative color if the colors are quantized.
+    fn custom(&self, c: SrgbaTuple) -> ColorStyle {
+        let rgba: quantize::Rgba = c.as_rgba_u8().into();
+        match self.quantized.get(&rgba) {
+            Some(color) => ColorStyle::Custom(color.clone()),
+            None => ColorStyle::Custom(Color::new(c.0, c.1, c.2, c.3)),
+        }
+    }
+
+    /// Resolves a color style previously produced by this builder to its actual color.
+    ///
+    /// # Arguments
+    ///
+    /// * `style` - The color style.
+    ///
+    /// # Returns
+    ///
+    /// The actual color.
+    fn color(&self, style: &ColorStyle) -> Color {
+        match style {
+            ColorStyle::Custom(color) => color.clone(),
+            ColorStyle::Themed(ColorStyleId::DefaultBackground) => self.bg.clone(),
+            ColorStyle::Themed(ColorStyleId::DefaultForeground) => self.fg.clone(),
+            ColorStyle::Themed(ColorStyleId::BrightForeground) => {
+                self.theme.bright_fg.as_ref().unwrap_or(&self.fg).clone()
+            }
+            ColorStyle::Themed(ColorStyleId::Palette(i)) => {
+                self.palette.get(i).unwrap_or(&self.fg).clone()
+            }
+        }
+    }
+
+    /// Ensures the foreground color has at least the given contrast ratio against the background.
+    ///
+    /// # Arguments
+    ///
+    /// * `fg` - The foreground color style.
+    /// * `bg` - The background color style, or `None` for the default background.
+    /// * `min` - The minimum contrast ratio.
+    ///
+    /// # Returns
+    ///
+    /// The original foreground color style if the contrast is sufficient,
+    /// or a custom color style with adjusted lightness otherwise.
+    pub(super) fn ensure_contrast(
+        &self,
+        fg: ColorStyle,
+        bg: Option<&ColorStyle>,
+        min: f32,
+    ) -> ColorStyle {
+        let fg_color = self.color(&fg);
+        let bg_color = bg
+            .map(|bg| self.color(bg))
+            .unwrap_or_else(|| self.bg.clone());
+
+        if contrast_ratio(&fg_color, &bg_color) >= min {
+            return fg;
+        }
+
+        ColorStyle::Custom(adjust_contrast(&fg_color, &bg_color, min))
+    }
+}
+
+/// Calculates the relative luminance of a color as defined by WCAG.
+fn relative_luminance(color: &Color) -> f32 {
+    let linear = |c: f32| {
+        let c = c.clamp(0.0, 1.0);
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    0.2126 * linear(color.r) + 0.7152 * linear(color.g) + 0.0722 * linear(color.b)
+}
+
+/// Calculates the contrast ratio between two colors as defined by WCAG.
+///
+/// The result is in range from 1 (no contrast) to 21 (black on white).
+fn contrast_ratio(a: &Color, b: &Color) -> f32 {
+    let a = relative_luminance(a);
+    let b = relative_luminance(b);
+    (a.max(b) + 0.05) / (a.min(b) + 0.05)
+}
+
+/// Adjusts the lightness of the foreground color to reach the minimum contrast ratio
+/// against the background with the smallest possible change.
+///
+/// The lightness is increased for dark backgrounds and decreased for light backgrounds.
+/// If the ratio cannot be reached, the lightness is pushed to its limit.
+fn adjust_contrast(fg: &Color, bg: &Color, min: f32) -> Color {
+    let [h, s, l, a] = fg.to_hsla();
+    let with_lightness = |l: f32| Color::from_hsla(h, s, l, a);
+
+    let white = Color::new(1.0, 1.0, 1.0, 1.0);
+    let black = Color::new(0.0, 0.0, 0.0, 1.0);
+    let mut near = l;
+    let mut far = if contrast_ratio(&white, bg) >= contrast_ratio(&black, bg) {
+        1.0
+    } else {
+        0.0
+    };
+
+    if contrast_ratio(&with_lightness(far), bg) < min {
+        return with_lightness(far);
+    }
+
+    for _ in 0..16 {
+        let mid = (near + far) / 2.0;
+        if contrast_ratio(&with_lightness(mid), bg) >= min {
+            far = mid;
+        } else {
+            near = mid;
+        }
     }
+
+    with_lightness(far)
 }
 
 // ---
 
 #[derive(Debug, Clone, PartialEq)]
-enum ColorStyle {
+pub(super) enum ColorStyle {
     Themed(ColorStyleId),
     Custom(Color),
 }
@@ -1382,12 +2823,16 @@ enum ColorStyle {
 impl ColorStyle {
     /// Renders the color style as a string.
     ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix of the CSS variable names.
+    ///
     /// # Returns
     ///
     /// The rendered color style.
-    fn render(&self) -> Cow<'static, str> {
+    pub(super) fn render(&self, prefix: &str) -> Cow<'static, str> {
         match self {
-            ColorStyle::Themed(id) => format!("var({id})").into(),
+            ColorStyle::Themed(id) => format!("var({})", id.name(prefix)).into(),
             ColorStyle::Custom(color) => color.to_css_hex().into(),
         }
     }
@@ -1402,28 +2847,50 @@ impl PartialEq<ColorStyleId> for ColorStyle {
     }
 }
 
-impl std::fmt::Display for ColorStyle {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.render())
+impl From<ColorStyleId> for ColorStyle {
+    fn from(id: ColorStyleId) -> Self {
+        ColorStyle::Themed(id)
     }
 }
 
-impl From<ColorStyle> for svg::node::Value {
-    fn from(style: ColorStyle) -> Self {
-        style.render().as_ref().into()
+/// Assigns the fill color to the node, using a CSS class for themed colors if `classes` is set.
+/// The `prefix` is prepended to the names of the CSS variables and classes.
+fn assign_fill<T: Node>(node: &mut T, color: ColorStyle, classes: bool, prefix: &str) {
+    match color {
+        ColorStyle::Themed(id) if classes => node.assign("class", id.class(prefix)),
+        color => node.assign("fill", color.render(prefix)),
     }
 }
 
-impl From<ColorStyleId> for ColorStyle {
-    fn from(id: ColorStyleId) -> Self {
-        ColorStyle::Themed(id)
-    }
+/// Returns the value of the `text-decoration` property combining all lines decorating the text.
+///
+/// The result is empty if the text is not decorated.
+pub(super) fn text_decoration(attrs: &CellAttributes) -> String {
+    let lines = [
+        (attrs.underline() != Underline::None, "underline"),
+        (attrs.overline(), "overline"),
+        (attrs.strikethrough(), "line-through"),
+    ];
+    lines
+        .into_iter()
+        .filter_map(|(enabled, line)| enabled.then_some(line))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Adds the CSS class to the node, keeping the classes assigned before.
+fn add_class<T: Node>(node: &mut T, class: &str) {
+    let classes = match node.get_attributes().and_then(|attrs| attrs.get("class")) {
+        Some(classes) => format!("{classes} {class}"),
+        None => class.to_owned(),
+    };
+    node.assign("class", classes);
 }
 
 // ---
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum ColorStyleId {
+pub(super) enum ColorStyleId {
     DefaultBackground,
     DefaultForeground,
     BrightForeground,
@@ -1431,24 +2898,27 @@ enum ColorStyleId {
 }
 
 impl ColorStyleId {
-    /// Returns the name of the color style ID.
+    /// Returns the name of the CSS variable holding the color.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The prefix prepended to the name.
     ///
     /// # Returns
     ///
-    /// The name of the color style ID.
-    fn name(&self) -> Cow<'static, str> {
+    /// The name of the CSS variable.
+    fn name(&self, prefix: &str) -> String {
         match self {
-            ColorStyleId::DefaultBackground => "--bg".into(),
-            ColorStyleId::DefaultForeground => "--fg".into(),
-            ColorStyleId::BrightForeground => "--br-fg".into(),
-            ColorStyleId::Palette(i) => format!("--c-{i}").into(),
+            ColorStyleId::DefaultBackground => format!("--{prefix}bg"),
+            ColorStyleId::DefaultForeground => format!("--{prefix}fg"),
+            ColorStyleId::BrightForeground => format!("--{prefix}br-fg"),
+            ColorStyleId::Palette(i) => format!("--{prefix}c-{i}"),
         }
     }
-}
 
-impl std::fmt::Display for ColorStyleId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.name())
+    /// Returns the name of the CSS class assigning the color.
+    fn class(&self, prefix: &str) -> String {
+        self.name(prefix).trim_start_matches("--").to_owned()
     }
 }
 
@@ -1508,7 +2978,7 @@ fn match_font_face(
 /// # Returns
 ///
 /// The SVG-compatible string representation of the font weight.
-fn svg_weight(weight: FontWeight) -> String {
+pub(super) fn svg_weight(weight: FontWeight) -> String {
     match weight {
         FontWeight::Normal => "normal".into(),
         FontWeight::Bold => "bold".into(),
@@ -1554,29 +3024,5 @@ impl SpanCursor {
     }
 }
 
-// ---
-
-mod styles {
-    // third-party imports
-    use askama::Template;
-
-    #[derive(Template)]
-    #[template(path = "styles/font-face.css")]
-    pub struct FontFace {
-        pub font_family: String,
-        pub font_weight: String,
-        pub font_style: Option<String>,
-        pub src_url: String,
-        pub format: Option<&'static str>,
-    }
-
-    #[derive(Template)]
-    #[template(path = "styles/theme.css")]
-    pub struct Theme {
-        pub name: String,
-        pub vars: Vec<(String, String)>,
-    }
-}
-
 #[cfg(test)]
 mod tests;
@@ -1,59 +1,24 @@
 use super::*;
 
+use std::collections::HashSet;
+
 use csscolorparser::Color;
 use itertools::Itertools;
 use termwiz::surface::Change;
 
 use crate::{
     config::{
-        Number, PaddingOption, Settings,
+        CursorStyle, Number, PaddingOption, Settings,
         mode::Mode,
         winstyle::{
-            Font, SelectiveColor, Window, WindowBorder, WindowBorderColors, WindowButtons,
-            WindowHeader, WindowShadow, WindowStyleConfig, WindowTitle,
+            CornerRadius, Font, SelectiveColor, TitleAlign, Window, WindowBorder,
+            WindowBorderColors, WindowButtons, WindowButtonsPreset, WindowHeader, WindowShadow,
+            WindowShadowLayer, WindowStyleConfig, WindowTitle,
         },
     },
     render::{FontMetrics, FontOptions, FontWeights, Options},
 };
 
-trait Sample {
-    fn sample() -> Self;
-}
-
-impl Sample for Options {
-    fn sample() -> Self {
-        Options {
-            settings: Default::default(),
-            font: FontOptions {
-                family: vec!["Monospace".to_string()],
-                size: 12.0,
-                metrics: FontMetrics {
-                    width: 0.6,
-                    ascender: 0.8,
-                    descender: -0.2,
-                },
-                faces: vec![],
-                weights: FontWeights {
-                    normal: FontWeight::Normal,
-                    bold: FontWeight::Bold,
-                    faint: FontWeight::Normal,
-                },
-            },
-            theme: Rc::new(Theme {
-                bg: Color::from_rgba8(255, 255, 255, 255),
-                fg: Color::from_rgba8(0, 0, 0, 255),
-                bright_fg: None,
-                palette: Default::default(),
-            }),
-            window: WindowStyleConfig::default().window,
-            title: Some("Sample Title".to_string()),
-            mode: Mode::Light,
-            background: None,
-            foreground: None,
-        }
-    }
-}
-
 #[test]
 fn test_estimate_char_width_narrow_chars() {
     // Very narrow characters
@@ -188,6 +153,26 @@ fn test_trim_text_to_width_mixed_widths() {
     assert!(!result.is_empty());
 }
 
+#[test]
+fn test_trim_text_to_width_with_measured_glyphs() {
+    let title = "WWWWWWWWWW";
+
+    // The heuristic assumes 'W' is 1.3 times the average width, so the title fits
+    let estimated = trim_text_to_width(title, 30.0, 1.0, "…");
+    assert_eq!(estimated, title);
+
+    // The real glyphs of a wide font don't fit
+    let measured = |ch| if ch == 'W' { 2.5 } else { 1.0 };
+    let result = trim_text_to_width_with(title, 30.0, 1.0, "…", measured);
+    assert_eq!(result, "WWWWWWWWW…");
+
+    // Narrow glyphs fit where the heuristic would trim
+    let title = "mmmmmmmmmmmmmmmmmmmm";
+    assert!(trim_text_to_width(title, 30.0, 1.0, "…").ends_with('…'));
+    let result = trim_text_to_width_with(title, 30.0, 1.0, "…", |_| 1.0);
+    assert_eq!(result, title);
+}
+
 #[test]
 fn test_calculate_available_width_no_buttons() {
     // No buttons configured
@@ -462,7 +447,7 @@ fn test_make_window_integration_with_title() {
         margin: PaddingOption::Uniform(Number::from(5.0)),
         border: WindowBorder {
             width: Number::from(1.0),
-            radius: Number::from(4.0),
+            radius: Number::from(4.0).into(),
             gap: None,
             colors: WindowBorderColors {
                 outer: SelectiveColor::Uniform(Color::from_rgba8(0, 0, 0, 255)),
@@ -481,6 +466,8 @@ fn test_make_window_integration_with_title() {
                 size: Number::from(12.0),
                 weight: Some("normal".to_string()),
             },
+            ellipsis: None,
+            align: TitleAlign::Center,
         },
         buttons: button_cfg,
         shadow: WindowShadow {
@@ -488,6 +475,9 @@ fn test_make_window_integration_with_title() {
             x: Number::from(0.0),
             y: Number::from(0.0),
             blur: Number::from(0.0),
+            spread: Number::from(0.0),
+            inset: false,
+            layers: vec![],
             color: SelectiveColor::Uniform(Color::from_rgba8(0, 0, 0, 100)),
         },
     };
@@ -514,6 +504,7 @@ fn test_make_window_integration_with_title() {
             bg: Color::from_rgba8(255, 255, 255, 255),
             fg: Color::from_rgba8(0, 0, 0, 255),
             bright_fg: None,
+            cursor: None,
             palette: Default::default(),
         }),
         window: window_config,
@@ -521,6 +512,12 @@ fn test_make_window_integration_with_title() {
         mode: Mode::Light,
         background: None,
         foreground: None,
+        cursor: None,
+        scrollback: 0,
+        highlights: Vec::new(),
+        images: Vec::new(),
+        palette: Default::default(),
+        title_advances: Default::default(),
     };
 
     // Call make_window to exercise title rendering paths
@@ -549,7 +546,7 @@ fn test_make_window_integration_no_title() {
         margin: PaddingOption::Uniform(Number::from(5.0)),
         border: WindowBorder {
             width: Number::from(1.0),
-            radius: Number::from(4.0),
+            radius: Number::from(4.0).into(),
             gap: None,
             colors: WindowBorderColors {
                 outer: SelectiveColor::Uniform(Color::from_rgba8(0, 0, 0, 255)),
@@ -568,6 +565,8 @@ fn test_make_window_integration_no_title() {
                 size: Number::from(12.0),
                 weight: Some("bold".to_string()),
             },
+            ellipsis: None,
+            align: TitleAlign::Center,
         },
         buttons: button_cfg,
         shadow: WindowShadow {
@@ -575,6 +574,9 @@ fn test_make_window_integration_no_title() {
             x: Number::from(0.0),
             y: Number::from(0.0),
             blur: Number::from(0.0),
+            spread: Number::from(0.0),
+            inset: false,
+            layers: vec![],
             color: SelectiveColor::Uniform(Color::from_rgba8(0, 0, 0, 100)),
         },
     };
@@ -600,6 +602,7 @@ fn test_make_window_integration_no_title() {
             bg: Color::from_rgba8(255, 255, 255, 255),
             fg: Color::from_rgba8(0, 0, 0, 255),
             bright_fg: None,
+            cursor: None,
             palette: Default::default(),
         }),
         window: window_config,
@@ -607,6 +610,12 @@ fn test_make_window_integration_no_title() {
         mode: Mode::Light,
         background: None,
         foreground: None,
+        cursor: None,
+        scrollback: 0,
+        highlights: Vec::new(),
+        images: Vec::new(),
+        palette: Default::default(),
+        title_advances: Default::default(),
     };
 
     let result = make_window(&options, 200.0, 150.0, screen);
@@ -748,3 +757,1899 @@ fn test_render_with_unresolved_font() {
     assert!(svg.contains("test"));
     assert!(svg.contains("textLength"));
 }
+
+#[test]
+fn test_render_synthetic_bold_with_regular_face_only() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Attribute(
+        termwiz::cell::AttributeChange::Intensity(Intensity::Bold),
+    ));
+    surface.add_change(Change::Text("bold".into()));
+
+    let mut settings = Settings::default();
+    settings.rendering.synthetic_bold = true;
+    settings.window.enabled = false;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+    options.font.faces = vec![FontFace {
+        family: "Monospace".to_string(),
+        weight: FontWeight::Normal,
+        style: Some(FontStyle::Normal),
+        url: "monospace.ttf".to_string(),
+        format: None,
+        chars: Rc::new(crate::render::CharSetFn::new(|_| true)),
+        metrics_match: true,
+        unicode_range: Vec::new(),
+    }];
+
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(svg.contains("bold"));
+    assert!(svg.contains("paint-order=\"stroke\""));
+    assert!(svg.contains("stroke=\"#000000\""));
+    assert!(svg.contains("stroke-width=\"0.48\""));
+}
+
+#[test]
+fn test_render_no_synthetic_bold_with_bold_face() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Attribute(
+        termwiz::cell::AttributeChange::Intensity(Intensity::Bold),
+    ));
+    surface.add_change(Change::Text("bold".into()));
+
+    let mut settings = Settings::default();
+    settings.rendering.synthetic_bold = true;
+    settings.window.enabled = false;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+    options.font.faces = vec![FontFace {
+        family: "Monospace".to_string(),
+        weight: FontWeight::Variable(100, 900),
+        style: Some(FontStyle::Normal),
+        url: "monospace.ttf".to_string(),
+        format: None,
+        chars: Rc::new(crate::render::CharSetFn::new(|_| true)),
+        metrics_match: true,
+        unicode_range: Vec::new(),
+    }];
+
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(svg.contains("bold"));
+    assert!(!svg.contains("stroke=\"#000000\""));
+}
+
+#[test]
+fn test_render_no_synthetic_bold_with_bold_upright_face_for_italic() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Attribute(
+        termwiz::cell::AttributeChange::Intensity(Intensity::Bold),
+    ));
+    surface.add_change(Change::Attribute(termwiz::cell::AttributeChange::Italic(
+        true,
+    )));
+    surface.add_change(Change::Text("bold".into()));
+
+    let mut settings = Settings::default();
+    settings.rendering.synthetic_bold = true;
+    settings.window.enabled = false;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+    options.font.faces = vec![FontFace {
+        family: "Monospace".to_string(),
+        weight: FontWeight::Variable(100, 900),
+        style: Some(FontStyle::Normal),
+        url: "monospace.ttf".to_string(),
+        format: None,
+        chars: Rc::new(crate::render::CharSetFn::new(|_| true)),
+        metrics_match: true,
+        unicode_range: Vec::new(),
+    }];
+
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(svg.contains("bold"));
+    assert!(!svg.contains("paint-order"));
+    assert!(!svg.contains("stroke=\"#000000\""));
+}
+
+#[test]
+fn test_render_synthetic_oblique_with_normal_face_only() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Attribute(termwiz::cell::AttributeChange::Italic(
+        true,
+    )));
+    surface.add_change(Change::Text("slant".into()));
+
+    let mut settings = Settings::default();
+    settings.rendering.svg.synthetic_oblique = true;
+    settings.window.enabled = false;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+    options.font.faces = vec![FontFace {
+        family: "Monospace".to_string(),
+        weight: FontWeight::Normal,
+        style: Some(FontStyle::Normal),
+        url: "monospace.ttf".to_string(),
+        format: None,
+        chars: Rc::new(crate::render::CharSetFn::new(|_| true)),
+        metrics_match: true,
+        unicode_range: Vec::new(),
+    }];
+
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(svg.contains("slant"));
+    assert!(svg.contains("transform=\"skewX(-12)\""));
+    assert!(svg.contains("transform-origin=\"0em "));
+    assert!(!svg.contains("font-style=\"italic\""));
+}
+
+#[test]
+fn test_render_no_synthetic_oblique_with_italic_face() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Attribute(termwiz::cell::AttributeChange::Italic(
+        true,
+    )));
+    surface.add_change(Change::Text("slant".into()));
+
+    let mut settings = Settings::default();
+    settings.rendering.svg.synthetic_oblique = true;
+    settings.window.enabled = false;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+    options.font.faces = vec![FontFace {
+        family: "Monospace".to_string(),
+        weight: FontWeight::Normal,
+        style: Some(FontStyle::Italic),
+        url: "monospace-italic.ttf".to_string(),
+        format: None,
+        chars: Rc::new(crate::render::CharSetFn::new(|_| true)),
+        metrics_match: true,
+        unicode_range: Vec::new(),
+    }];
+
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(svg.contains("slant"));
+    assert!(svg.contains("font-style=\"italic\""));
+    assert!(!svg.contains("skewX"));
+}
+
+#[test]
+fn test_render_row_ids() {
+    let mut surface = Surface::new(10, 3);
+    surface.add_change(Change::Text("one\r\ntwo\r\nthree".into()));
+
+    let mut settings = Settings::default();
+    settings.rendering.svg.row_ids = true;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    let positions = ["row-0", "row-1", "row-2"]
+        .iter()
+        .map(|id| svg.find(&format!("<g id=\"{id}\">")).unwrap())
+        .collect_vec();
+    assert!(positions.is_sorted());
+}
+
+#[test]
+fn test_render_word_ids() {
+    let mut surface = Surface::new(20, 1);
+    surface.add_change(Change::Text("ls -la  fo".into()));
+    surface.add_change(Change::Attribute(
+        termwiz::cell::AttributeChange::Intensity(Intensity::Bold),
+    ));
+    surface.add_change(Change::Text("o".into()));
+
+    let mut settings = Settings::default();
+    settings.rendering.svg.word_ids = true;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(svg.contains("<tspan data-word=\"0\">ls</tspan>"));
+    assert!(svg.contains("<tspan data-word=\"1\">-la</tspan>"));
+    assert!(svg.contains("<tspan data-word=\"2\">fo</tspan>"));
+    // The bold part continues the same word.
+    assert_eq!(svg.matches("data-word=\"2\"").count(), 2);
+    assert!(!svg.contains("data-word=\"3\""));
+}
+
+#[test]
+fn test_render_without_row_ids() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Text("one".into()));
+
+    let renderer = SvgRenderer::new(Options::sample());
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(!svg.contains("row-0"));
+}
+
+#[test]
+fn test_render_embed_text() {
+    let mut surface = Surface::new(8, 2);
+    surface.add_change(Change::Text("a <b> &c\r\n  indent".into()));
+
+    let mut settings = Settings::default();
+    settings.rendering.svg.embed_text = true;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(
+        svg.contains("<metadata xml:space=\"preserve\">a &lt;b&gt; &amp;c\n  indent\n</metadata>")
+    );
+}
+
+#[test]
+fn test_render_embed_text_invisible() {
+    use termwiz::cell::AttributeChange;
+
+    let mut surface = Surface::new(12, 1);
+    surface.add_change(Change::Text("pw: ".into()));
+    surface.add_change(Change::Attribute(AttributeChange::Invisible(true)));
+    surface.add_change(Change::Text("secret".into()));
+    surface.add_change(Change::Attribute(AttributeChange::Invisible(false)));
+    surface.add_change(Change::Text("!".into()));
+
+    let mut settings = Settings::default();
+    settings.rendering.svg.embed_text = true;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let mut output = Vec::new();
+    SvgRenderer::new(options)
+        .render(&surface, &mut output)
+        .unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(!svg.contains("secret"));
+    assert!(svg.contains("<metadata xml:space=\"preserve\">pw:       !  \n</metadata>"));
+}
+
+#[test]
+fn test_render_without_embed_text() {
+    let mut surface = Surface::new(8, 1);
+    surface.add_change(Change::Text("text".into()));
+
+    let renderer = SvgRenderer::new(Options::sample());
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(!svg.contains("<metadata"));
+}
+
+#[test]
+fn test_adjust_contrast_lightens_on_dark_background() {
+    let bg = Color::from_rgba8(0x1e, 0x1e, 0x1e, 255);
+    let fg = Color::from_rgba8(0x33, 0x33, 0x66, 255);
+    assert!(contrast_ratio(&fg, &bg) < 4.5);
+
+    let adjusted = adjust_contrast(&fg, &bg, 4.5);
+    assert!(contrast_ratio(&adjusted, &bg) >= 4.5);
+    assert!(relative_luminance(&adjusted) > relative_luminance(&fg));
+}
+
+#[test]
+fn test_adjust_contrast_darkens_on_light_background() {
+    let bg = Color::from_rgba8(0xf0, 0xf0, 0xf0, 255);
+    let fg = Color::from_rgba8(0xc0, 0xc0, 0xc0, 255);
+
+    let adjusted = adjust_contrast(&fg, &bg, 4.5);
+    assert!(contrast_ratio(&adjusted, &bg) >= 4.5);
+    assert!(relative_luminance(&adjusted) < relative_luminance(&fg));
+}
+
+#[test]
+fn test_render_min_contrast() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Text("low".into()));
+
+    let bg = Color::from_rgba8(0x1e, 0x1e, 0x1e, 255);
+    let fg = Color::from_rgba8(0x33, 0x33, 0x33, 255);
+
+    let render = |min_contrast: Option<f32>| {
+        let mut settings = Settings::default();
+        settings.rendering.min_contrast = min_contrast.map(Number::Float);
+        settings.window.enabled = false;
+
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+        options.background = Some(bg.clone());
+        options.foreground = Some(fg.clone());
+
+        let mut output = Vec::new();
+        SvgRenderer::new(options)
+            .render(&surface, &mut output)
+            .unwrap();
+        let svg = String::from_utf8(output).unwrap();
+
+        let end = svg.find("\">low</tspan>").unwrap();
+        svg[end - 7..end].parse::<Color>().unwrap()
+    };
+
+    assert_eq!(render(None), fg);
+
+    let adjusted = render(Some(4.5));
+    assert!(contrast_ratio(&adjusted, &bg) >= 4.5);
+    assert!(relative_luminance(&adjusted) > relative_luminance(&fg));
+}
+
+#[test]
+fn test_render_full_width_bg() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Attribute(termwiz::cell::AttributeChange::Reverse(
+        true,
+    )));
+    surface.add_change(Change::Text("menu".into()));
+
+    let render = |full_width_bg: bool| {
+        let mut settings = Settings::default();
+        settings.rendering.full_width_bg = full_width_bg;
+        settings.window.enabled = false;
+
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+
+        let mut output = Vec::new();
+        SvgRenderer::new(options)
+            .render(&surface, &mut output)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    // 4 cells of 0.6em wide
+    let svg = render(false);
+    assert!(svg.contains("d=\"M0,0 H2.4 V1.2 H0 Z\""));
+
+    // 10 cells of 0.6em wide
+    let svg = render(true);
+    assert!(svg.contains("d=\"M0,0 H6 V1.2 H0 Z\""));
+}
+
+#[test]
+fn test_css_font_faces_match_inline_style() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Text("text".into()));
+
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+    options.font.faces = vec![FontFace {
+        family: "Monospace".to_string(),
+        weight: FontWeight::Variable(100, 900),
+        style: Some(FontStyle::Normal),
+        url: "monospace.ttf".to_string(),
+        format: None,
+        chars: Rc::new(crate::render::CharSetFn::new(|_| true)),
+        metrics_match: true,
+        unicode_range: Vec::new(),
+    }];
+
+    let faces = css::font_faces(&options, &BTreeSet::from([0])).unwrap();
+    assert_eq!(faces.len(), 1);
+    assert!(faces[0].starts_with("@font-face {"));
+
+    let stylesheet = css::stylesheet(&options, None, &BTreeSet::from([0])).unwrap();
+    assert_eq!(stylesheet, faces[0]);
+
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(svg.contains(&format!("<style>\n{}\n</style>", faces[0])));
+}
+
+#[test]
+fn test_render_scrollbar() {
+    let mut surface = Surface::new(10, 10);
+    surface.add_change(Change::Text("text".into()));
+
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+    settings.rendering.scrollbar = true;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let renderer = SvgRenderer::new(options.clone());
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+    let svg = String::from_utf8(output).unwrap();
+    assert!(!svg.contains("<rect height=\"144\""));
+
+    // 10 visible rows out of 40 make a thumb a quarter of the screen height (144px)
+    options.scrollback = 30;
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+    let svg = String::from_utf8(output).unwrap();
+    assert!(svg.contains("<rect height=\"144\" opacity=\"0.1\""));
+    assert!(svg.contains("<rect height=\"36\" opacity=\"0.4\""));
+    assert!(svg.contains("y=\"108\""));
+}
+
+#[test]
+fn test_render_merge_spans() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Attribute(
+        termwiz::cell::AttributeChange::Background(termwiz::color::AnsiColor::Maroon.into()),
+    ));
+    surface.add_change(Change::Text("ab".into()));
+    surface.add_change(Change::Attribute(
+        termwiz::cell::AttributeChange::Background(termwiz::color::AnsiColor::Green.into()),
+    ));
+    surface.add_change(Change::Text("cd".into()));
+
+    let render = |merge_spans: bool| {
+        let mut settings = Settings::default();
+        settings.rendering.svg.merge_spans = merge_spans;
+        settings.window.enabled = false;
+
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+        options.font.faces = vec![FontFace {
+            family: "Monospace".to_string(),
+            weight: FontWeight::Normal,
+            style: Some(FontStyle::Normal),
+            url: "monospace.ttf".to_string(),
+            format: None,
+            chars: Rc::new(crate::render::CharSetFn::new(|_| true)),
+            metrics_match: true,
+            unicode_range: Vec::new(),
+        }];
+
+        let mut output = Vec::new();
+        SvgRenderer::new(options)
+            .render(&surface, &mut output)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    let svg = render(false);
+    assert!(svg.contains("<tspan>ab</tspan><tspan>cd</tspan>"));
+
+    let svg = render(true);
+    assert!(svg.contains("<tspan>abcd</tspan>"));
+}
+
+#[test]
+fn test_render_fixed_bold_weight() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Attribute(
+        termwiz::cell::AttributeChange::Intensity(Intensity::Bold),
+    ));
+    surface.add_change(Change::Text("bold".into()));
+
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+    options.font.weights.bold = FontWeight::Fixed(800);
+    options.font.faces = vec![
+        FontFace {
+            family: "Monospace".to_string(),
+            weight: FontWeight::Normal,
+            style: Some(FontStyle::Normal),
+            url: "monospace.ttf".to_string(),
+            format: None,
+            chars: Rc::new(crate::render::CharSetFn::new(|_| true)),
+            metrics_match: true,
+            unicode_range: Vec::new(),
+        },
+        FontFace {
+            family: "Monospace".to_string(),
+            weight: FontWeight::Fixed(800),
+            style: Some(FontStyle::Normal),
+            url: "monospace-extrabold.ttf".to_string(),
+            format: None,
+            chars: Rc::new(crate::render::CharSetFn::new(|_| true)),
+            metrics_match: true,
+            unicode_range: Vec::new(),
+        },
+    ];
+
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(svg.contains("font-weight=\"800\""));
+    assert!(!svg.contains("font-weight=\"bold\""));
+    assert!(svg.contains("font-weight: 800;"));
+    assert!(svg.contains("monospace-extrabold.ttf"));
+}
+
+#[test]
+fn test_render_baseline_offset() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Text("text".into()));
+
+    let render = |offset: f32| {
+        let mut settings = Settings::default();
+        settings.font.baseline_offset = Number::from(offset);
+        settings.window.enabled = false;
+
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+
+        let mut output = Vec::new();
+        SvgRenderer::new(options)
+            .render(&surface, &mut output)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    // (1.2 + 0.8 - 0.2) / 2 = 0.9em without the offset
+    let svg = render(0.0);
+    assert!(svg.contains("y=\"0.9em\""));
+
+    let svg = render(0.1);
+    assert!(svg.contains("y=\"1em\""));
+    assert!(!svg.contains("y=\"0.9em\""));
+
+    let svg = render(-0.05);
+    assert!(svg.contains("y=\"0.85em\""));
+}
+
+#[test]
+fn test_render_trailing_colored_spaces() {
+    let mut terminal = crate::term::Terminal::new(crate::term::Options {
+        cols: Some(20),
+        rows: Some(3),
+        ..Default::default()
+    });
+    terminal.advance(b"x\x1b[41m  \x1b[0m\r\ny\x1b[7m  \x1b[0m\r\n");
+    terminal.set_width(10);
+
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let mut output = Vec::new();
+    SvgRenderer::new(options)
+        .render(terminal.surface(), &mut output)
+        .unwrap();
+    let svg = String::from_utf8(output).unwrap();
+
+    // Colored spaces in columns 1..3 of both rows are painted despite having no text.
+    assert!(svg.contains("d=\"M0.6,0 H1.8 V1.2 H0.6 Z\""));
+    assert!(svg.contains("d=\"M0.6,1.2 H1.8 V2.4 H0.6 Z\""));
+}
+
+#[test]
+fn test_render_full_transcript() {
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+    settings.terminal.width.current = 10.into();
+    settings.terminal.height.current = 2.into();
+
+    let feed = || {
+        let mut terminal = crate::term::Terminal::new(crate::term::Options {
+            cols: Some(10),
+            rows: Some(2),
+            ..Default::default()
+        });
+        terminal.advance(b"first\r\nsecond\r\nthird\r\nfourth");
+        assert_eq!(terminal.scrollback_len(), 2);
+        terminal
+    };
+
+    // The fixed height is kept, and the first lines stay in the scrollback.
+    let mut terminal = feed();
+    crate::capture::fit_terminal(&mut terminal, &settings, false, false);
+    assert_eq!(terminal.surface().dimensions(), (10, 2));
+    assert_eq!(terminal.scrollback_len(), 2);
+
+    // Full output grows the terminal to the transcript height and brings the scrollback back on screen.
+    settings.rendering.full_output = true;
+    let mut terminal = feed();
+    crate::capture::fit_terminal(&mut terminal, &settings, false, false);
+    assert_eq!(terminal.surface().dimensions(), (10, 4));
+    assert_eq!(terminal.scrollback_len(), 0);
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let mut output = Vec::new();
+    SvgRenderer::new(options)
+        .render(terminal.surface(), &mut output)
+        .unwrap();
+    let svg = String::from_utf8(output).unwrap();
+
+    for text in ["first", "second", "third", "fourth"] {
+        assert!(svg.contains(text), "missing {text:?}");
+    }
+}
+
+#[test]
+fn test_render_cursor() {
+    let mut terminal = crate::term::Terminal::new(crate::term::Options {
+        cols: Some(10),
+        rows: Some(2),
+        ..Default::default()
+    });
+    terminal.advance(b"ls\r\n$ ");
+
+    let render = |terminal: &crate::term::Terminal, style: CursorStyle| {
+        let mut settings = Settings::default();
+        settings.window.enabled = false;
+        settings.rendering.cursor.enabled = true;
+        settings.rendering.cursor.style = style;
+
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+
+        let mut output = Vec::new();
+        SvgRenderer::new(options)
+            .render(terminal.surface(), &mut output)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    // The cursor is at column 2 of row 1, cells are 0.6em wide and 1.2em high.
+    let svg = render(&terminal, CursorStyle::Block);
+    assert!(
+        svg.contains("<rect height=\"1.2\" opacity=\"0.5\" width=\"0.6\" x=\"1.2\" y=\"1.2\"/>")
+    );
+
+    let svg = render(&terminal, CursorStyle::Bar);
+    assert!(svg.contains("<rect height=\"1.2\" width=\"0.1\" x=\"1.2\" y=\"1.2\"/>"));
+
+    let svg = render(&terminal, CursorStyle::Underline);
+    assert!(svg.contains("<rect height=\"0.1\" width=\"0.6\" x=\"1.2\" y=\"2.3\"/>"));
+
+    // A cursor hidden with DECTCEM is not drawn.
+    terminal.advance(b"\x1b[?25l");
+    let svg = render(&terminal, CursorStyle::Bar);
+    assert!(!svg.contains("x=\"1.2\" y=\"1.2\""));
+}
+
+#[test]
+fn test_render_hyperlinks() {
+    let mut terminal = crate::term::Terminal::new(crate::term::Options {
+        cols: Some(20),
+        rows: Some(1),
+        ..Default::default()
+    });
+    terminal.advance(b"see \x1b]8;;https://example.com\x1b\\docs\x1b]8;;\x1b\\ here");
+
+    let render = |hyperlinks: bool| {
+        let mut settings = Settings::default();
+        settings.window.enabled = false;
+        settings.rendering.svg.hyperlinks = hyperlinks;
+
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+
+        let mut output = Vec::new();
+        SvgRenderer::new(options)
+            .render(terminal.surface(), &mut output)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    let svg = render(true);
+    assert!(svg.contains("xmlns:xlink=\"http://www.w3.org/1999/xlink\""));
+    let anchor = svg
+        .split("<a xlink:href=\"https://example.com\">")
+        .nth(1)
+        .expect("anchor is missing");
+    assert!(anchor.split("</a>").next().unwrap().contains("docs"));
+    assert!(!anchor.split("</a>").next().unwrap().contains("here"));
+
+    let svg = render(false);
+    assert!(!svg.contains("<a "));
+    assert!(svg.contains("docs"));
+}
+
+#[test]
+fn test_make_window_shadow_layers() {
+    let mut options = Options::sample();
+    options.window.shadow.layers = vec![WindowShadowLayer {
+        color: SelectiveColor::Uniform(Color::from_rgba8(0, 0, 0, 64)),
+        x: Number::from(0.0),
+        y: Number::from(2.0),
+        blur: Number::from(4.0),
+        spread: Number::from(1.0),
+        inset: false,
+    }];
+
+    let svg = make_window(&options, 200.0, 150.0, element::SVG::new()).to_string();
+
+    assert_eq!(svg.matches("<feGaussianBlur").count(), 2);
+
+    let shadows = svg
+        .split("<rect")
+        .filter(|rect| rect.contains("filter=\"url(#shadow"))
+        .collect_vec();
+    assert_eq!(shadows.len(), 2);
+
+    // The main shadow layer keeps its offset, the extra one is offset and grown by the spread.
+    assert!(shadows[0].contains("filter=\"url(#shadow)\""));
+    assert!(shadows[0].contains("x=\"4\" y=\"12\""));
+    assert!(shadows[1].contains("filter=\"url(#shadow-1)\""));
+    assert!(shadows[1].contains("width=\"202\" x=\"-1\" y=\"1\""));
+}
+
+#[test]
+fn test_render_max_colors() {
+    let mut surface = Surface::new(32, 1);
+    for i in 0..32 {
+        let color = SrgbaTuple::from((100 + i, 50, 50, 255));
+        surface.add_change(Change::Attribute(
+            termwiz::cell::AttributeChange::Background(
+                ColorAttribute::TrueColorWithDefaultFallback(color),
+            ),
+        ));
+        surface.add_change(Change::Text(" ".into()));
+    }
+
+    let render = |max_colors| {
+        let mut settings = Settings::default();
+        settings.window.enabled = false;
+        settings.rendering.max_colors = max_colors;
+
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+
+        let mut output = Vec::new();
+        SvgRenderer::new(options)
+            .render(&surface, &mut output)
+            .unwrap();
+        let svg = String::from_utf8(output).unwrap();
+
+        svg.split("<path ")
+            .skip(1)
+            .filter_map(|path| path.split("fill=\"").nth(1))
+            .filter_map(|fill| fill.split('"').next())
+            .map(|fill| fill.to_owned())
+            .collect::<HashSet<_>>()
+    };
+
+    assert_eq!(render(None).len(), 32);
+    let fills = render(Some(4));
+    assert!(!fills.is_empty() && fills.len() <= 4, "fills: {fills:?}");
+}
+
+#[test]
+fn test_make_buttons_macos_preset() {
+    let mut options = Options::sample();
+    options.window.buttons = WindowButtonsPreset::Macos.buttons().unwrap();
+
+    let svg = make_buttons(&options, 200.0).to_string();
+
+    assert_eq!(svg.matches("<circle").count(), 3);
+    for color in ["#ff5f56", "#ffbd2e", "#28c941"] {
+        assert!(
+            svg.contains(&format!("fill=\"{color}\"")),
+            "missing {color}"
+        );
+    }
+}
+
+#[test]
+fn test_dump_shapes() {
+    let mut surface = Surface::new(10, 2);
+    let red = SrgbaTuple::from((255, 0, 0, 255));
+    let blue = SrgbaTuple::from((0, 0, 255, 255));
+    surface.add_change(Change::Attribute(
+        termwiz::cell::AttributeChange::Background(ColorAttribute::TrueColorWithDefaultFallback(
+            red,
+        )),
+    ));
+    surface.add_change(Change::Text("ab".into()));
+    surface.add_change(Change::Attribute(
+        termwiz::cell::AttributeChange::Background(ColorAttribute::TrueColorWithDefaultFallback(
+            blue,
+        )),
+    ));
+    surface.add_change(Change::Text("\r\n     c".into()));
+
+    let mut output = Vec::new();
+    SvgRenderer::new(Options::sample())
+        .dump_shapes(&surface, &mut output)
+        .unwrap();
+
+    let shapes: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let shapes = shapes.as_array().unwrap();
+    assert_eq!(shapes.len(), 2);
+
+    let keys = shapes
+        .iter()
+        .map(|shape| shape["key"].as_str().unwrap())
+        .sorted()
+        .collect_vec();
+    assert_eq!(keys, ["#0000ff", "#ff0000"]);
+
+    let red = shapes
+        .iter()
+        .find(|shape| shape["key"] == "#ff0000")
+        .unwrap();
+    assert_eq!(
+        red["contours"],
+        serde_json::json!([[[0, 0], [2, 0], [2, 1], [0, 1]]])
+    );
+}
+
+#[test]
+fn test_render_indent_guides() {
+    let mut surface = Surface::new(20, 5);
+    surface.add_change(Change::Text(
+        "fn main() {\r\n    if x {\r\n        y();\r\n    }\r\n}".into(),
+    ));
+
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+    settings.terminal.tab_width = 4;
+    settings.rendering.indent_guides = true;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    // Column 0 spans rows 1-3, column 4 only row 2.
+    assert!(svg.contains("d=\"M0.3,1.2 V4.8 M2.7,2.4 V3.6\""));
+    assert!(svg.contains("stroke-opacity=\"0.2\""));
+}
+
+#[test]
+fn test_render_without_indent_guides() {
+    let mut surface = Surface::new(20, 2);
+    surface.add_change(Change::Text("a\r\n    b".into()));
+
+    let renderer = SvgRenderer::new(Options::sample());
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(!svg.contains("stroke-opacity"));
+}
+
+#[test]
+fn test_render_opacity() {
+    let render = |window, opacity: f32| {
+        let mut surface = Surface::new(10, 1);
+        surface.add_change(Change::Text("hello".into()));
+
+        let mut settings = Settings::default();
+        settings.window.enabled = window;
+        settings.rendering.opacity = opacity.into();
+
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+
+        let renderer = SvgRenderer::new(options);
+        let mut output = Vec::new();
+        renderer.render(&surface, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    let opaque = render(true, 1.0);
+    let translucent = render(true, 0.5);
+    assert!(!opaque.contains("opacity=\"0.5\""));
+    assert!(translucent.contains("<g opacity=\"0.5\" transform=\"translate("));
+    // Document dimensions are not affected.
+    let header = |svg: &str| svg[..svg.find('>').unwrap()].to_string();
+    assert_eq!(header(&opaque), header(&translucent));
+
+    let translucent = render(false, 0.5);
+    assert!(translucent.contains("<g opacity=\"0.5\"><rect fill="));
+}
+
+#[test]
+fn test_render_highlight_layers() {
+    let render = |layer| {
+        let mut surface = Surface::new(10, 2);
+        surface.add_change(Change::Text("hello\r\nworld".into()));
+
+        let mut settings = Settings::default();
+        settings.window.enabled = false;
+        settings.rendering.highlight_layer = layer;
+
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+        options.highlights = vec!["1:1..4=#ff000080".parse().unwrap()];
+
+        let renderer = SvgRenderer::new(options);
+        let mut output = Vec::new();
+        renderer.render(&surface, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    let rect = "<rect fill=\"#ff000080\" height=\"1.2\" width=\"1.8\" x=\"0.6\" y=\"1.2\"/>";
+
+    // The overlay highlight is drawn above the text so that it tints it.
+    let svg = render(HighlightLayer::Overlay);
+    let highlight = svg.find(rect).unwrap();
+    assert!(svg.rfind("<text").unwrap() < highlight);
+
+    let svg = render(HighlightLayer::Underlay);
+    let highlight = svg.find(rect).unwrap();
+    assert!(highlight < svg.find("<text").unwrap());
+}
+
+#[test]
+fn test_render_color_classes() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Attribute(
+        termwiz::cell::AttributeChange::Foreground(ColorAttribute::PaletteIndex(1)),
+    ));
+    surface.add_change(Change::Attribute(
+        termwiz::cell::AttributeChange::Background(ColorAttribute::PaletteIndex(4)),
+    ));
+    surface.add_change(Change::Text("red".into()));
+
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+    settings.rendering.svg.color_classes = true;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(svg.contains("<tspan class=\"c-1\">red</tspan>"));
+    assert!(svg.contains("<path class=\"c-4\" d=\"M0,0 H1.8 V1.2 H0 Z\"/>"));
+    assert!(!svg.contains("fill=\"var("));
+
+    assert!(svg.contains(".terminal .c-1 { fill: var(--c-1); }"));
+    assert!(svg.contains(".terminal .c-4 { fill: var(--c-4); }"));
+    assert!(svg.contains(".terminal .fg { fill: var(--fg); }"));
+    assert!(svg.contains(".terminal .bg { fill: var(--bg); }"));
+}
+
+#[test]
+fn test_render_diff_highlights() {
+    let mut baseline = Surface::new(10, 1);
+    baseline.add_change(Change::Text("hello".into()));
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Text("hallo".into()));
+
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+    options.highlights =
+        crate::render::diff_highlights(&surface, &baseline, Some(Color::from_rgba8(0, 255, 0, 64)));
+
+    let renderer = SvgRenderer::new(options);
+    let mut output = Vec::new();
+    renderer.render(&surface, &mut output).unwrap();
+
+    let svg = String::from_utf8(output).unwrap();
+    assert!(
+        svg.contains("<rect fill=\"#00ff0040\" height=\"1.2\" width=\"0.6\" x=\"0.6\" y=\"0\"/>")
+    );
+    assert_eq!(svg.matches("#00ff0040").count(), 1);
+}
+
+#[test]
+fn test_render_animation() {
+    let mut terminal = crate::term::Terminal::new(crate::term::Options {
+        cols: Some(10),
+        rows: Some(3),
+        ..Default::default()
+    });
+
+    let mut frames = Vec::new();
+    for (time, data) in [(0.5, "alpha\r\n"), (1.0, "beta\r\n")] {
+        terminal.advance(data.as_bytes());
+        frames.push(Frame {
+            time,
+            lines: terminal
+                .surface()
+                .screen_lines()
+                .into_iter()
+                .map(Cow::into_owned)
+                .collect(),
+        });
+    }
+
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let mut output = Vec::new();
+    SvgRenderer::new(options)
+        .render_animation(terminal.surface(), &frames, &mut output)
+        .unwrap();
+    let svg = String::from_utf8(output).unwrap();
+
+    assert_eq!(svg.matches("data-time=").count(), 2);
+    assert!(svg.contains("data-time=\"0.5\""));
+    assert!(svg.contains("data-time=\"1\""));
+    assert_eq!(svg.matches("<animate").count(), 1);
+    assert!(svg.contains("keyTimes=\"0;0.5\""));
+    assert!(svg.contains("dur=\"2s\""));
+
+    // The second frame only contains the changed row.
+    let (first, second) = svg.split_once("data-time=\"1\"").unwrap();
+    assert!(first.contains("alpha"));
+    assert!(second.contains("beta"));
+    assert!(!second.contains("alpha"));
+}
+
+#[test]
+fn test_render_animation_cursor() {
+    let mut terminal = crate::term::Terminal::new(crate::term::Options {
+        cols: Some(10),
+        rows: Some(3),
+        ..Default::default()
+    });
+
+    let mut frames = Vec::new();
+    for (time, data) in [(0.5, "alpha\r\n"), (1.0, "beta\r\n")] {
+        terminal.advance(data.as_bytes());
+        frames.push(Frame {
+            time,
+            lines: terminal
+                .surface()
+                .screen_lines()
+                .into_iter()
+                .map(Cow::into_owned)
+                .collect(),
+        });
+    }
+
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+    settings.rendering.cursor.enabled = true;
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let mut output = Vec::new();
+    SvgRenderer::new(options)
+        .render_animation(terminal.surface(), &frames, &mut output)
+        .unwrap();
+    let svg = String::from_utf8(output).unwrap();
+
+    // The final cursor is revealed together with the last frame.
+    let (_, cursor) = svg.rsplit_once("data-time=\"1\"").unwrap();
+    assert_eq!(cursor.matches("<animate").count(), 2);
+    assert_eq!(cursor.matches("keyTimes=\"0;0.5\"").count(), 2);
+}
+
+#[test]
+fn test_render_animation_row_ids() {
+    let mut terminal = crate::term::Terminal::new(crate::term::Options {
+        cols: Some(10),
+        rows: Some(3),
+        ..Default::default()
+    });
+
+    let mut frames = Vec::new();
+    for (time, data) in [(0.5, "alpha"), (1.0, "\rbeta ")] {
+        terminal.advance(data.as_bytes());
+        frames.push(Frame {
+            time,
+            lines: terminal
+                .surface()
+                .screen_lines()
+                .into_iter()
+                .map(Cow::into_owned)
+                .collect(),
+        });
+    }
+
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+    settings.rendering.svg.row_ids = true;
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let mut output = Vec::new();
+    SvgRenderer::new(options)
+        .render_animation(terminal.surface(), &frames, &mut output)
+        .unwrap();
+    let svg = String::from_utf8(output).unwrap();
+
+    let doc = roxmltree::Document::parse(&svg).unwrap();
+    let ids = doc
+        .descendants()
+        .filter_map(|node| node.attribute("id"))
+        .collect_vec();
+    assert!(ids.iter().all_unique(), "{ids:?}");
+    assert!(ids.contains(&"frame-0-row-0"), "{ids:?}");
+    assert!(ids.contains(&"frame-1-row-0"), "{ids:?}");
+}
+
+#[test]
+fn test_render_sixel_image() {
+    let mut terminal = crate::term::Terminal::new(crate::term::Options {
+        cols: Some(10),
+        rows: Some(3),
+        ..Default::default()
+    });
+    terminal.advance(b"line\r\nab\x1bPq#0;2;100;0;0#0~~\x1b\\");
+
+    let render = |images: bool| {
+        let mut settings = Settings::default();
+        settings.window.enabled = false;
+        settings.rendering.svg.images = images;
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+        options.images = terminal.images().iter().map(Into::into).collect();
+
+        let mut output = Vec::new();
+        SvgRenderer::new(options)
+            .render(terminal.surface(), &mut output)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    let svg = render(true);
+    assert_eq!(svg.matches("<image").count(), 1);
+    assert!(svg.contains(
+        "height=\"0.36\" preserveAspectRatio=\"none\" width=\"0.12\" x=\"1.2\" \
+         xlink:href=\"data:image/png;base64,iVBORw0KGgo"
+    ));
+    assert!(svg.contains("xmlns:xlink="));
+
+    let svg = render(false);
+    assert!(!svg.contains("<image"));
+}
+
+#[test]
+fn test_render_palette_overrides() {
+    let mut terminal = crate::term::Terminal::new(crate::term::Options {
+        cols: Some(10),
+        rows: Some(1),
+        ..Default::default()
+    });
+    terminal.advance(b"\x1b]4;1;rgb:12/34/56\x07\x1b[31mred\x1b[32mgreen");
+
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+    options.palette = terminal
+        .palette()
+        .iter()
+        .map(|(&i, color)| (i, color.as_rgba_u8().into()))
+        .collect();
+
+    let mut output = Vec::new();
+    SvgRenderer::new(options)
+        .render(terminal.surface(), &mut output)
+        .unwrap();
+    let svg = String::from_utf8(output).unwrap();
+
+    assert!(svg.contains("fill=\"#123456\">red<"));
+    assert_eq!(svg.matches("#123456").count(), 1);
+}
+
+#[test]
+fn test_make_window_title_ellipsis() {
+    let title_text = |ellipsis: Option<&str>| {
+        let mut options = Options::sample();
+        options.window.title.ellipsis = ellipsis.map(Into::into);
+        options.title = Some("a window title far too long to fit into the header".into());
+
+        let svg = make_window(&options, 200.0, 150.0, element::SVG::new()).to_string();
+        let start = svg.find("<text dominant-baseline=\"central\"").unwrap();
+        let text = &svg[start..];
+        let text = &text[text.find('>').unwrap() + 1..];
+        text[..text.find('<').unwrap()].to_string()
+    };
+
+    let title = title_text(None);
+    assert!(title.ends_with('…'), "{title}");
+
+    let title = title_text(Some("..."));
+    assert!(title.starts_with("a window"), "{title}");
+    assert!(title.ends_with("..."), "{title}");
+    assert!(!title.contains('…'));
+
+    let title = title_text(Some(""));
+    assert!(title.starts_with("a window"), "{title}");
+    assert!(!title.ends_with('…') && !title.ends_with("..."), "{title}");
+}
+
+#[test]
+fn test_make_window_per_corner_radius() {
+    let mut options = Options::sample();
+    options.window.border.radius =
+        CornerRadius::PerCorner([10.0, 10.0, 0.0, 0.0].map(Number::from));
+
+    let svg = make_window(&options, 200.0, 150.0, element::SVG::new()).to_string();
+    let height = 150.0 + options.window.header.height.f32();
+
+    // Rounded top corners, square bottom corners.
+    let top = "d=\"M10,0 H190 A10,10 0 0 1 200,10 ";
+    assert!(svg.matches(top).count() >= 3, "{svg}");
+    assert!(svg.contains(&format!(
+        "V{height} A0,0 0 0 1 200,{height} H0 A0,0 0 0 1 0,{height} V10 A10,10 0 0 1 10,0 Z"
+    )));
+    assert!(!svg.contains("rx=\"10\""));
+
+    // The uniform radius keeps using rounded rectangles.
+    options.window.border.radius = Number::from(10.0).into();
+    let svg = make_window(&options, 200.0, 150.0, element::SVG::new()).to_string();
+    assert!(svg.contains("rx=\"10\""));
+    assert!(!svg.contains(top));
+}
+
+#[test]
+fn test_place_title() {
+    let button_cfg = WindowButtons {
+        position: WindowButtonsPosition::Left,
+        shape: None,
+        size: Number::from(10.0),
+        roundness: None,
+        items: vec![WindowButton {
+            offset: Number::from(10.0),
+            fill: None,
+            stroke: None,
+            stroke_width: None,
+            icon: None,
+        }],
+    };
+    // button extent: offset 10 + half size 5 + margin 14 * 0.2
+    let extent = 17.8;
+    let place = |align| place_title(200.0, &button_cfg, 14.0, align, 14.0, 2);
+    let approx = |a: f32, b: f32| (a - b).abs() < 1e-3;
+
+    let center = place(TitleAlign::Center);
+    assert_eq!(center.anchor, "middle");
+    assert!(approx(center.x, 100.0));
+    assert!(approx(center.available_width, 200.0 - 2.0 * extent));
+
+    let left = place(TitleAlign::Left);
+    assert_eq!(left.anchor, "start");
+    assert!(approx(left.x, extent), "{left:?}");
+    assert!(
+        approx(left.available_width, 200.0 - 14.0 - extent),
+        "{left:?}"
+    );
+
+    let right = place(TitleAlign::Right);
+    assert_eq!(right.anchor, "end");
+    assert!(approx(right.x, 186.0), "{right:?}");
+    assert!(
+        approx(right.available_width, left.available_width),
+        "{right:?}"
+    );
+
+    // Buttons on the same side push a right aligned title away from the edge.
+    let button_cfg = WindowButtons {
+        position: WindowButtonsPosition::Right,
+        ..button_cfg.clone()
+    };
+    let right = place_title(200.0, &button_cfg, 14.0, TitleAlign::Right, 14.0, 2);
+    assert!(approx(right.x, 200.0 - extent), "{right:?}");
+    let left = place_title(200.0, &button_cfg, 14.0, TitleAlign::Left, 14.0, 2);
+    assert!(approx(left.x, 14.0), "{left:?}");
+}
+
+#[test]
+fn test_make_window_title_align() {
+    let title_attrs = |align| {
+        let mut options = Options::sample();
+        options.window.title.align = align;
+        options.title = Some("title".into());
+
+        let svg = make_window(&options, 200.0, 150.0, element::SVG::new()).to_string();
+        let start = svg.find("<text dominant-baseline=\"central\"").unwrap();
+        let text = &svg[start..svg[start..].find('>').unwrap() + start];
+        let attr = |name: &str| {
+            let value = &text[text.find(&format!(" {name}=\"")).unwrap() + name.len() + 3..];
+            value[..value.find('"').unwrap()].to_string()
+        };
+        let expected = place_title(
+            200.0,
+            &options.window.buttons,
+            options.font.size,
+            align,
+            options.window.header.height.f32() / 2.0,
+            options.settings.rendering.svg.precision,
+        );
+        let x: f32 = attr("x").parse().unwrap();
+        assert!((x - expected.x).abs() < 0.01, "{text}");
+        (attr("text-anchor"), x)
+    };
+
+    let (anchor, x) = title_attrs(TitleAlign::Center);
+    assert_eq!(anchor, "middle");
+    assert_eq!(x, 100.0);
+
+    let (anchor, x) = title_attrs(TitleAlign::Left);
+    assert_eq!(anchor, "start");
+    assert!(x > 0.0 && x < 100.0);
+
+    let (anchor, x) = title_attrs(TitleAlign::Right);
+    assert_eq!(anchor, "end");
+    assert!(x > 100.0 && x < 200.0);
+}
+
+#[test]
+fn test_make_buttons_triangle_and_diamond() {
+    let render = |shape| {
+        let mut options = Options::sample();
+        options.window.header.height = Number::from(20.0);
+        options.window.buttons = WindowButtons {
+            position: WindowButtonsPosition::Left,
+            shape: Some(shape),
+            size: Number::from(10.0),
+            roundness: None,
+            items: vec![WindowButton {
+                offset: Number::from(20.0),
+                fill: Some(SelectiveColor::Uniform(Color::from_rgba8(255, 0, 0, 255))),
+                stroke: Some(SelectiveColor::Uniform(Color::from_rgba8(0, 0, 255, 255))),
+                stroke_width: Some(Number::from(1.5)),
+                icon: None,
+            }],
+        };
+        make_buttons(&options, 200.0).to_string()
+    };
+
+    let svg = render(WindowButtonShape::Triangle);
+    assert!(svg.contains("d=\"M20,5 L25,15 L15,15 Z\""), "{svg}");
+    assert!(svg.contains("fill=\"#ff0000\""), "{svg}");
+    assert!(svg.contains("stroke=\"#0000ff\""), "{svg}");
+    assert!(svg.contains("stroke-width=\"1.5\""), "{svg}");
+
+    let svg = render(WindowButtonShape::Diamond);
+    assert!(svg.contains("d=\"M20,5 L25,10 L20,15 L15,10 Z\""), "{svg}");
+    assert!(svg.contains("stroke=\"#0000ff\""), "{svg}");
+    assert!(svg.contains("stroke-width=\"1.5\""), "{svg}");
+}
+
+#[test]
+fn test_make_buttons_custom_icon() {
+    let button: WindowButton = toml::from_str(
+        r##"
+        offset = 20
+        icon = { kind = { custom = "M0,0.5 H1 M0.5,0 V1" }, size = 10, stroke-width = 1.5, stroke-linecap = "round", stroke = "#0000ff" }
+        "##,
+    )
+    .unwrap();
+
+    let mut options = Options::sample();
+    options.window.header.height = Number::from(20.0);
+    options.window.buttons = WindowButtons {
+        position: WindowButtonsPosition::Left,
+        shape: None,
+        size: Number::from(10.0),
+        roundness: None,
+        items: vec![button],
+    };
+
+    let svg = make_buttons(&options, 200.0).to_string();
+    assert!(svg.contains("d=\"M0,0.5 H1 M0.5,0 V1\""), "{svg}");
+    assert!(
+        svg.contains("transform=\"translate(15,5) scale(10)\""),
+        "{svg}"
+    );
+    assert!(svg.contains("stroke=\"#0000ff\""), "{svg}");
+    assert!(svg.contains("stroke-width=\"1.5\""), "{svg}");
+    assert!(svg.contains("stroke-linecap=\"round\""), "{svg}");
+    assert!(svg.contains("fill=\"none\""), "{svg}");
+}
+
+#[test]
+fn test_render_is_deterministic() {
+    use termwiz::cell::AttributeChange;
+
+    let mut surface = Surface::new(40, 3);
+    for (i, text) in ["red", "green", "blue", "cyan"].into_iter().enumerate() {
+        surface.add_change(Change::Attribute(AttributeChange::Foreground(
+            ColorAttribute::PaletteIndex(i as u8 + 1),
+        )));
+        surface.add_change(Change::Attribute(AttributeChange::Background(
+            ColorAttribute::PaletteIndex(i as u8 + 9),
+        )));
+        surface.add_change(Change::Text(format!("{text} ")));
+    }
+    surface.add_change(Change::Attribute(AttributeChange::Foreground(
+        ColorAttribute::TrueColorWithDefaultFallback(SrgbaTuple(0.9, 0.5, 0.1, 1.0)),
+    )));
+    surface.add_change(Change::Attribute(AttributeChange::Intensity(
+        termwiz::cell::Intensity::Bold,
+    )));
+    surface.add_change(Change::Text("\r\nbold ".into()));
+    surface.add_change(Change::Attribute(AttributeChange::Italic(true)));
+    surface.add_change(Change::Text("italic\r\nplain".into()));
+
+    let mut settings = Settings::default();
+    settings.rendering.svg.var_palette = true;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+    options.font.faces = [
+        ("face-0.ttf", FontWeight::Normal, FontStyle::Normal),
+        ("face-1.ttf", FontWeight::Bold, FontStyle::Normal),
+        ("face-2.ttf", FontWeight::Normal, FontStyle::Italic),
+        ("face-3.ttf", FontWeight::Bold, FontStyle::Italic),
+    ]
+    .into_iter()
+    .map(|(url, weight, style)| FontFace {
+        family: "Monospace".to_string(),
+        weight,
+        style: Some(style),
+        url: url.to_string(),
+        format: None,
+        chars: Rc::new(crate::render::CharSetFn::new(|_| true)),
+        metrics_match: true,
+        unicode_range: Vec::new(),
+    })
+    .collect();
+
+    let render = || {
+        let mut output = Vec::new();
+        SvgRenderer::new(options.clone())
+            .render(&surface, &mut output)
+            .unwrap();
+        output
+    };
+
+    let first = render();
+    for _ in 0..8 {
+        assert!(first == render(), "output differs between renders");
+    }
+
+    // Font faces are emitted in the order they are configured.
+    let svg = String::from_utf8(first).unwrap();
+    let positions = ["face-0.ttf", "face-1.ttf", "face-2.ttf", "face-3.ttf"]
+        .into_iter()
+        .filter_map(|url| svg.find(url))
+        .collect_vec();
+    assert!(positions.len() >= 2, "{svg}");
+    assert!(positions.is_sorted(), "{positions:?}");
+}
+
+#[test]
+fn test_render_minified() {
+    use termwiz::cell::AttributeChange;
+
+    let mut surface = Surface::new(20, 3);
+    surface.add_change(Change::Attribute(AttributeChange::Foreground(
+        ColorAttribute::PaletteIndex(2),
+    )));
+    surface.add_change(Change::Text("hello  world\r\n".into()));
+    surface.add_change(Change::Attribute(AttributeChange::Background(
+        ColorAttribute::PaletteIndex(4),
+    )));
+    surface.add_change(Change::Text("a < b & c".into()));
+
+    let render = |minify: bool| {
+        let mut settings = Settings::default();
+        settings.rendering.svg.var_palette = true;
+        settings.rendering.svg.minify = minify;
+
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+
+        let mut output = Vec::new();
+        SvgRenderer::new(options)
+            .render(&surface, &mut output)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    let normal = render(false);
+    let minified = render(true);
+    assert!(
+        minified.len() < normal.len(),
+        "{} >= {}",
+        minified.len(),
+        normal.len()
+    );
+    assert!(!minified.contains(">\n<"));
+
+    let text = |svg: &str| {
+        let doc = roxmltree::Document::parse(svg).unwrap();
+        let text = doc
+            .descendants()
+            .filter(|node| node.is_text())
+            .filter(|node| {
+                node.parent_element()
+                    .is_some_and(|e| e.tag_name().name() != "style")
+            })
+            .filter_map(|node| node.text())
+            .filter(|text| !text.trim().is_empty())
+            .collect::<String>();
+        (
+            text,
+            doc.descendants().filter(|node| node.is_element()).count(),
+        )
+    };
+    assert_eq!(text(&minified), text(&normal));
+}
+
+#[test]
+fn test_render_minified_quoted_font_family() {
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Text("text".into()));
+
+    let mut settings = Settings::default();
+    settings.rendering.svg.minify = true;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+    options.font.family = vec![
+        "\"Fira Code\"".into(),
+        "'JetBrains Mono'".into(),
+        "monospace".into(),
+    ];
+
+    let mut output = Vec::new();
+    SvgRenderer::new(options)
+        .render(&surface, &mut output)
+        .unwrap();
+    let svg = String::from_utf8(output).unwrap();
+
+    let doc = roxmltree::Document::parse(&svg).unwrap();
+    let family = doc
+        .descendants()
+        .find_map(|node| node.attribute("font-family"))
+        .unwrap();
+    assert_eq!(family, "\"Fira Code\", 'JetBrains Mono', monospace");
+}
+
+#[test]
+fn test_render_compressed() {
+    use std::io::Read;
+
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::Text("zipped".into()));
+
+    let path = std::env::temp_dir().join(format!("termframe-test-{}.svgz", std::process::id()));
+    let mut file = std::fs::File::create(&path).unwrap();
+    SvgRenderer::new(Options::sample())
+        .render_animation_compressed(&surface, &[], &mut file)
+        .unwrap();
+    drop(file);
+
+    let data = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert_eq!(data[..2], [0x1f, 0x8b]);
+
+    let mut svg = String::new();
+    flate2::read::GzDecoder::new(data.as_slice())
+        .read_to_string(&mut svg)
+        .unwrap();
+    let doc = roxmltree::Document::parse(&svg).unwrap();
+    assert_eq!(doc.root_element().tag_name().name(), "svg");
+    assert!(svg.contains("zipped"));
+}
+
+#[test]
+fn test_render_id_prefix() {
+    use termwiz::cell::AttributeChange;
+
+    let mut surface = Surface::new(10, 2);
+    surface.add_change(Change::Attribute(AttributeChange::Foreground(
+        ColorAttribute::PaletteIndex(1),
+    )));
+    surface.add_change(Change::Text("red\r\n".into()));
+    surface.add_change(Change::Attribute(AttributeChange::Background(
+        ColorAttribute::PaletteIndex(4),
+    )));
+    surface.add_change(Change::Text("blue".into()));
+
+    let render = |prefix: &str| {
+        let mut settings = Settings::default();
+        settings.rendering.svg.color_classes = true;
+        settings.rendering.svg.row_ids = true;
+        settings.rendering.svg.id_prefix = prefix.into();
+
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+        options.window.shadow.enabled = true;
+        options.window.shadow.layers = vec![WindowShadowLayer {
+            color: SelectiveColor::Uniform(Color::from_rgba8(0, 0, 0, 64)),
+            x: Number::from(0.0),
+            y: Number::from(2.0),
+            blur: Number::from(4.0),
+            spread: Number::from(1.0),
+            inset: false,
+        }];
+
+        let mut output = Vec::new();
+        SvgRenderer::new(options)
+            .render(&surface, &mut output)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    let identifiers = |svg: &str| {
+        let doc = roxmltree::Document::parse(svg).unwrap();
+        let mut result = BTreeSet::new();
+        for node in doc.descendants().filter(|node| node.is_element()) {
+            result.extend(node.attribute("id").map(str::to_owned));
+            result.extend(
+                node.attribute("class")
+                    .into_iter()
+                    .flat_map(str::split_whitespace)
+                    .map(str::to_owned),
+            );
+            for attr in ["clip-path", "filter"] {
+                result.extend(
+                    node.attribute(attr)
+                        .and_then(|url| url.strip_prefix("url(#")?.strip_suffix(')'))
+                        .map(str::to_owned),
+                );
+            }
+        }
+        for (i, _) in svg.match_indices("--") {
+            let name = svg[i + 2..]
+                .split(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+                .next()
+                .unwrap();
+            result.insert(format!("--{name}"));
+        }
+        result
+    };
+
+    let first = identifiers(&render("a-"));
+    let second = identifiers(&render("b-"));
+
+    for id in [
+        "a-terminal",
+        "a-row-0",
+        "a-shadow",
+        "a-shadow-1",
+        "a-header",
+        "a-c-1",
+        "--a-c-4",
+    ] {
+        assert!(first.contains(id), "{id} not found in {first:?}");
+    }
+    assert!(
+        first
+            .iter()
+            .all(|id| id.trim_start_matches("--").starts_with("a-")),
+        "{first:?}"
+    );
+    assert!(
+        second
+            .iter()
+            .all(|id| id.trim_start_matches("--").starts_with("b-")),
+        "{second:?}"
+    );
+    assert!(first.is_disjoint(&second));
+}
+
+#[test]
+fn test_render_id_prefix_background() {
+    use termwiz::cell::AttributeChange;
+
+    let mut surface = Surface::new(10, 2);
+    surface.add_change(Change::Attribute(AttributeChange::Background(
+        ColorAttribute::PaletteIndex(4),
+    )));
+    surface.add_change(Change::Text("blue".into()));
+
+    let mut settings = Settings::default();
+    settings.rendering.svg.var_palette = true;
+    settings.rendering.svg.stroke = Some(Number::from(0.1));
+    settings.rendering.svg.id_prefix = "p-".into();
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let mut output = Vec::new();
+    SvgRenderer::new(options)
+        .render(&surface, &mut output)
+        .unwrap();
+    let svg = String::from_utf8(output).unwrap();
+
+    let doc = roxmltree::Document::parse(&svg).unwrap();
+    let path = doc
+        .descendants()
+        .find(|node| node.has_tag_name("path") && node.attribute("fill") == Some("var(--p-c-4)"))
+        .unwrap_or_else(|| panic!("background path not found in {svg}"));
+    assert_eq!(path.attribute("stroke"), Some("var(--p-c-4)"));
+    assert!(svg.contains("--p-c-4:"), "{svg}");
+    assert!(!svg.contains("var(--c-"), "{svg}");
+}
+
+#[test]
+fn test_render_line_numbers() {
+    let mut surface = Surface::new(10, 6);
+    surface.add_change(Change::Text("one\r\ntwo\r\n\r\nfour".into()));
+
+    let render = |enabled: bool| {
+        let mut settings = Settings::default();
+        settings.window.enabled = false;
+        settings.rendering.line_numbers.enabled = enabled;
+        settings.rendering.line_numbers.start = 9;
+
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+
+        let mut output = Vec::new();
+        SvgRenderer::new(options)
+            .render(&surface, &mut output)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    let plain = render(false);
+    let numbered = render(true);
+
+    for n in 9..=12 {
+        assert!(numbered.contains(&format!(">{n}</text>")), "{n} not found");
+    }
+    // Trailing blank rows are not numbered.
+    assert!(!numbered.contains(">13</text>"));
+    assert!(!plain.contains(">9</text>"));
+
+    // The gutter has two digits and a separating cell.
+    let width = |svg: &str| {
+        let doc = roxmltree::Document::parse(svg).unwrap();
+        doc.root_element()
+            .attribute("width")
+            .unwrap()
+            .parse::<f32>()
+            .unwrap()
+    };
+    assert!((width(&numbered) - width(&plain) - 21.6).abs() < 0.01);
+    assert!(numbered.contains("<svg x=\"21.6\">"));
+    assert!(!plain.contains("<svg x=\"21.6\">"));
+}
+
+#[test]
+fn test_render_invisible() {
+    use termwiz::{cell::AttributeChange, color::SrgbaTuple};
+
+    let mut surface = Surface::new(20, 1);
+    surface.add_change(Change::Text("password: ".into()));
+    surface.add_change(Change::Attribute(AttributeChange::Invisible(true)));
+    surface.add_change(Change::Attribute(AttributeChange::Foreground(
+        ColorAttribute::TrueColorWithDefaultFallback(SrgbaTuple(1.0, 0.0, 0.0, 1.0)),
+    )));
+    surface.add_change(Change::Attribute(AttributeChange::Background(
+        ColorAttribute::TrueColorWithDefaultFallback(SrgbaTuple(0.0, 0.0, 1.0, 1.0)),
+    )));
+    surface.add_change(Change::Text("secret".into()));
+
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+
+    let mut options = Options::sample();
+    options.settings = Rc::new(settings);
+
+    let mut output = Vec::new();
+    SvgRenderer::new(options)
+        .render(&surface, &mut output)
+        .unwrap();
+    let svg = String::from_utf8(output).unwrap();
+
+    assert!(svg.contains("password:"));
+    assert!(!svg.contains("secret"));
+    assert!(!svg.contains("#ff0000"));
+    // The background of the concealed text is still drawn.
+    assert!(svg.contains("#0000ff"));
+}
+
+#[test]
+fn test_render_blink() {
+    use termwiz::cell::{AttributeChange, Blink};
+
+    let mut surface = Surface::new(20, 1);
+    surface.add_change(Change::Text("steady".into()));
+    surface.add_change(Change::Attribute(AttributeChange::Blink(Blink::Slow)));
+    surface.add_change(Change::Text("slow".into()));
+    surface.add_change(Change::Attribute(AttributeChange::Blink(Blink::Rapid)));
+    surface.add_change(Change::Text("rapid".into()));
+
+    let render = |blink: bool| {
+        let mut settings = Settings::default();
+        settings.rendering.svg.blink = blink;
+
+        let mut options = Options::sample();
+        options.settings = Rc::new(settings);
+
+        let mut output = Vec::new();
+        SvgRenderer::new(options)
+            .render(&surface, &mut output)
+            .unwrap();
+        String::from_utf8(output).unwrap()
+    };
+
+    // Returns the opening tag of the element containing the text.
+    let tag = |svg: &str, text: &str| {
+        let end = svg.find(&format!(">{text}<")).unwrap();
+        svg[..end].rsplit('<').next().unwrap().to_owned()
+    };
+
+    let svg = render(true);
+    assert!(!tag(&svg, "steady").contains("class="), "{svg}");
+    assert!(tag(&svg, "slow").contains("class=\"blink\""), "{svg}");
+    assert!(
+        tag(&svg, "rapid").contains("class=\"blink-rapid\""),
+        "{svg}"
+    );
+    assert!(svg.contains("@keyframes blink {"));
+    assert!(svg.contains(".blink { animation: blink 1s step-end infinite; }"));
+
+    // Blinking text is drawn steady if the animation is disabled.
+    let svg = render(false);
+    assert!(svg.contains(">slow<"));
+    assert!(!svg.contains("blink"));
+}
+
+#[test]
+fn test_render_overline() {
+    let mut attrs = CellAttributes::default();
+    attrs.set_overline(true);
+    assert_eq!(text_decoration(&attrs), "overline");
+    attrs.set_underline(Underline::Double);
+    attrs.set_strikethrough(true);
+    assert_eq!(text_decoration(&attrs), "underline overline line-through");
+    assert_eq!(text_decoration(&CellAttributes::default()), "");
+
+    let mut surface = Surface::new(10, 1);
+    surface.add_change(Change::AllAttributes(attrs));
+    surface.add_change(Change::Text("over".into()));
+
+    let mut output = Vec::new();
+    SvgRenderer::new(Options::sample())
+        .render(&surface, &mut output)
+        .unwrap();
+    let svg = String::from_utf8(output).unwrap();
+    assert!(
+        svg.contains("text-decoration=\"underline overline line-through\""),
+        "{svg}"
+    );
+}
@@ -42,6 +42,7 @@ fn test_font_options() {
                 url: "monospace.ttf".to_string(),
                 chars: Rc::new(MockCharSet::new(|_| true)),
                 metrics_match: true,
+                unicode_range: Vec::new(),
             },
             FontFace {
                 family: "Consolas".to_string(),
@@ -51,6 +52,7 @@ fn test_font_options() {
                 url: "consolas.ttf".to_string(),
                 chars: Rc::new(MockCharSet::new(|_| true)),
                 metrics_match: true,
+                unicode_range: Vec::new(),
             },
         ],
         weights: FontWeights {
@@ -110,3 +112,176 @@ fn test_color_conversion() {
     let rgba8 = color.to_rgba8();
     assert_eq!((rgba8[0], rgba8[1], rgba8[2], rgba8[3]), (0, 255, 0, 255));
 }
+
+#[test]
+fn test_parse_highlight() {
+    use crate::render::Highlight;
+
+    let highlight: Highlight = "2".parse().unwrap();
+    assert_eq!(
+        highlight,
+        Highlight {
+            row: 2,
+            columns: None,
+            color: None
+        }
+    );
+
+    let highlight: Highlight = "3:4..10=#ff000080".parse().unwrap();
+    assert_eq!(highlight.row, 3);
+    assert_eq!(highlight.columns, Some(4..10));
+    assert_eq!(highlight.color, Some(Color::from_rgba8(255, 0, 0, 128)));
+
+    assert!("x".parse::<Highlight>().is_err());
+    assert!("1:5..2".parse::<Highlight>().is_err());
+    assert!("1:2-5".parse::<Highlight>().is_err());
+    assert!("1=nocolor".parse::<Highlight>().is_err());
+}
+
+#[test]
+fn test_diff_highlights() {
+    use crate::render::{Highlight, diff_highlights};
+    use termwiz::surface::{Change, Surface};
+
+    let mut baseline = Surface::new(10, 2);
+    baseline.add_change(Change::Text("hello\r\nworld".into()));
+    let mut current = Surface::new(10, 2);
+    current.add_change(Change::Text("hallo\r\nworld".into()));
+
+    let color = Color::from_rgba8(255, 0, 0, 128);
+    let highlights = diff_highlights(&current, &baseline, Some(color.clone()));
+
+    assert_eq!(
+        highlights,
+        vec![Highlight {
+            row: 0,
+            columns: Some(1..2),
+            color: Some(color),
+        }]
+    );
+    assert!(diff_highlights(&baseline, &baseline, None).is_empty());
+}
+
+#[test]
+fn test_parse_crop() {
+    use crate::render::Crop;
+
+    let crop: Crop = "3".parse().unwrap();
+    assert_eq!(
+        crop,
+        Crop {
+            rows: 3,
+            columns: None,
+            origin: None
+        }
+    );
+
+    let crop: Crop = "2,10@4,1".parse().unwrap();
+    assert_eq!(crop.rows, 2);
+    assert_eq!(crop.columns, Some(10));
+    assert_eq!(crop.origin, Some((4, 1)));
+
+    assert!("0".parse::<Crop>().is_err());
+    assert!("x".parse::<Crop>().is_err());
+    assert!("2,".parse::<Crop>().is_err());
+    assert!("2@1".parse::<Crop>().is_err());
+}
+
+#[test]
+fn test_crop_last_rows() {
+    use crate::render::Crop;
+    use termwiz::surface::{Change, Surface};
+
+    let mut surface = Surface::new(8, 5);
+    surface.add_change(Change::Text("one\r\ntwo\r\nthree\r\nfour\r\nfive".into()));
+
+    let crop: Crop = "3".parse().unwrap();
+    assert_eq!(crop.area(surface.dimensions()), (0..8, 2..5));
+
+    let cropped = crop.apply(&surface);
+    assert_eq!(cropped.dimensions(), (8, 3));
+    assert_eq!(
+        cropped.screen_chars_to_string(),
+        "three   \nfour    \nfive    \n"
+    );
+}
+
+#[test]
+fn test_crop_area() {
+    use crate::render::Crop;
+    use termwiz::surface::{Change, Surface};
+
+    let mut surface = Surface::new(8, 3);
+    surface.add_change(Change::Text("abcdefg\r\nijklmno\r\nqrstuvw".into()));
+
+    let crop: Crop = "2,3@2,1".parse().unwrap();
+    let cropped = crop.apply(&surface);
+    assert_eq!(cropped.dimensions(), (3, 2));
+    assert_eq!(cropped.screen_chars_to_string(), "klm\nstu\n");
+
+    // The area is clamped to the screen.
+    let crop: Crop = "5,5@6,2".parse().unwrap();
+    assert_eq!(crop.area(surface.dimensions()), (6..8, 2..3));
+}
+
+#[test]
+fn test_crop_cursor() {
+    use crate::render::Crop;
+    use termwiz::surface::{Change, CursorVisibility, Position, Surface};
+
+    let mut surface = Surface::new(8, 3);
+    surface.add_change(Change::Text("abcdefg\r\nijklmno\r\nqrstuvw".into()));
+    surface.add_change(Change::CursorPosition {
+        x: Position::Absolute(3),
+        y: Position::Absolute(1),
+    });
+
+    let crop: Crop = "2,3@2,1".parse().unwrap();
+    let cropped = crop.apply(&surface);
+    assert_eq!(cropped.cursor_position(), (1, 0));
+    assert_eq!(cropped.cursor_visibility(), CursorVisibility::Visible);
+
+    surface.add_change(Change::CursorVisibility(CursorVisibility::Hidden));
+    let cropped = crop.apply(&surface);
+    assert_eq!(cropped.cursor_visibility(), CursorVisibility::Hidden);
+
+    // The cursor outside of the area is hidden.
+    surface.add_change(Change::CursorVisibility(CursorVisibility::Visible));
+    surface.add_change(Change::CursorPosition {
+        x: Position::Absolute(0),
+        y: Position::Absolute(0),
+    });
+    let cropped = crop.apply(&surface);
+    assert_eq!(cropped.cursor_visibility(), CursorVisibility::Hidden);
+}
+
+#[test]
+fn test_crop_images() {
+    use crate::render::{Crop, Image};
+
+    let image = |column, row, size| Image {
+        row,
+        column,
+        size,
+        png: Vec::new(),
+    };
+    let mut images = vec![
+        // Starts left of the area and overlaps it.
+        image(1, 1, (2.0, 1.0)),
+        // Entirely left of the area.
+        image(0, 1, (1.5, 1.0)),
+        // Entirely right of the area.
+        image(6, 1, (1.0, 1.0)),
+        // Starts above the area and overlaps it.
+        image(3, 0, (1.0, 2.0)),
+        // Entirely above the area.
+        image(3, 0, (1.0, 1.0)),
+    ];
+
+    let crop: Crop = "2,3@2,1".parse().unwrap();
+    crop.apply_to_images((8, 3), &mut images);
+    assert_eq!(
+        images,
+        vec![image(-1, 0, (2.0, 1.0)), image(1, -1, (1.0, 2.0))]
+    );
+}
@@ -0,0 +1,42 @@
+// std imports
+use std::io;
+
+// third-party imports
+use termwiz::surface::Surface;
+
+// local imports
+use super::{Render, Result, visible_text};
+
+/// A renderer writing the screen as plain text without any attributes.
+///
+/// Concealed text is written as spaces.
+pub struct TextRenderer;
+
+impl TextRenderer {
+    /// Renders the given terminal surface to the specified target as plain text.
+    ///
+    /// Trailing whitespace of each row and trailing empty rows are omitted.
+    pub fn render(&self, surface: &Surface, target: &mut dyn io::Write) -> Result<()> {
+        let text = visible_text(surface);
+        let rows = text.lines().map(str::trim_end).collect::<Vec<_>>();
+        let len = rows
+            .iter()
+            .rposition(|row| !row.is_empty())
+            .map_or(0, |i| i + 1);
+
+        for row in &rows[..len] {
+            writeln!(target, "{row}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Render for TextRenderer {
+    fn render(&self, surface: &Surface, target: &mut dyn io::Write) -> Result<()> {
+        Self::render(self, surface, target)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,29 @@
+use super::*;
+
+use termwiz::{cell::AttributeChange, surface::Change};
+
+#[test]
+fn test_render_text() {
+    let mut surface = Surface::new(10, 4);
+    surface.add_change(Change::Text("hello  \r\n\r\nworld".into()));
+
+    let mut output = Vec::new();
+    TextRenderer.render(&surface, &mut output).unwrap();
+
+    assert_eq!(String::from_utf8(output).unwrap(), "hello\n\nworld\n");
+}
+
+#[test]
+fn test_render_text_invisible() {
+    let mut surface = Surface::new(12, 1);
+    surface.add_change(Change::Text("pw: ".into()));
+    surface.add_change(Change::Attribute(AttributeChange::Invisible(true)));
+    surface.add_change(Change::Text("secret".into()));
+    surface.add_change(Change::Attribute(AttributeChange::Invisible(false)));
+    surface.add_change(Change::Text("!".into()));
+
+    let mut output = Vec::new();
+    TextRenderer.render(&surface, &mut output).unwrap();
+
+    assert_eq!(String::from_utf8(output).unwrap(), "pw:       !\n");
+}
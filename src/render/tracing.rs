@@ -1,5 +1,7 @@
 use std::collections::{BTreeMap, VecDeque};
 
+use rayon::prelude::*;
+
 pub type Point = (i32, i32); // cell boundary coordinates.
 pub type Contour = Vec<Point>;
 pub type Path = Vec<Contour>;
@@ -10,27 +12,65 @@ pub struct Shape<K> {
     pub path: Path,
 }
 
+/// Defines which neighboring cells are considered adjacent when clustering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Connectivity {
+    /// Only horizontal and vertical neighbors are adjacent.
+    #[default]
+    Four,
+    /// Diagonal neighbors are adjacent as well.
+    Eight,
+}
+
+impl Connectivity {
+    /// Returns the (dy, dx) offsets of the neighbors.
+    fn neighbors(self) -> &'static [(isize, isize)] {
+        match self {
+            Self::Four => &[(0, 1), (1, 0), (0, -1), (-1, 0)],
+            Self::Eight => &[
+                (0, 1),
+                (1, 1),
+                (1, 0),
+                (1, -1),
+                (0, -1),
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+            ],
+        }
+    }
+}
+
 /// Entry point: Given grid dimensions and a closure to get group key of a cell,
 /// returns a vector of shapes (each with the group key and its path) formed
 /// by adjacent cells of the same group.
 /// The closure should return:
 ///   - Some(key) for a key belonging to the group identified by the key.
 ///   - None for an empty cell.
-pub fn trace<K, F>(cols: usize, rows: usize, group: F) -> Vec<Shape<K>>
+pub fn trace<K, F>(cols: usize, rows: usize, connectivity: Connectivity, group: F) -> Vec<Shape<K>>
 where
     F: FnMut(usize, usize) -> Option<K>,
-    K: PartialEq,
+    K: PartialEq + Send,
 {
-    let clusters = find_clusters(cols, rows, group);
-    let mut result = Vec::new();
-    for (key, cluster) in clusters {
-        let mask = create_mask(&cluster, cols, rows);
-        let contours = extract_contours(&mask);
-        let oriented = reorient_contours(contours);
-        let path = oriented.into_iter().map(optimize_contour).collect();
-        result.push(Shape { key, path });
-    }
-    result
+    let clusters = find_clusters(cols, rows, connectivity, group);
+
+    // Clusters are traced independently of each other, so they are processed in parallel.
+    // Collecting an indexed parallel iterator keeps the shapes in the order of the clusters.
+    clusters
+        .into_par_iter()
+        .map(|(key, cluster)| Shape {
+            key,
+            path: trace_cluster(&cluster, cols, rows),
+        })
+        .collect()
+}
+
+/// Builds the optimized outline of a single cluster of cells.
+fn trace_cluster(cluster: &[Position], cols: usize, rows: usize) -> Path {
+    let mask = create_mask(cluster, cols, rows);
+    let contours = extract_contours(&mask);
+    let oriented = reorient_contours(contours);
+    oriented.into_iter().map(optimize_contour).collect()
 }
 
 type Position = (usize, usize); // (x, y) in grid cell coordinates.
@@ -109,7 +149,13 @@ fn optimize_contour(mut contour: Contour) -> Contour {
 /// or None if a cell is empty. Cells that are empty
 /// (i.e. where comparing a cell to itself returns None) are skipped.
 /// For each cluster, the group key is saved along with the list of cells belonging to the cluster.
-fn find_clusters<K, F>(cols: usize, rows: usize, mut group: F) -> Vec<(K, Vec<Position>)>
+/// The connectivity defines whether diagonal neighbors belong to the same cluster.
+fn find_clusters<K, F>(
+    cols: usize,
+    rows: usize,
+    connectivity: Connectivity,
+    mut group: F,
+) -> Vec<(K, Vec<Position>)>
 where
     F: FnMut(usize, usize) -> Option<K>,
     K: PartialEq,
@@ -135,8 +181,8 @@ where
 
             while let Some((cx, cy)) = queue.pop_front() {
                 cluster_points.push((cx, cy)); // store as (x,y)
-                // Check 4-connected neighbors.
-                for (dy, dx) in &[(0, 1), (1, 0), (0, -1), (-1, 0)] {
+                // Check connected neighbors.
+                for (dy, dx) in connectivity.neighbors() {
                     let ny = cy as isize + dy;
                     let nx = cx as isize + dx;
                     if ny >= 0 && ny < rows as isize && nx >= 0 && nx < cols as isize {
@@ -213,6 +259,8 @@ fn extract_boundary_segments(mask: &Mask) -> Vec<(Point, Point)> {
 
 /// Groups the given line segments into simplified, closed contours.
 /// Consecutive collinear points are merged so that unnecessary intermediate points are omitted.
+/// Where regions touch at a corner, several segments start at the same point; the one turning
+/// right is preferred, so that each such region gets its own closed contour.
 fn group_segments_into_contours(segments: Vec<(Point, Point)>) -> Vec<Contour> {
     // Build a map from starting point to segments.
     let mut seg_map: BTreeMap<Point, Vec<(Point, Point)>> = BTreeMap::new();
@@ -235,7 +283,13 @@ fn group_segments_into_contours(segments: Vec<(Point, Point)>) -> Vec<Contour> {
             let next_seg = {
                 if let Some(vec) = seg_map.get_mut(&current) {
                     if !vec.is_empty() {
-                        Some(vec.remove(0))
+                        let i = contour
+                            .len()
+                            .checked_sub(2)
+                            .map(|i| right_turn(contour[i], current))
+                            .and_then(|target| vec.iter().position(|seg| seg.1 == target))
+                            .unwrap_or(0);
+                        Some(vec.remove(i))
                     } else {
                         None
                     }
@@ -271,6 +325,15 @@ fn group_segments_into_contours(segments: Vec<(Point, Point)>) -> Vec<Contour> {
     contours
 }
 
+/// Returns the point one unit away from `b` after turning right at `b` while moving from `a`.
+/// Boundary segments keep the region on their right side, so turning right at a point
+/// shared by several segments keeps the contour around the same region.
+fn right_turn(a: Point, b: Point) -> Point {
+    let dx = (b.0 - a.0).signum();
+    let dy = (b.1 - a.1).signum();
+    (b.0 - dy, b.1 + dx)
+}
+
 /// Extracts the contours (outer boundary and holes) as closed paths in integer boundary coordinates.
 fn extract_contours(mask: &Mask) -> Vec<Contour> {
     group_segments_into_contours(extract_boundary_segments(mask))
@@ -36,7 +36,7 @@ fn test_find_clusters() {
             None
         }
     };
-    let clusters = find_clusters(cols, rows, group);
+    let clusters = find_clusters(cols, rows, Connectivity::Four, group);
     assert_eq!(clusters.len(), 2);
     assert_eq!(clusters[0].0, 1);
     assert_eq!(clusters[0].1, vec![(1, 1)]);
@@ -96,3 +96,56 @@ fn test_reorient_contours() {
         ],
     );
 }
+
+#[test]
+fn test_find_clusters_eight_connectivity() {
+    let group = |x, y| if x == y { Some(1) } else { None };
+    let clusters = find_clusters(3, 3, Connectivity::Eight, group);
+    assert_eq!(clusters.len(), 1);
+    assert_eq!(clusters[0].1, vec![(0, 0), (1, 1), (2, 2)]);
+}
+
+#[test]
+fn test_trace_diagonal_pattern() {
+    // Checkerboard: cells with even x + y are filled.
+    let group = |x: usize, y: usize| if (x + y) % 2 == 0 { Some(1) } else { None };
+
+    let four = trace(4, 4, Connectivity::Four, group);
+    let eight = trace(4, 4, Connectivity::Eight, group);
+
+    assert_eq!(four.len(), 8);
+    assert_eq!(eight.len(), 1);
+    assert!(eight.len() < four.len());
+
+    // Each cell touching others only at corners still gets its own closed square contour.
+    let contours = &eight[0].path;
+    assert_eq!(contours.len(), 8);
+    for contour in contours {
+        let mut points = contour.clone();
+        points.sort();
+        let (x0, y0) = points[0];
+        assert_eq!(
+            points,
+            [(x0, y0), (x0, y0 + 1), (x0 + 1, y0), (x0 + 1, y0 + 1)]
+        );
+    }
+}
+
+#[test]
+fn test_trace_matches_serial() {
+    let (cols, rows) = (240, 120);
+    let group = |x: usize, y: usize| ((x * y) % 13 != 0).then_some((x / 3 + y / 2 * 7) % 11);
+
+    let shapes = trace(cols, rows, Connectivity::Four, group);
+    let serial = find_clusters(cols, rows, Connectivity::Four, group)
+        .into_iter()
+        .map(|(key, cluster)| (key, trace_cluster(&cluster, cols, rows)))
+        .collect::<Vec<_>>();
+
+    assert!(serial.len() > 100);
+    assert_eq!(shapes.len(), serial.len());
+    for (shape, (key, path)) in shapes.iter().zip(&serial) {
+        assert_eq!(&shape.key, key);
+        assert_eq!(&shape.path, path);
+    }
+}
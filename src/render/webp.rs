@@ -0,0 +1,71 @@
+// std imports
+use std::io;
+
+// third-party imports
+use termwiz::surface::Surface;
+
+// local imports
+use super::{Options, Render, Result};
+
+/// A renderer encoding the rasterized SVG representation of terminal surfaces to WebP.
+#[cfg_attr(not(feature = "webp"), allow(dead_code))]
+pub struct WebpRenderer {
+    options: Options,
+    scale: f32,
+    quality: Option<f32>,
+}
+
+impl WebpRenderer {
+    /// Creates a new `WebpRenderer` with the given options and scale factor for DPI.
+    ///
+    /// The image is compressed lossy with the given quality from 0 to 100, or lossless if it is not set.
+    pub fn new(options: Options, scale: f32, quality: Option<f32>) -> Self {
+        Self {
+            options,
+            scale,
+            quality,
+        }
+    }
+
+    /// Renders the given terminal surface to the specified target as a WebP image.
+    #[cfg(feature = "webp")]
+    pub fn render(&self, surface: &Surface, target: &mut dyn io::Write) -> Result<()> {
+        let pixmap = super::raster::rasterize(&self.options, self.scale, surface)?;
+
+        // The pixmap stores premultiplied colors, while the encoder expects straight ones.
+        let rgba = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect::<Vec<_>>();
+
+        let encoder = webp::Encoder::from_rgba(&rgba, pixmap.width(), pixmap.height());
+        let data = match self.quality {
+            Some(quality) => encoder.encode_simple(false, quality),
+            None => encoder.encode_simple(true, 100.0),
+        }
+        .map_err(|e| anyhow::anyhow!("failed to encode WebP image: {e:?}"))?;
+
+        target.write_all(&data)?;
+
+        Ok(())
+    }
+
+    /// Fails because termframe is built without the `webp` feature.
+    #[cfg(not(feature = "webp"))]
+    pub fn render(&self, _surface: &Surface, _target: &mut dyn io::Write) -> Result<()> {
+        anyhow::bail!("cannot render WebP output: termframe is built without webp support")
+    }
+}
+
+impl Render for WebpRenderer {
+    fn render(&self, surface: &Surface, target: &mut dyn io::Write) -> Result<()> {
+        Self::render(self, surface, target)
+    }
+}
+
+#[cfg(all(test, feature = "webp"))]
+mod tests;
@@ -0,0 +1,45 @@
+use super::*;
+
+use std::rc::Rc;
+
+use termwiz::surface::Change;
+
+use crate::config::Settings;
+
+fn options() -> Options {
+    let mut settings = Settings::default();
+    settings.window.enabled = false;
+
+    Options {
+        settings: Rc::new(settings),
+        title: None,
+        ..Options::sample()
+    }
+}
+
+/// Checks the RIFF container header and returns the format of the first chunk.
+fn webp_chunk(data: &[u8]) -> &[u8] {
+    assert_eq!(&data[..4], b"RIFF");
+    let size = u32::from_le_bytes(data[4..8].try_into().unwrap());
+    assert_eq!(size as usize, data.len() - 8);
+    assert_eq!(&data[8..12], b"WEBP");
+    &data[12..16]
+}
+
+#[test]
+fn test_render_webp() {
+    let mut surface = Surface::new(4, 2);
+    surface.add_change(Change::Text("ab".into()));
+
+    let mut lossless = Vec::new();
+    WebpRenderer::new(options(), 1.0, None)
+        .render(&surface, &mut lossless)
+        .unwrap();
+    assert_eq!(webp_chunk(&lossless), b"VP8L");
+
+    let mut lossy = Vec::new();
+    WebpRenderer::new(options(), 1.0, Some(75.0))
+        .render(&surface, &mut lossy)
+        .unwrap();
+    assert_ne!(webp_chunk(&lossy), b"VP8L");
+}
@@ -1,28 +1,38 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+    fmt,
     io::{self, BufRead, BufReader, BufWriter},
     mem,
+    ops::Range,
     sync::{
         Arc, Mutex,
-        mpsc::{Sender, channel},
+        mpsc::{Receiver, RecvTimeoutError, Sender, channel},
     },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::{Context, Result};
 use num_traits::FromPrimitive;
-use portable_pty::{ChildKiller, CommandBuilder, PtySize, native_pty_system};
+use percent_encoding::percent_decode_str;
+use portable_pty::{ChildKiller, CommandBuilder, ExitStatus, PtySize, native_pty_system};
 use termwiz::{
-    cell::AttributeChange,
+    cell::{AttributeChange, Cell, CellAttributes},
     color::{ColorAttribute, SrgbaTuple},
     escape::{
-        Action, CSI, ControlCode, OneBased, OperatingSystemCommand,
-        csi::{Cursor, Sgr},
-        osc::{ColorOrQuery, DynamicColorNumber},
+        Action, CSI, ControlCode, Esc, EscCode, OneBased, OperatingSystemCommand, Sixel,
+        csi::{
+            Cursor, DecPrivateMode, DecPrivateModeCode, Device, Edit, EraseInDisplay, EraseInLine,
+            Mode, Sgr, TabulationClear,
+        },
+        osc::{ChangeColorPair, ColorOrQuery, DynamicColorNumber},
         parser::Parser,
     },
-    surface::{Change, Line, Position, SEQ_ZERO, SequenceNo, Surface, change::ChangeSequence},
+    surface::{
+        Change, CursorVisibility, Line, Position, SEQ_ZERO, SequenceNo, Surface,
+        change::ChangeSequence,
+    },
 };
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
@@ -34,6 +44,65 @@ pub struct Options {
     pub background: Option<SrgbaTuple>,
     pub foreground: Option<SrgbaTuple>,
     pub env: HashMap<String, String>,
+    pub tab_width: Option<usize>,
+    /// Maximum number of lines kept above the visible surface, `0` disables scrollback.
+    pub scrollback_limit: Option<usize>,
+    pub formfeed: FormFeed,
+    pub wrap_mode: WrapMode,
+    pub c0_filter: C0Filter,
+    pub newline: Newline,
+    /// Attributes advertised in the reply to a primary device attributes request.
+    pub device_attributes: Vec<u16>,
+    /// Palette used to answer queries for the colors not set by the program.
+    pub palette: Vec<SrgbaTuple>,
+}
+
+/// Form feed handling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormFeed {
+    /// Treat form feed as a new line.
+    #[default]
+    Newline,
+    /// Clear the screen.
+    Clear,
+    /// Insert a blank separator row.
+    Pagebreak,
+}
+
+/// Line rewrapping mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WrapMode {
+    /// Wrap at any character.
+    #[default]
+    Char,
+    /// Wrap at whitespace, splitting only tokens that do not fit in a row.
+    Word,
+    /// Do not wrap, text of long lines past the right edge is cut off.
+    None,
+}
+
+/// Handling of stray C0 control characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum C0Filter {
+    /// Drop the control character.
+    #[default]
+    Ignore,
+    /// Print the control character in caret notation, e.g. `^@` for NUL.
+    Caret,
+    /// Print a replacement character instead of the control character.
+    Replace,
+}
+
+/// Input newline normalization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Newline {
+    /// Keep the input as is, both LF and CR LF end a line and a lone CR returns to the line start.
+    #[default]
+    Auto,
+    /// Lines end with LF, CR characters are dropped so that they never overwrite text.
+    Lf,
+    /// Lines end with CR LF, a lone CR also ends a line instead of overwriting it.
+    Crlf,
 }
 
 /// Represents a terminal with a surface, parser, state, and size.
@@ -41,6 +110,7 @@ pub struct Terminal {
     env: HashMap<String, String>,
     surface: Surface,
     parser: Parser,
+    newline: NewlineFilter,
     state: State,
     size: PtySize,
 }
@@ -69,7 +139,17 @@ impl Terminal {
             env: options.env,
             surface: Surface::new(cols.into(), rows.into()),
             parser: Parser::new(),
-            state: State::new(background, foreground, rows as usize),
+            newline: NewlineFilter::new(options.newline),
+            state: State {
+                tab_stops: TabStops::new(options.tab_width.unwrap_or(DEFAULT_TAB_WIDTH)),
+                scrollback_limit: options.scrollback_limit.unwrap_or(DEFAULT_SCROLLBACK_LIMIT),
+                formfeed: options.formfeed,
+                wrap_mode: options.wrap_mode,
+                c0_filter: options.c0_filter,
+                device_attributes: options.device_attributes,
+                base_palette: options.palette,
+                ..State::new(background, foreground, rows as usize)
+            },
             size,
         }
     }
@@ -89,6 +169,67 @@ impl Terminal {
         self.state.foreground
     }
 
+    /// Returns the cursor color set by the program via OSC 12, if any.
+    pub fn cursor_color(&self) -> Option<SrgbaTuple> {
+        self.state.cursor_color
+    }
+
+    /// Returns the current working directory reported by the program via OSC 7, if any.
+    pub fn cwd(&self) -> Option<&str> {
+        self.state.cwd.as_deref()
+    }
+
+    /// Returns the palette colors set by the program via OSC 4.
+    pub fn palette(&self) -> &BTreeMap<u8, SrgbaTuple> {
+        &self.state.palette
+    }
+
+    /// Returns the window title set by the program via OSC 0 or OSC 2, if any.
+    pub fn title(&self) -> Option<&str> {
+        self.state.title.as_deref()
+    }
+
+    /// Returns the images drawn by sixel sequences that are at least partially visible,
+    /// with rows relative to the top of the screen.
+    pub fn images(&self) -> Vec<sixel::Placement> {
+        let top = (self.state.dropped_lines + self.state.scrollback.len()) as isize;
+        let (_, height) = self.surface.dimensions();
+        self.state
+            .images
+            .iter()
+            .map(|placement| sixel::Placement {
+                row: placement.row - top,
+                ..placement.clone()
+            })
+            .filter(|placement| {
+                let (_, rows) = placement.image.cells();
+                placement.row + (rows as isize) > 0 && placement.row < height as isize
+            })
+            .collect()
+    }
+
+    /// Returns the number of lines scrolled out above the visible surface.
+    pub fn scrollback_len(&self) -> usize {
+        self.state.scrollback.len()
+    }
+
+    /// Checks whether nothing visible has been captured, neither on the screen nor in the scrollback.
+    pub fn is_blank(&self) -> bool {
+        self.recommended_width() == 0
+    }
+
+    /// Prints the placeholder text in faint style at the top of the screen if nothing has been captured.
+    ///
+    /// Returns `true` if the placeholder was printed.
+    pub fn fill_if_blank(&mut self, placeholder: &str) -> bool {
+        if !self.is_blank() {
+            return false;
+        }
+
+        self.advance(format!("\x1b[H\x1b[2m{placeholder}\x1b[0m").as_bytes());
+        true
+    }
+
     /// Feeds input from the reader to the terminal and writes output to the writer.
     pub fn feed(&mut self, mut reader: impl BufRead, mut writer: impl io::Write) -> Result<()> {
         loop {
@@ -97,27 +238,64 @@ impl Terminal {
                 return Ok(());
             }
 
-            let mut actions = Vec::new();
-            self.parser
-                .parse(buffer, |action| action.append_to(&mut actions));
-
-            for action in actions {
-                let seq = Self::apply_action_with_autowrap(
-                    &mut self.surface,
-                    &mut self.state,
-                    &mut writer,
-                    action,
-                );
-                self.surface.flush_changes_older_than(seq);
-            }
+            self.advance_with_writer(buffer, &mut writer);
 
             let len = buffer.len();
             reader.consume(len);
         }
     }
 
-    /// Runs a command in the terminal with an optional timeout.
-    pub fn run(&mut self, mut cmd: CommandBuilder, timeout: Option<Duration>) -> Result<()> {
+    /// Parses a chunk of input and applies it to the terminal.
+    ///
+    /// Incomplete escape sequences at the end of the chunk are kept by the parser
+    /// and completed by subsequent calls, so callers are free to split input at any byte.
+    /// Responses to terminal queries are discarded.
+    pub fn advance(&mut self, data: &[u8]) {
+        self.advance_with_writer(data, io::sink());
+    }
+
+    /// Parses a chunk of input, applies it to the terminal and writes responses to the writer.
+    pub fn advance_with_writer(&mut self, data: &[u8], mut writer: impl io::Write) {
+        let data = self.newline.apply(data);
+
+        let mut actions = Vec::new();
+        self.parser
+            .parse(&data, |action| action.append_to(&mut actions));
+
+        for action in actions {
+            let seq = Self::apply_action_with_autowrap(
+                &mut self.surface,
+                &mut self.state,
+                &mut writer,
+                action,
+            );
+            self.surface.flush_changes_older_than(seq);
+        }
+    }
+
+    /// Runs a command in the terminal with an optional timeout and returns its exit status.
+    pub fn run(&mut self, cmd: CommandBuilder, timeout: Option<Duration>) -> Result<ExitStatus> {
+        self.execute(cmd, timeout, false)
+    }
+
+    /// Runs a command like [`Terminal::run`], but keeps reading the output after the command exits.
+    ///
+    /// Reading stops at the end of file or when the timeout elapses since the start of the command,
+    /// so the output of processes left behind by the command is captured as well.
+    pub fn run_and_hold(
+        &mut self,
+        cmd: CommandBuilder,
+        timeout: Option<Duration>,
+    ) -> Result<ExitStatus> {
+        self.execute(cmd, timeout, true)
+    }
+
+    fn execute(
+        &mut self,
+        mut cmd: CommandBuilder,
+        timeout: Option<Duration>,
+        hold: bool,
+    ) -> Result<ExitStatus> {
         for (key, value) in &self.env {
             cmd.env(key, value);
         }
@@ -130,7 +308,12 @@ impl Terminal {
         let pty = native_pty_system();
         let pair = pty.openpty(self.size)?;
 
-        let reader = BufReader::new(pair.master.try_clone_reader()?);
+        let reader = pair.master.try_clone_reader()?;
+        let reader: Box<dyn io::Read + Send> = match timeout.filter(|_| hold) {
+            Some(timeout) => Box::new(DeadlineReader::new(reader, Instant::now() + timeout)),
+            None => reader,
+        };
+        let reader = BufReader::new(reader);
         let mut child = pair.slave.spawn_command(cmd)?;
         let killer = child.clone_killer();
 
@@ -142,10 +325,14 @@ impl Terminal {
             let wr = writer.clone();
             let thread = s.spawn(move || self.feed(reader, wr));
 
-            with_timeout(timeout, killer, s, || child.wait())?;
+            let status = with_timeout(timeout, killer, s, || child.wait())?;
+            log::debug!("command status: {status}");
 
-            log::debug!("drop writer");
-            writer.detach().flush()?;
+            // When holding, the writer is kept to answer queries of the remaining processes.
+            if !hold {
+                log::debug!("drop writer");
+                writer.detach().flush()?;
+            }
 
             log::debug!("drop child");
             drop(child);
@@ -154,18 +341,37 @@ impl Terminal {
             drop(pair);
 
             log::debug!("join processing thread");
-            thread.join().unwrap()
-        })?;
+            let result = thread.join().unwrap();
 
-        Ok(())
+            if hold {
+                log::debug!("drop writer");
+                writer.detach().flush()?;
+            }
+
+            result?;
+            Ok(status)
+        })
     }
 
     pub fn recommended_width(&self) -> u16 {
-        self.process_logical_lines_with_accumulator(0, |max_width, width| {
+        let width = self.process_logical_lines_with_accumulator(0, |max_width, width| {
             if width > *max_width {
                 *max_width = width;
             }
-        }) as u16
+        });
+        width.max(self.images_extent().0) as u16
+    }
+
+    /// Returns the number of columns and transcript rows needed to show all images.
+    fn images_extent(&self) -> (usize, usize) {
+        self.state
+            .images
+            .iter()
+            .fold((0, 0), |(width, height), placement| {
+                let (cols, rows) = placement.image.cells();
+                let row = (placement.row - self.state.dropped_lines as isize).max(0) as usize;
+                (width.max(placement.column + cols), height.max(row + rows))
+            })
     }
 
     /// Core logical line processor that handles the transcript iteration and logical line detection.
@@ -195,6 +401,7 @@ impl Terminal {
 
     /// Process logical lines from the transcript with a flexible accumulator pattern.
     /// Iterates over transcript references without cloning for optimal performance.
+    /// A final logical line is reported even if it is not terminated by a newline.
     fn process_logical_lines_with_accumulator<T, F>(&self, accumulator: T, mut callback: F) -> T
     where
         F: FnMut(&mut T, usize),
@@ -213,15 +420,30 @@ impl Terminal {
         self.size.cols = width;
     }
 
+    /// Joins wrapped rows back into logical lines and widens the surface to fit the longest of them,
+    /// so that no line is wrapped. Returns the resulting width.
+    pub fn unwrap_lines(&mut self) -> u16 {
+        let width = self.recommended_width().max(1);
+        self.set_width(width);
+        width
+    }
+
     pub fn recommended_height(&self) -> u16 {
         let (width, _) = self.surface.dimensions();
+        let wrap_mode = self.state.wrap_mode;
+        if wrap_mode == WrapMode::Word {
+            // Row count depends on word boundaries, so it has to be measured by actual reflow
+            let rows = self.reflow_transcript_to_width(width).len();
+            return rows.max(self.images_extent().1) as u16;
+        }
+
         let mut total_rows = 0;
         let mut trailing_empty_rows = 0;
 
         self.process_logical_lines_with_accumulator((), |_acc, logical_width| {
             // Calculate rows needed for this logical line using ceiling division
-            let rows_needed = if logical_width == 0 {
-                1 // Empty logical lines still take one row
+            let rows_needed = if logical_width == 0 || wrap_mode == WrapMode::None {
+                1 // Empty and unwrapped logical lines take exactly one row
             } else {
                 logical_width.div_ceil(width)
             };
@@ -237,7 +459,7 @@ impl Terminal {
         });
 
         // Don't count trailing empty logical lines
-        total_rows as u16
+        total_rows.max(self.images_extent().1) as u16
     }
 
     pub fn set_height(&mut self, height: u16) {
@@ -246,20 +468,14 @@ impl Terminal {
         self.size.rows = height;
     }
 
-    /// Build owned transcript lines (scrollback + visible).
-    /// This clones data and is used when owned Lines are needed for operations
-    /// like wrapping. For read-only operations, consider transcript_line_refs().
-    fn transcript_lines(&self) -> Vec<Line> {
-        let mut out: Vec<Line> = Vec::new();
-        // append scrollback lines (chronological order)
-        for ln in &self.state.scrollback {
-            out.push(ln.clone());
-        }
-        // append current visible rows
-        for cow in self.surface.screen_lines() {
-            out.push(cow.into_owned());
-        }
-        out
+    /// Iterate over the transcript lines (scrollback + visible) without cloning them.
+    fn transcript_lines(&self) -> impl Iterator<Item = Cow<'_, Line>> {
+        // scrollback lines in chronological order, followed by the current visible rows
+        self.state
+            .scrollback
+            .iter()
+            .map(Cow::Borrowed)
+            .chain(self.surface.screen_lines())
     }
 
     fn trimmed_line_width(line: &Line) -> usize {
@@ -275,21 +491,20 @@ impl Terminal {
         rightmost_end
     }
 
-    fn join_logical_lines(&self, lines: Vec<Line>) -> Vec<Line> {
-        self.join_logical_lines_from_iter(lines.into_iter())
-    }
-
-    /// Join logical lines from an iterator of owned Lines using the unified core processor.
-    fn join_logical_lines_from_iter<I>(&self, lines: I) -> Vec<Line>
+    /// Join wrapped rows into logical lines.
+    ///
+    /// Rows that are not continued by the following ones stay borrowed,
+    /// only the logical lines spanning several rows are materialized.
+    fn join_logical_lines<'a, I>(&self, lines: I) -> Vec<Cow<'a, Line>>
     where
-        I: Iterator<Item = Line>,
+        I: Iterator<Item = Cow<'a, Line>>,
     {
         let seq = self.surface.current_seqno();
         let mut result = Vec::new();
 
         // Create a mock processor state for the line iterator
         let mut state = LogicalLineState::new();
-        let mut current_line: Option<Line> = None;
+        let mut current_line: Option<Cow<'a, Line>> = None;
 
         for line in lines {
             let this_wrapped = line.last_cell_was_wrapped();
@@ -297,7 +512,7 @@ impl Terminal {
             if state.prev_wrapped {
                 // Continue the current logical line
                 if let Some(ref mut current) = current_line {
-                    current.append_line(line, seq);
+                    current.to_mut().append_line(line.into_owned(), seq);
                 }
             } else {
                 // Finish previous logical line and start new one
@@ -341,11 +556,12 @@ impl Terminal {
     /// - O(window_height) for surface updates
     /// - Preserves all content attributes during reflow
     fn unscroll_to_window(&mut self, new_width: usize, window_height: usize) {
-        let reflowed = self.reflow_transcript_to_width(new_width);
+        let mut reflowed = self.reflow_transcript_to_width(new_width);
         let window_start = reflowed.len().saturating_sub(window_height);
+        let window = reflowed.split_off(window_start);
 
-        self.rebuild_scrollback_from_reflowed(&reflowed, window_start);
-        self.apply_reflowed_window_to_surface(&reflowed, window_start, new_width, window_height);
+        self.rebuild_scrollback_from_reflowed(reflowed);
+        self.apply_reflowed_window_to_surface(&window, new_width, window_height);
     }
 
     /// Reflow the complete terminal transcript to the specified width.
@@ -361,8 +577,8 @@ impl Terminal {
         let logicals = self.join_logical_lines(self.transcript_lines());
 
         let mut reflowed: Vec<Line> = Vec::new();
-        for ln in logicals {
-            reflowed.extend(Self::wrap_line(ln, new_width, seq));
+        for ln in &logicals {
+            reflowed.extend(Self::wrap_line(ln, new_width, self.state.wrap_mode, seq));
         }
 
         // Trim trailing blank rows to avoid empty tail
@@ -378,8 +594,13 @@ impl Terminal {
     }
 
     /// Returns true if a cell is visually blank — a space with no non-default background color.
+    ///
+    /// Reverse video spaces are not blank because they are painted with the foreground color.
     fn is_blank_cell(cell: &termwiz::surface::line::CellRef) -> bool {
-        cell.str().trim().is_empty() && cell.attrs().background() == ColorAttribute::Default
+        let attrs = cell.attrs();
+        cell.str().trim().is_empty()
+            && attrs.background() == ColorAttribute::Default
+            && !attrs.reverse()
     }
 
     /// Wrap a logical line to the given width, preserving cells with non-default background
@@ -389,43 +610,66 @@ impl Terminal {
     /// which discards background-colored spaces (e.g. colored bar decorations). This
     /// replacement uses the same splitting logic but keeps any trailing cell that has a
     /// non-default background color.
-    fn wrap_line(line: Line, width: usize, seq: SequenceNo) -> Vec<Line> {
+    ///
+    /// The `mode` controls where rows are split: at any cell, preferably after
+    /// whitespace, or not at all, in which case the line overflows the width.
+    fn wrap_line(line: &Line, width: usize, mode: WrapMode, seq: SequenceNo) -> Vec<Line> {
         let cells: Vec<_> = line.visible_cells().collect();
 
         // Find the rightmost cell that should be kept: non-whitespace text OR colored background.
         let last_visible = cells.iter().rposition(|c| !Self::is_blank_cell(c));
 
         let Some(end_idx) = last_visible else {
-            return vec![line];
+            return vec![line.clone()];
         };
 
         let cells = &cells[..=end_idx];
 
-        let mut lines: Vec<Line> = vec![];
-        let mut delta = 0usize;
-
-        for cell in cells {
-            let need_new_line = lines
-                .last()
-                .map(|ln: &Line| ln.len() + cell.width() > width)
-                .unwrap_or(true);
+        // Columns taken by the cells from `start` to `end` inclusive when placed in a row.
+        let span = |start: usize, end: usize| {
+            cells[end].cell_index() + cells[end].width() - cells[start].cell_index()
+        };
 
-            if need_new_line {
-                if let Some(prev) = lines.last_mut() {
-                    prev.set_last_cell_was_wrapped(true, seq);
+        // Indices of the cells starting each row.
+        let mut starts = vec![0];
+        if mode != WrapMode::None {
+            for i in 1..cells.len() {
+                let start = *starts.last().unwrap();
+                if span(start, i) <= width {
+                    continue;
                 }
-                lines.push(Line::new(seq));
-                delta = cell.cell_index();
+
+                let word_start = match mode {
+                    WrapMode::Word => (start + 1..i)
+                        .rev()
+                        .find(|&j| cells[j].str() == " ")
+                        .map(|j| j + 1)
+                        .filter(|&j| span(j, i) <= width),
+                    _ => None,
+                };
+                starts.push(word_start.unwrap_or(i));
             }
+        }
 
-            let ln = lines.last_mut().unwrap();
-            ln.set_cell_grapheme(
-                cell.cell_index() - delta,
-                cell.str(),
-                cell.width(),
-                (*cell.attrs()).clone(),
-                seq,
-            );
+        let mut lines: Vec<Line> = Vec::with_capacity(starts.len());
+        for (n, &start) in starts.iter().enumerate() {
+            let end = starts.get(n + 1).copied().unwrap_or(cells.len());
+            let delta = cells[start].cell_index();
+
+            let mut ln = Line::new(seq);
+            for cell in &cells[start..end] {
+                ln.set_cell_grapheme(
+                    cell.cell_index() - delta,
+                    cell.str(),
+                    cell.width(),
+                    (*cell.attrs()).clone(),
+                    seq,
+                );
+            }
+            if end != cells.len() {
+                ln.set_last_cell_was_wrapped(true, seq);
+            }
+            lines.push(ln);
         }
 
         lines
@@ -433,13 +677,13 @@ impl Terminal {
 
     /// Rebuild the scrollback buffer from reflowed content above the visible window.
     ///
-    /// Clears the current scrollback and repopulates it with lines that fall
-    /// above the visible window in the reflowed content. This maintains the
+    /// Clears the current scrollback and repopulates it with the given lines
+    /// that fall above the visible window in the reflowed content. This maintains the
     /// scrollback limit during the rebuild process.
-    fn rebuild_scrollback_from_reflowed(&mut self, reflowed: &[Line], window_start: usize) {
+    fn rebuild_scrollback_from_reflowed(&mut self, lines: Vec<Line>) {
         self.state.scrollback.clear();
-        for ln in reflowed.iter().take(window_start) {
-            self.state.push_scrollback_line(ln.clone());
+        for ln in lines {
+            self.state.push_scrollback_line(ln);
         }
     }
 
@@ -450,8 +694,7 @@ impl Terminal {
     /// This ensures the surface and state remain synchronized.
     fn apply_reflowed_window_to_surface(
         &mut self,
-        reflowed: &[Line],
-        window_start: usize,
+        window: &[Line],
         new_width: usize,
         window_height: usize,
     ) {
@@ -459,18 +702,16 @@ impl Terminal {
         self.surface.resize(new_width, window_height);
 
         // Render the bottom window rows into the surface
-        for row in 0..window_height {
-            if let Some(ln) = reflowed.get(window_start + row) {
-                self.replace_row_with_line(row, ln);
-            }
+        for (row, ln) in window.iter().enumerate().take(window_height) {
+            self.replace_row_with_line(row, ln);
         }
 
         // Update wrap flags for visible rows
         self.state.ensure_height(window_height);
         for row in 0..window_height {
             if let Some(flag) = self.state.wrap_flags.get_mut(row) {
-                let wrapped = reflowed
-                    .get(window_start + row)
+                let wrapped = window
+                    .get(row)
                     .map(|ln| ln.last_cell_was_wrapped())
                     .unwrap_or(false); // Missing reflowed line means not wrapped
                 *flag = wrapped;
@@ -501,6 +742,11 @@ impl Terminal {
         for cell in ln.visible_cells() {
             let x = cell.cell_index();
 
+            // Clip cells overflowing the row, which happens with unwrapped lines
+            if x + cell.width() > w {
+                break;
+            }
+
             // Move cursor to the correct x for this run
             seq.add(Change::CursorPosition {
                 x: Position::Absolute(x),
@@ -530,6 +776,51 @@ impl Terminal {
         });
     }
 
+    /// Places a sixel image at the cursor and moves the cursor below it.
+    ///
+    /// Images drawn on the alternate screen are not kept,
+    /// and images larger than the screen are dropped.
+    fn apply_sixel(
+        surface: &mut Surface,
+        st: &mut State,
+        writer: &mut dyn io::Write,
+        sixel: &Sixel,
+    ) -> SequenceNo {
+        let (cols, rows) = surface.dimensions();
+        let max_width = (cols as u32).saturating_mul(sixel::CELL_WIDTH);
+        let max_height = (rows as u32).saturating_mul(sixel::CELL_HEIGHT);
+        let Some(image) = sixel::decode(sixel, st.background, max_width, max_height) else {
+            log::warn!("skip sixel image larger than {max_width}x{max_height} pixels");
+            return SEQ_ZERO;
+        };
+        if image.is_empty() {
+            return SEQ_ZERO;
+        }
+
+        let (x, y) = surface.cursor_position();
+        let (_, rows) = image.cells();
+        if st.primary_screen.is_none() {
+            st.images.push(sixel::Placement {
+                row: (st.dropped_lines + st.scrollback.len() + y) as isize,
+                column: x,
+                image: Arc::new(image),
+            });
+        }
+
+        for _ in 0..rows {
+            Self::apply_action_with_autowrap_internal(
+                surface,
+                st,
+                writer,
+                Action::Control(ControlCode::LineFeed),
+            );
+        }
+        surface.add_change(Change::CursorPosition {
+            x: Position::Absolute(x),
+            y: Position::Relative(0),
+        })
+    }
+
     /// Applies an action to the terminal's surface and state, and writes output to the writer.
     fn apply_action(
         surface: &mut Surface,
@@ -546,7 +837,11 @@ impl Terminal {
                 }
                 ControlCode::CarriageReturn => surface.add_change("\r"),
                 ControlCode::HorizontalTab => surface.add_change(Change::CursorPosition {
-                    x: Position::Absolute(tabulate(surface.cursor_position().0, 1)),
+                    x: Position::Absolute(st.tab_stops.forward(
+                        surface.cursor_position().0,
+                        1,
+                        surface.dimensions().0,
+                    )),
                     y: Position::Relative(0),
                 }),
                 ControlCode::Backspace => {
@@ -566,48 +861,73 @@ impl Terminal {
                 }
             },
             Action::CSI(csi) => match csi {
-                CSI::Sgr(sgr) => match sgr {
-                    Sgr::Reset => surface.add_change(Change::AllAttributes(Default::default())),
-                    Sgr::Intensity(intensity) => {
-                        surface.add_change(Change::Attribute(AttributeChange::Intensity(intensity)))
-                    }
-                    Sgr::Underline(underline) => {
-                        surface.add_change(Change::Attribute(AttributeChange::Underline(underline)))
-                    }
-                    Sgr::UnderlineColor(_) => SEQ_ZERO,
-                    Sgr::Blink(_) => SEQ_ZERO,
-                    Sgr::Inverse(inverse) => {
-                        surface.add_change(Change::Attribute(AttributeChange::Reverse(inverse)))
-                    }
-                    Sgr::Foreground(color) => surface
-                        .add_change(Change::Attribute(AttributeChange::Foreground(color.into()))),
-                    Sgr::Background(color) => surface
-                        .add_change(Change::Attribute(AttributeChange::Background(color.into()))),
-                    Sgr::Italic(italic) => {
-                        surface.add_change(Change::Attribute(AttributeChange::Italic(italic)))
-                    }
-                    Sgr::StrikeThrough(enabled) => surface
-                        .add_change(Change::Attribute(AttributeChange::StrikeThrough(enabled))),
-                    Sgr::Invisible(enabled) => {
-                        surface.add_change(Change::Attribute(AttributeChange::Invisible(enabled)))
+                CSI::Sgr(sgr) => {
+                    st.track_sgr(&sgr);
+                    match sgr {
+                        Sgr::Reset => surface.add_change(Change::AllAttributes(Default::default())),
+                        Sgr::Intensity(intensity) => surface
+                            .add_change(Change::Attribute(AttributeChange::Intensity(intensity))),
+                        Sgr::Underline(underline) => surface
+                            .add_change(Change::Attribute(AttributeChange::Underline(underline))),
+                        Sgr::UnderlineColor(_) => SEQ_ZERO,
+                        Sgr::Blink(blink) => {
+                            surface.add_change(Change::Attribute(AttributeChange::Blink(blink)))
+                        }
+                        Sgr::Inverse(inverse) => {
+                            surface.add_change(Change::Attribute(AttributeChange::Reverse(inverse)))
+                        }
+                        Sgr::Foreground(color) => surface.add_change(Change::Attribute(
+                            AttributeChange::Foreground(color.into()),
+                        )),
+                        Sgr::Background(color) => surface.add_change(Change::Attribute(
+                            AttributeChange::Background(color.into()),
+                        )),
+                        Sgr::Italic(italic) => {
+                            surface.add_change(Change::Attribute(AttributeChange::Italic(italic)))
+                        }
+                        Sgr::StrikeThrough(enabled) => surface
+                            .add_change(Change::Attribute(AttributeChange::StrikeThrough(enabled))),
+                        Sgr::Invisible(enabled) => surface
+                            .add_change(Change::Attribute(AttributeChange::Invisible(enabled))),
+                        Sgr::Font(_) => SEQ_ZERO,
+                        Sgr::VerticalAlign(_) => SEQ_ZERO,
+                        // The surface has no attribute change for overline, so the attributes
+                        // tracked with it are applied at once.
+                        Sgr::Overline(_) => {
+                            surface.add_change(Change::AllAttributes(st.sgr.clone()))
+                        }
                     }
-                    Sgr::Font(_) => SEQ_ZERO,
-                    Sgr::VerticalAlign(_) => SEQ_ZERO,
-                    Sgr::Overline(_) => SEQ_ZERO,
-                },
+                }
                 CSI::Cursor(cursor) => match cursor {
                     Cursor::BackwardTabulation(n) => surface.add_change(Change::CursorPosition {
-                        x: Position::Absolute(tabulate_back(
-                            surface.cursor_position().0,
-                            n as usize,
-                        )),
+                        x: Position::Absolute(
+                            st.tab_stops
+                                .backward(surface.cursor_position().0, n as usize),
+                        ),
                         y: Position::Relative(0),
                     }),
                     Cursor::ForwardTabulation(n) => surface.add_change(Change::CursorPosition {
-                        x: Position::Absolute(tabulate(surface.cursor_position().0, n as usize)),
+                        x: Position::Absolute(st.tab_stops.forward(
+                            surface.cursor_position().0,
+                            n as usize,
+                            surface.dimensions().0,
+                        )),
                         y: Position::Relative(0),
                     }),
-                    Cursor::TabulationClear(_) => SEQ_ZERO,
+                    Cursor::TabulationClear(clear) => {
+                        let (w, _) = surface.dimensions();
+                        let (x, _) = surface.cursor_position();
+                        match clear {
+                            TabulationClear::ClearCharacterTabStopAtActivePosition => {
+                                st.tab_stops.clear(x, w)
+                            }
+                            TabulationClear::ClearCharacterTabStopsAtActiveLine
+                            | TabulationClear::ClearAllCharacterTabStops
+                            | TabulationClear::ClearAllTabStops => st.tab_stops.clear_all(),
+                            _ => log::debug!("unsupported: TabulationClear({clear:?})"),
+                        }
+                        SEQ_ZERO
+                    }
                     Cursor::TabulationControl(_) => SEQ_ZERO,
                     Cursor::CharacterAbsolute(n) => surface.add_change(Change::CursorPosition {
                         x: Position::Absolute(n.as_zero_based() as usize),
@@ -677,22 +997,23 @@ impl Terminal {
                         x: Position::Absolute(col.as_zero_based() as usize),
                         y: Position::Absolute(line.as_zero_based() as usize),
                     }),
-                    Cursor::SaveCursor => {
-                        st.positions.push(surface.cursor_position());
-                        SEQ_ZERO
-                    }
-                    Cursor::RestoreCursor => {
-                        if let Some((x, y)) = st.positions.pop() {
-                            surface.add_change(Change::CursorPosition {
-                                x: Position::Absolute(x),
-                                y: Position::Absolute(y),
-                            })
-                        } else {
-                            SEQ_ZERO
+                    Cursor::SaveCursor => Self::save_cursor(surface, st),
+                    Cursor::RestoreCursor => Self::restore_cursor(surface, st),
+                    Cursor::LineTabulation(_) => SEQ_ZERO,
+                    Cursor::SetTopAndBottomMargins { top, bottom } => {
+                        let (_, h) = surface.dimensions();
+                        let top = top.as_zero_based() as usize;
+                        let bottom = (bottom.as_one_based() as usize).min(h);
+                        if top + 1 >= bottom {
+                            // The region must span at least two rows.
+                            return SEQ_ZERO;
                         }
+                        st.scroll_region = (top != 0 || bottom != h).then_some(top..bottom);
+                        surface.add_change(Change::CursorPosition {
+                            x: Position::Absolute(0),
+                            y: Position::Absolute(0),
+                        })
                     }
-                    Cursor::LineTabulation(_) => SEQ_ZERO,
-                    Cursor::SetTopAndBottomMargins { .. } => SEQ_ZERO,
                     Cursor::SetLeftAndRightMargins { .. } => SEQ_ZERO,
                     Cursor::CursorStyle(_) => SEQ_ZERO,
                     Cursor::ActivePositionReport { .. } => SEQ_ZERO,
@@ -707,14 +1028,150 @@ impl Terminal {
                         SEQ_ZERO
                     }
                 },
-                CSI::Device(device) => {
-                    log::debug!("unsupported: CSI::Device({device:?})");
-                    SEQ_ZERO
-                }
+                CSI::Device(device) => match *device {
+                    Device::RequestPrimaryDeviceAttributes => {
+                        // Identify as a VT220 with the configured extensions.
+                        let mut report = String::from("\x1b[?62");
+                        for attribute in &st.device_attributes {
+                            report.push_str(&format!(";{attribute}"));
+                        }
+                        report.push('c');
+                        log::debug!("DeviceAttributes {report:?}");
+                        writer.write_all(report.as_bytes()).ok();
+                        writer.flush().ok();
+                        SEQ_ZERO
+                    }
+                    Device::RequestSecondaryDeviceAttributes => {
+                        // A VT220 with firmware version 10 and no ROM cartridge.
+                        writer.write_all(b"\x1b[>1;10;0c").ok();
+                        writer.flush().ok();
+                        SEQ_ZERO
+                    }
+                    device => {
+                        log::debug!("unsupported: CSI::Device({device:?})");
+                        SEQ_ZERO
+                    }
+                },
+                CSI::Mode(Mode::SetDecPrivateMode(DecPrivateMode::Code(
+                    code @ (DecPrivateModeCode::ClearAndEnableAlternateScreen
+                    | DecPrivateModeCode::EnableAlternateScreen
+                    | DecPrivateModeCode::OptEnableAlternateScreen),
+                ))) => Self::enter_alternate_screen(surface, st, code),
+                CSI::Mode(Mode::ResetDecPrivateMode(DecPrivateMode::Code(
+                    DecPrivateModeCode::ClearAndEnableAlternateScreen
+                    | DecPrivateModeCode::EnableAlternateScreen
+                    | DecPrivateModeCode::OptEnableAlternateScreen,
+                ))) => Self::leave_alternate_screen(surface, st),
+                CSI::Mode(Mode::SetDecPrivateMode(DecPrivateMode::Code(
+                    DecPrivateModeCode::ShowCursor,
+                ))) => Self::set_cursor_visibility(surface, st, CursorVisibility::Visible),
+                CSI::Mode(Mode::ResetDecPrivateMode(DecPrivateMode::Code(
+                    DecPrivateModeCode::ShowCursor,
+                ))) => Self::set_cursor_visibility(surface, st, CursorVisibility::Hidden),
                 CSI::Mode(mode) => {
                     log::debug!("unsupported: CSI::Mode({mode:?})");
                     SEQ_ZERO
                 }
+                CSI::Edit(Edit::EraseInLine(erase)) => {
+                    let (w, _) = surface.dimensions();
+                    let (x, y) = surface.cursor_position();
+                    let x = x.min(w.saturating_sub(1));
+                    let cols = match erase {
+                        EraseInLine::EraseToEndOfLine => x..w,
+                        EraseInLine::EraseToStartOfLine => 0..x + 1,
+                        EraseInLine::EraseLine => 0..w,
+                    };
+                    Self::erase_cells(surface, st, y, cols);
+                    surface.current_seqno()
+                }
+                CSI::Edit(Edit::EraseInDisplay(erase)) => {
+                    let (w, h) = surface.dimensions();
+                    let (x, y) = surface.cursor_position();
+                    let x = x.min(w.saturating_sub(1));
+                    match erase {
+                        EraseInDisplay::EraseToEndOfDisplay => {
+                            Self::erase_cells(surface, st, y, x..w);
+                            for row in y + 1..h {
+                                Self::erase_cells(surface, st, row, 0..w);
+                            }
+                        }
+                        EraseInDisplay::EraseToStartOfDisplay => {
+                            for row in 0..y {
+                                Self::erase_cells(surface, st, row, 0..w);
+                            }
+                            Self::erase_cells(surface, st, y, 0..x + 1);
+                        }
+                        EraseInDisplay::EraseDisplay => {
+                            for row in 0..h {
+                                Self::erase_cells(surface, st, row, 0..w);
+                            }
+                        }
+                        EraseInDisplay::EraseScrollback => {
+                            st.dropped_lines += st.scrollback.len();
+                            st.scrollback.clear();
+                        }
+                    }
+                    surface.current_seqno()
+                }
+                CSI::Edit(Edit::InsertLine(n)) => {
+                    let (_, h) = surface.dimensions();
+                    let (_, y) = surface.cursor_position();
+                    let region = st.scroll_region(h);
+                    if !region.contains(&y) {
+                        return SEQ_ZERO;
+                    }
+                    Self::insert_lines(surface, st, y..region.end, n as usize);
+                    surface.add_change(Change::CursorPosition {
+                        x: Position::Absolute(0),
+                        y: Position::Absolute(y),
+                    })
+                }
+                CSI::Edit(Edit::DeleteLine(n)) => {
+                    let (_, h) = surface.dimensions();
+                    let (_, y) = surface.cursor_position();
+                    let region = st.scroll_region(h);
+                    if !region.contains(&y) {
+                        return SEQ_ZERO;
+                    }
+                    Self::delete_lines(surface, st, y..region.end, n as usize);
+                    surface.add_change(Change::CursorPosition {
+                        x: Position::Absolute(0),
+                        y: Position::Absolute(y),
+                    })
+                }
+                CSI::Edit(Edit::ScrollUp(n)) => {
+                    let (_, h) = surface.dimensions();
+                    let region = st.scroll_region(h);
+                    let n = (n as usize).min(region.len());
+                    if region == (0..h) {
+                        // Rows scrolled out of the whole screen are kept like the ones scrolled by line feeds.
+                        let lines = surface
+                            .screen_lines()
+                            .into_iter()
+                            .take(n)
+                            .map(Cow::into_owned);
+                        for line in lines.collect::<Vec<_>>() {
+                            st.push_scrollback_line(line);
+                        }
+                    }
+                    Self::delete_lines(surface, st, region, n);
+                    surface.current_seqno()
+                }
+                CSI::Edit(Edit::ScrollDown(n)) => {
+                    let (_, h) = surface.dimensions();
+                    let region = st.scroll_region(h);
+                    Self::insert_lines(surface, st, region, n as usize);
+                    surface.current_seqno()
+                }
+                CSI::Edit(Edit::InsertCharacter(n)) => {
+                    Self::edit_cells(surface, st, CellEdit::Insert(n as usize))
+                }
+                CSI::Edit(Edit::DeleteCharacter(n)) => {
+                    Self::edit_cells(surface, st, CellEdit::Delete(n as usize))
+                }
+                CSI::Edit(Edit::EraseCharacter(n)) => {
+                    Self::edit_cells(surface, st, CellEdit::Erase(n as usize))
+                }
                 CSI::Edit(edit) => {
                     log::debug!("unsupported: CSI::Edit({edit:?})");
                     SEQ_ZERO
@@ -751,49 +1208,169 @@ impl Terminal {
                         let which_color: Option<DynamicColorNumber> = FromPrimitive::from_u8(idx);
                         log::debug!("ChangeDynamicColors({which_color:?}): {color:?}");
                         if let Some(which_color) = which_color {
-                            let mut set_or_query = |target: &mut SrgbaTuple| match color {
+                            // Answers a query with the current color, or returns the new color to set.
+                            let mut set_or_query = |current: SrgbaTuple| match color {
                                 ColorOrQuery::Query => {
                                     let response = OperatingSystemCommand::ChangeDynamicColors(
                                         which_color,
-                                        vec![ColorOrQuery::Color(*target)],
+                                        vec![ColorOrQuery::Color(current)],
                                     );
                                     log::debug!("Color Query response {response:?}");
                                     write!(writer, "{response}").ok();
                                     writer.flush().ok();
+                                    None
                                 }
                                 ColorOrQuery::Color(c) => {
                                     log::debug!("{which_color:?} set to {c}", c = c.to_string());
-                                    *target = c
+                                    Some(c)
                                 }
                             };
                             match which_color {
                                 DynamicColorNumber::TextForegroundColor => {
-                                    set_or_query(&mut st.foreground)
+                                    if let Some(c) = set_or_query(st.foreground) {
+                                        st.foreground = c;
+                                    }
                                 }
                                 DynamicColorNumber::TextBackgroundColor => {
-                                    set_or_query(&mut st.background)
+                                    if let Some(c) = set_or_query(st.background) {
+                                        st.background = c;
+                                    }
+                                }
+                                DynamicColorNumber::TextCursorColor => {
+                                    let current = st.cursor_color.unwrap_or(st.foreground);
+                                    if let Some(c) = set_or_query(current) {
+                                        st.cursor_color = Some(c);
+                                    }
+                                }
+                                DynamicColorNumber::HighlightForegroundColor => {
+                                    let current = st.highlight_foreground.unwrap_or(st.background);
+                                    if let Some(c) = set_or_query(current) {
+                                        st.highlight_foreground = Some(c);
+                                    }
+                                }
+                                DynamicColorNumber::HighlightBackgroundColor => {
+                                    let current = st.highlight_background.unwrap_or(st.foreground);
+                                    if let Some(c) = set_or_query(current) {
+                                        st.highlight_background = Some(c);
+                                    }
                                 }
-                                DynamicColorNumber::TextCursorColor => unimplemented!(),
-                                DynamicColorNumber::HighlightForegroundColor => unimplemented!(),
-                                DynamicColorNumber::HighlightBackgroundColor => unimplemented!(),
                                 DynamicColorNumber::MouseForegroundColor
                                 | DynamicColorNumber::MouseBackgroundColor
                                 | DynamicColorNumber::TektronixForegroundColor
                                 | DynamicColorNumber::TektronixBackgroundColor
-                                | DynamicColorNumber::TektronixCursorColor => unimplemented!(),
+                                | DynamicColorNumber::TektronixCursorColor => {
+                                    log::debug!("unsupported: ChangeDynamicColors({which_color:?})")
+                                }
                             }
                         }
                         idx += 1;
                     }
                     SEQ_ZERO
                 }
+                OperatingSystemCommand::ChangeColorNumber(pairs) => {
+                    for ChangeColorPair {
+                        palette_index,
+                        color,
+                    } in pairs
+                    {
+                        match color {
+                            ColorOrQuery::Query => {
+                                let current =
+                                    st.palette.get(&palette_index).copied().or_else(|| {
+                                        st.base_palette.get(palette_index as usize).copied()
+                                    });
+                                let Some(current) = current else {
+                                    log::debug!(
+                                        "unknown color for ChangeColorNumber({palette_index}) query"
+                                    );
+                                    continue;
+                                };
+                                let response = OperatingSystemCommand::ChangeColorNumber(vec![
+                                    ChangeColorPair {
+                                        palette_index,
+                                        color: ColorOrQuery::Color(current),
+                                    },
+                                ]);
+                                log::debug!("Color Query response {response:?}");
+                                write!(writer, "{response}").ok();
+                                writer.flush().ok();
+                            }
+                            ColorOrQuery::Color(c) => {
+                                log::debug!(
+                                    "palette color {palette_index} set to {c}",
+                                    c = c.to_string()
+                                );
+                                st.palette.insert(palette_index, c);
+                            }
+                        }
+                    }
+                    SEQ_ZERO
+                }
+                OperatingSystemCommand::ResetColors(indices) => {
+                    log::debug!("ResetColors({indices:?})");
+                    if indices.is_empty() {
+                        st.palette.clear();
+                    }
+                    for index in indices {
+                        st.palette.remove(&index);
+                    }
+                    SEQ_ZERO
+                }
+                OperatingSystemCommand::SetIconNameAndWindowTitle(title)
+                | OperatingSystemCommand::SetWindowTitle(title) => {
+                    log::debug!("SetWindowTitle({title:?})");
+                    st.title = Some(title);
+                    SEQ_ZERO
+                }
+                OperatingSystemCommand::SetHyperlink(link) => {
+                    log::debug!("SetHyperlink({link:?})");
+                    let change = AttributeChange::Hyperlink(link.map(Arc::new));
+                    st.sgr.apply_change(&change);
+                    surface.add_change(Change::Attribute(change))
+                }
+                OperatingSystemCommand::CurrentWorkingDirectory(url) => {
+                    log::debug!("CurrentWorkingDirectory({url:?})");
+                    st.cwd = Some(cwd_from_url(&url));
+                    SEQ_ZERO
+                }
                 _ => {
                     log::debug!("unsupported: OperatingSystemCommand({cmd:?})");
                     SEQ_ZERO
                 }
             },
             Action::Esc(esc) => match esc {
-                termwiz::escape::Esc::Code(termwiz::escape::EscCode::StringTerminator) => SEQ_ZERO,
+                Esc::Code(EscCode::StringTerminator) => SEQ_ZERO,
+                Esc::Code(EscCode::HorizontalTabSet) => {
+                    let (w, _) = surface.dimensions();
+                    st.tab_stops.set(surface.cursor_position().0, w);
+                    SEQ_ZERO
+                }
+                Esc::Code(EscCode::DecSaveCursorPosition) => Self::save_cursor(surface, st),
+                Esc::Code(EscCode::DecRestoreCursorPosition) => Self::restore_cursor(surface, st),
+                Esc::Code(EscCode::ReverseIndex) => {
+                    let (_, h) = surface.dimensions();
+                    let (_, y) = surface.cursor_position();
+                    let region = st.scroll_region(h);
+                    if y == region.start {
+                        Self::insert_lines(surface, st, region, 1);
+                        surface.current_seqno()
+                    } else {
+                        surface.add_change(Change::CursorPosition {
+                            x: Position::Relative(0),
+                            y: Position::Relative(-1),
+                        })
+                    }
+                }
+                Esc::Code(EscCode::FullReset) => {
+                    Self::leave_alternate_screen(surface, st);
+                    st.scroll_region = None;
+                    st.tab_stops.reset();
+                    st.sgr = CellAttributes::default();
+                    st.wrap_flags.fill(false);
+                    Self::set_cursor_visibility(surface, st, CursorVisibility::Visible);
+                    surface.add_change(Change::AllAttributes(Default::default()));
+                    surface.add_change(Change::ClearScreen(ColorAttribute::Default))
+                }
                 _ => {
                     log::debug!("unsupported: Esc({esc:?})");
                     SEQ_ZERO
@@ -803,10 +1380,7 @@ impl Terminal {
                 log::debug!("unsupported: XtGetTcap({cap:?})");
                 SEQ_ZERO
             }
-            Action::Sixel(sixel) => {
-                log::debug!("unsupported: Sixel({sixel:?})");
-                SEQ_ZERO
-            }
+            Action::Sixel(sixel) => Self::apply_sixel(surface, st, &mut writer, &sixel),
             Action::KittyImage(image) => {
                 log::debug!("unsupported: KittyImage({image:?})");
                 SEQ_ZERO
@@ -828,12 +1402,18 @@ impl Terminal {
 /// - Operations are optimized for streaming terminal output
 #[derive(Debug)]
 struct State {
-    /// Saved cursor positions (currently unused legacy field)
-    positions: Vec<(usize, usize)>,
+    /// Cursor positions and graphic renditions saved by DECSC or SCOSC
+    positions: Vec<SavedCursor>,
     /// Default background color for the terminal
     background: SrgbaTuple,
     /// Default foreground color for the terminal
     foreground: SrgbaTuple,
+    /// Cursor color set via OSC 12
+    cursor_color: Option<SrgbaTuple>,
+    /// Selection foreground color set via OSC 19
+    highlight_foreground: Option<SrgbaTuple>,
+    /// Selection background color set via OSC 17
+    highlight_background: Option<SrgbaTuple>,
     /// Per-row wrap flags indicating which physical rows are soft-wrapped.
     /// Index corresponds to surface row, value indicates if that row wrapped to the next.
     /// This is essential for accurate logical line reconstruction during reflow.
@@ -844,6 +1424,38 @@ struct State {
     scrollback: VecDeque<Line>,
     /// Maximum number of lines to keep in scrollback before trimming oldest entries
     scrollback_limit: usize,
+    /// Tab stops used by horizontal tabulation
+    tab_stops: TabStops,
+    /// How form feed characters are handled
+    formfeed: FormFeed,
+    /// How long lines are split when the transcript is rewrapped
+    wrap_mode: WrapMode,
+    /// How stray C0 control characters are handled
+    c0_filter: C0Filter,
+    /// Current working directory reported via OSC 7
+    cwd: Option<String>,
+    /// Window title set via OSC 0 or OSC 2
+    title: Option<String>,
+    /// Attributes of the current graphic rendition, mirroring the ones kept by the surface
+    sgr: CellAttributes,
+    /// Rows of the scrolling region set by DECSTBM, `None` if it spans the whole screen
+    scroll_region: Option<Range<usize>>,
+    /// Primary screen saved while the alternate screen is active
+    primary_screen: Option<SavedScreen>,
+    /// Cursor visibility set by DECTCEM, shared by the primary and alternate screens
+    cursor_visibility: CursorVisibility,
+    /// Images drawn by sixel sequences, with rows counted from the first line ever captured
+    images: Vec<sixel::Placement>,
+    /// Number of lines dropped from the front of the scrollback
+    dropped_lines: usize,
+    /// Last printed grapheme, repeated by REP
+    last_grapheme: Option<String>,
+    /// Attributes advertised in the reply to DA1
+    device_attributes: Vec<u16>,
+    /// Palette colors set via OSC 4 and not reset by OSC 104 since
+    palette: BTreeMap<u8, SrgbaTuple>,
+    /// Palette used to answer OSC 4 queries for the colors not set by the program
+    base_palette: Vec<SrgbaTuple>,
 }
 
 impl State {
@@ -852,10 +1464,29 @@ impl State {
         Self {
             background,
             foreground,
+            cursor_color: None,
+            highlight_foreground: None,
+            highlight_background: None,
             positions: Vec::new(),
             wrap_flags: vec![false; height],
             scrollback: VecDeque::new(),
-            scrollback_limit: 10_000,
+            scrollback_limit: DEFAULT_SCROLLBACK_LIMIT,
+            tab_stops: TabStops::new(DEFAULT_TAB_WIDTH),
+            formfeed: FormFeed::default(),
+            wrap_mode: WrapMode::default(),
+            c0_filter: C0Filter::default(),
+            cwd: None,
+            title: None,
+            sgr: CellAttributes::default(),
+            scroll_region: None,
+            primary_screen: None,
+            cursor_visibility: CursorVisibility::Visible,
+            images: Vec::new(),
+            dropped_lines: 0,
+            last_grapheme: None,
+            device_attributes: Vec::new(),
+            palette: BTreeMap::new(),
+            base_palette: Vec::new(),
         }
     }
 
@@ -877,8 +1508,57 @@ impl State {
         }
     }
 
+    /// Remembers the last grapheme of the printed text to be repeated by REP.
+    ///
+    /// Zero-width characters, such as combining marks, extend the preceding grapheme.
+    fn track_print(&mut self, text: &str) {
+        for ch in text.chars().filter(|ch| !ch.is_control()) {
+            match (&mut self.last_grapheme, UnicodeWidthChar::width(ch)) {
+                (Some(grapheme), Some(0)) => grapheme.push(ch),
+                _ => self.last_grapheme = Some(ch.to_string()),
+            }
+        }
+    }
+
+    /// Rows scrolled by line feeds at the bottom margin, for a screen of the given height.
+    fn scroll_region(&self, height: usize) -> Range<usize> {
+        match &self.scroll_region {
+            Some(region) if region.end <= height => region.clone(),
+            _ => 0..height,
+        }
+    }
+
+    /// Keep track of the graphic rendition applied to the surface, which does not expose it.
+    fn track_sgr(&mut self, sgr: &Sgr) {
+        let change = match sgr {
+            Sgr::Reset => {
+                self.sgr = CellAttributes::default();
+                return;
+            }
+            Sgr::Intensity(intensity) => AttributeChange::Intensity(*intensity),
+            Sgr::Underline(underline) => AttributeChange::Underline(*underline),
+            Sgr::Blink(blink) => AttributeChange::Blink(*blink),
+            Sgr::Inverse(inverse) => AttributeChange::Reverse(*inverse),
+            Sgr::Foreground(color) => AttributeChange::Foreground((*color).into()),
+            Sgr::Background(color) => AttributeChange::Background((*color).into()),
+            Sgr::Italic(italic) => AttributeChange::Italic(*italic),
+            Sgr::StrikeThrough(enabled) => AttributeChange::StrikeThrough(*enabled),
+            Sgr::Invisible(enabled) => AttributeChange::Invisible(*enabled),
+            Sgr::Overline(enabled) => {
+                self.sgr.set_overline(*enabled);
+                return;
+            }
+            _ => return,
+        };
+        self.sgr.apply_change(&change);
+    }
+
     /// Push a line into scrollback and enforce the limit.
+    /// Lines scrolled off the alternate screen are not kept.
     fn push_scrollback_line(&mut self, line: Line) {
+        if self.primary_screen.is_some() {
+            return;
+        }
         self.scrollback.push_back(line);
         self.trim_scrollback_to_limit();
     }
@@ -887,6 +1567,7 @@ impl State {
     fn trim_scrollback_to_limit(&mut self) {
         while self.scrollback.len() > self.scrollback_limit {
             self.scrollback.pop_front();
+            self.dropped_lines += 1;
         }
     }
 }
@@ -944,6 +1625,71 @@ enum WriterMessage {
     Flush,
 }
 
+/// A reader that receives data from a separate thread and reports the end of file at the deadline.
+///
+/// The reading thread is detached, so a read blocked by a terminal kept open
+/// by other processes does not delay the caller beyond the deadline.
+struct DeadlineReader {
+    receiver: Receiver<io::Result<Vec<u8>>>,
+    deadline: Instant,
+    buffer: Vec<u8>,
+    pos: usize,
+}
+
+impl DeadlineReader {
+    /// Creates a new deadline reader.
+    fn new(mut reader: Box<dyn io::Read + Send>, deadline: Instant) -> Self {
+        let (sender, receiver) = channel();
+
+        std::thread::spawn(move || {
+            let mut buf = vec![0; 8192];
+            loop {
+                let data = match reader.read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => Ok(buf[..n].to_vec()),
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(err) => Err(err),
+                };
+                let failed = data.is_err();
+                if sender.send(data).is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            receiver,
+            deadline,
+            buffer: Vec::new(),
+            pos: 0,
+        }
+    }
+}
+
+impl io::Read for DeadlineReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.buffer.len() {
+            let timeout = self.deadline.saturating_duration_since(Instant::now());
+            match self.receiver.recv_timeout(timeout) {
+                Ok(data) => {
+                    self.buffer = data?;
+                    self.pos = 0;
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    log::debug!("stop reading at the deadline");
+                    return Ok(0);
+                }
+                Err(RecvTimeoutError::Disconnected) => return Ok(0),
+            }
+        }
+
+        let n = buf.len().min(self.buffer.len() - self.pos);
+        buf[..n].copy_from_slice(&self.buffer[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
 /// A writer that can be detached and replaced.
 #[derive(Clone)]
 struct DetachableWriter {
@@ -1006,15 +1752,201 @@ where
     }
 }
 
-fn tabulate(pos: usize, n: usize) -> usize {
-    pos + (TAB_STOP * n - pos % TAB_STOP)
+fn tabulate(pos: usize, n: usize, width: usize) -> usize {
+    pos + (width * n - pos % width)
+}
+
+fn tabulate_back(pos: usize, n: usize, width: usize) -> usize {
+    pos.saturating_sub(pos % width + width * (n - 1))
+}
+
+/// Extracts the percent-decoded path from a `file://host/path` URL reported via OSC 7.
+///
+/// Anything that does not look like a file URL is returned as is.
+fn cwd_from_url(url: &str) -> String {
+    match url.strip_prefix("file://") {
+        Some(rest) => {
+            let path = rest.find('/').map(|i| &rest[i..]).unwrap_or("/");
+            percent_decode_str(path).decode_utf8_lossy().into_owned()
+        }
+        None => url.to_owned(),
+    }
+}
+
+/// Returns true for C0 control codes that have no effect on the terminal,
+/// excluding the ones that are handled or intentionally silent, like BEL.
+fn is_stray_c0(code: ControlCode) -> bool {
+    (code as u8) < 0x20
+        && !matches!(
+            code,
+            ControlCode::Bell
+                | ControlCode::Backspace
+                | ControlCode::HorizontalTab
+                | ControlCode::LineFeed
+                | ControlCode::VerticalTab
+                | ControlCode::FormFeed
+                | ControlCode::CarriageReturn
+                | ControlCode::ShiftOut
+                | ControlCode::ShiftIn
+                | ControlCode::Escape
+        )
+}
+
+const DEFAULT_TAB_WIDTH: usize = 8;
+const DEFAULT_SCROLLBACK_LIMIT: usize = 10_000;
+const MAX_REPEAT: u32 = 65_535;
+
+/// Tab stops placed every `width` columns, unless adjusted by HTS or TBC.
+#[derive(Debug)]
+struct TabStops {
+    width: usize,
+    /// Explicit stop columns, `None` until the program sets or clears a stop
+    custom: Option<BTreeSet<usize>>,
+}
+
+impl TabStops {
+    fn new(width: usize) -> Self {
+        Self {
+            width: width.max(1),
+            custom: None,
+        }
+    }
+
+    /// Restores the default stops.
+    fn reset(&mut self) {
+        self.custom = None;
+    }
+
+    /// Sets a stop at the given column (HTS).
+    fn set(&mut self, x: usize, cols: usize) {
+        self.custom_mut(cols).insert(x);
+    }
+
+    /// Clears the stop at the given column (TBC 0).
+    fn clear(&mut self, x: usize, cols: usize) {
+        self.custom_mut(cols).remove(&x);
+    }
+
+    /// Clears all stops (TBC 3).
+    fn clear_all(&mut self) {
+        self.custom = Some(BTreeSet::new());
+    }
+
+    /// Returns the column of the n-th stop after `pos`, or the last column if there are no more stops.
+    fn forward(&self, pos: usize, n: usize, cols: usize) -> usize {
+        match &self.custom {
+            None => tabulate(pos, n, self.width),
+            Some(stops) => stops
+                .range(pos + 1..)
+                .nth(n.max(1) - 1)
+                .copied()
+                .unwrap_or(cols.saturating_sub(1)),
+        }
+    }
+
+    /// Returns the column of the n-th stop before `pos`, or the first column if there are no more stops.
+    fn backward(&self, pos: usize, n: usize) -> usize {
+        match &self.custom {
+            None => tabulate_back(pos, n, self.width),
+            Some(stops) => stops
+                .range(..pos)
+                .nth_back(n.max(1) - 1)
+                .copied()
+                .unwrap_or(0),
+        }
+    }
+
+    /// Materializes the default stops within the screen width before they are adjusted.
+    fn custom_mut(&mut self, cols: usize) -> &mut BTreeSet<usize> {
+        let width = self.width;
+        self.custom
+            .get_or_insert_with(|| (width..cols).step_by(width).collect())
+    }
+}
+
+/// Cursor state saved by DECSC or SCOSC.
+#[derive(Debug)]
+struct SavedCursor {
+    x: usize,
+    y: usize,
+    attrs: CellAttributes,
 }
 
-fn tabulate_back(pos: usize, n: usize) -> usize {
-    pos.saturating_sub(pos % TAB_STOP + TAB_STOP * (n - 1))
+/// Primary screen put aside while the alternate screen is active.
+struct SavedScreen {
+    surface: Surface,
+    wrap_flags: Vec<bool>,
+    /// Cursor position to restore on return, saved only by mode 1049
+    cursor: Option<(usize, usize)>,
 }
 
-const TAB_STOP: usize = 8;
+impl fmt::Debug for SavedScreen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SavedScreen")
+            .field("wrap_flags", &self.wrap_flags)
+            .field("cursor", &self.cursor)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Editing operation on the cells of a single row, each taking the number of cells.
+#[derive(Debug, Clone, Copy)]
+enum CellEdit {
+    Insert(usize),
+    Delete(usize),
+    Erase(usize),
+}
+
+/// Blanks both halves of a wide character occupying the cells `col - 1` and `col`,
+/// so that an edit starting at `col` does not split it.
+fn split_wide_cell(cells: &mut [Cell], col: usize, blank: &Cell) {
+    if col > 0 && col < cells.len() && cells[col - 1].width() > 1 {
+        cells[col - 1] = blank.clone();
+        cells[col] = blank.clone();
+    }
+}
+
+/// Normalizes line endings of the input before it is parsed.
+///
+/// Keeps track of a CR at the end of a chunk, so the input can be split at any byte.
+struct NewlineFilter {
+    mode: Newline,
+    after_cr: bool,
+}
+
+impl NewlineFilter {
+    fn new(mode: Newline) -> Self {
+        Self {
+            mode,
+            after_cr: false,
+        }
+    }
+
+    fn apply<'a>(&mut self, data: &'a [u8]) -> Cow<'a, [u8]> {
+        match self.mode {
+            Newline::Auto => Cow::Borrowed(data),
+            Newline::Lf => {
+                if !data.contains(&b'\r') {
+                    return Cow::Borrowed(data);
+                }
+                Cow::Owned(data.iter().copied().filter(|&b| b != b'\r').collect())
+            }
+            Newline::Crlf => {
+                let mut result = Vec::with_capacity(data.len());
+                for &b in data {
+                    match b {
+                        b'\r' => result.extend_from_slice(b"\r\n"),
+                        // The line has already been ended by the preceding CR.
+                        b'\n' if self.after_cr => {}
+                        _ => result.push(b),
+                    }
+                    self.after_cr = b == b'\r';
+                }
+                Cow::Owned(result)
+            }
+        }
+    }
+}
 
 /// State tracker for logical line processing that handles the wrap detection logic.
 /// This consolidates the logical line detection algorithm used by both width calculation
@@ -1108,6 +2040,85 @@ impl Terminal {
         writer: &mut dyn io::Write,
         action: Action,
     ) -> SequenceNo {
+        if let Action::Control(ControlCode::FormFeed) = action {
+            match st.formfeed {
+                FormFeed::Newline => {}
+                FormFeed::Clear => {
+                    st.wrap_flags.fill(false);
+                    // Clearing the screen also resets the current attributes.
+                    st.sgr = CellAttributes::default();
+                    return surface.add_change(Change::ClearScreen(ColorAttribute::Default));
+                }
+                FormFeed::Pagebreak => {
+                    // Finish the current line and leave a blank separator row after it.
+                    for _ in 0..2 {
+                        Self::apply_action_with_autowrap_internal(
+                            surface,
+                            st,
+                            writer,
+                            Action::Control(ControlCode::LineFeed),
+                        );
+                    }
+                    return surface.current_seqno();
+                }
+            }
+        }
+
+        if let Action::Control(code) = action
+            && is_stray_c0(code)
+        {
+            let text = match st.c0_filter {
+                C0Filter::Ignore => return SEQ_ZERO,
+                C0Filter::Caret => format!("^{}", char::from(code as u8 ^ 0x40)),
+                C0Filter::Replace => char::REPLACEMENT_CHARACTER.to_string(),
+            };
+            return Self::apply_action_with_autowrap_internal(
+                surface,
+                st,
+                writer,
+                Action::PrintString(text),
+            );
+        }
+
+        match action {
+            Action::Esc(Esc::Code(EscCode::Index)) => {
+                // A line feed keeping the cursor column.
+                let (x, _) = surface.cursor_position();
+                let action = Action::Control(ControlCode::LineFeed);
+                Self::apply_action_with_autowrap_internal(surface, st, writer, action);
+                return surface.add_change(Change::CursorPosition {
+                    x: Position::Absolute(x),
+                    y: Position::Relative(0),
+                });
+            }
+            Action::Esc(Esc::Code(EscCode::NextLine)) => {
+                let action = Action::Control(ControlCode::LineFeed);
+                return Self::apply_action_with_autowrap_internal(surface, st, writer, action);
+            }
+            Action::CSI(CSI::Edit(Edit::Repeat(n))) => {
+                // Print the last grapheme again, wrapping and scrolling as usual.
+                let Some(grapheme) = st.last_grapheme.clone() else {
+                    return SEQ_ZERO;
+                };
+                for _ in 0..n.min(MAX_REPEAT) {
+                    let action = Action::PrintString(grapheme.clone());
+                    Self::apply_action_with_autowrap_internal(surface, st, writer, action);
+                }
+                return surface.current_seqno();
+            }
+            Action::Print(ch) => st.track_print(ch.encode_utf8(&mut [0; 4])),
+            Action::PrintString(ref s) => st.track_print(s),
+            _ => {}
+        }
+
+        let (_, h) = surface.dimensions();
+        let region = st.scroll_region(h);
+        if region != (0..h)
+            && let Some(seq) = Self::apply_action_in_region(surface, st, writer, &action, region)
+        {
+            return seq;
+        }
+
         // Cursor prior to applying the action
         let (x0, y0) = surface.cursor_position();
 
@@ -1303,6 +2314,285 @@ impl Terminal {
         seq
     }
 
+    /// Push the cursor position and the current graphic rendition to the saved cursor stack.
+    fn save_cursor(surface: &mut Surface, st: &mut State) -> SequenceNo {
+        let (x, y) = surface.cursor_position();
+        st.positions.push(SavedCursor {
+            x,
+            y,
+            attrs: st.sgr.clone(),
+        });
+        SEQ_ZERO
+    }
+
+    /// Pop the cursor position and graphic rendition saved last, if any.
+    fn restore_cursor(surface: &mut Surface, st: &mut State) -> SequenceNo {
+        let Some(saved) = st.positions.pop() else {
+            return SEQ_ZERO;
+        };
+
+        st.sgr = saved.attrs;
+        surface.add_change(Change::AllAttributes(st.sgr.clone()));
+        surface.add_change(Change::CursorPosition {
+            x: Position::Absolute(saved.x),
+            y: Position::Absolute(saved.y),
+        })
+    }
+
+    /// Switch to a blank alternate screen, keeping the primary one aside until it is left.
+    /// The cursor stays in place, and with mode 1049 it is also saved for the return.
+    fn enter_alternate_screen(
+        surface: &mut Surface,
+        st: &mut State,
+        code: DecPrivateModeCode,
+    ) -> SequenceNo {
+        if st.primary_screen.is_some() {
+            return SEQ_ZERO;
+        }
+
+        let (w, h) = surface.dimensions();
+        let (x, y) = surface.cursor_position();
+        let mut alternate = Surface::new(w, h);
+        alternate.add_change(Change::CursorPosition {
+            x: Position::Absolute(x),
+            y: Position::Absolute(y),
+        });
+        alternate.add_change(Change::AllAttributes(st.sgr.clone()));
+        alternate.add_change(Change::CursorVisibility(st.cursor_visibility));
+
+        st.primary_screen = Some(SavedScreen {
+            surface: mem::replace(surface, alternate),
+            wrap_flags: mem::replace(&mut st.wrap_flags, vec![false; h]),
+            cursor: (code == DecPrivateModeCode::ClearAndEnableAlternateScreen).then_some((x, y)),
+        });
+        surface.current_seqno()
+    }
+
+    /// Return to the primary screen, discarding the contents of the alternate one.
+    fn leave_alternate_screen(surface: &mut Surface, st: &mut State) -> SequenceNo {
+        let Some(saved) = st.primary_screen.take() else {
+            return SEQ_ZERO;
+        };
+
+        let (w, h) = surface.dimensions();
+        let (x, y) = saved.cursor.unwrap_or(surface.cursor_position());
+        *surface = saved.surface;
+        st.wrap_flags = saved.wrap_flags;
+        if surface.dimensions() != (w, h) {
+            surface.resize(w, h);
+        }
+        st.ensure_height(h);
+
+        surface.add_change(Change::AllAttributes(st.sgr.clone()));
+        surface.add_change(Change::CursorVisibility(st.cursor_visibility));
+        surface.add_change(Change::CursorPosition {
+            x: Position::Absolute(x),
+            y: Position::Absolute(y),
+        })
+    }
+
+    /// Show or hide the cursor (DECTCEM), the renderer does not draw a hidden cursor.
+    fn set_cursor_visibility(
+        surface: &mut Surface,
+        st: &mut State,
+        visibility: CursorVisibility,
+    ) -> SequenceNo {
+        st.cursor_visibility = visibility;
+        surface.add_change(Change::CursorVisibility(visibility))
+    }
+
+    /// Handles line feeds and autowraps at the bottom margin while a scrolling region not spanning
+    /// the whole screen is set. Only the rows of the region are scrolled then, and nothing goes
+    /// to the scrollback. Below the region, the bottom row of the screen does not scroll at all.
+    ///
+    /// Returns `None` if the action needs no special handling.
+    fn apply_action_in_region(
+        surface: &mut Surface,
+        st: &mut State,
+        writer: &mut dyn io::Write,
+        action: &Action,
+        region: Range<usize>,
+    ) -> Option<SequenceNo> {
+        let (w, h) = surface.dimensions();
+        let (x0, y0) = surface.cursor_position();
+        let in_region = region.contains(&y0);
+        let bottom = if in_region {
+            region.end - 1
+        } else {
+            h.saturating_sub(1)
+        };
+
+        match action {
+            Action::Control(
+                ControlCode::LineFeed | ControlCode::VerticalTab | ControlCode::FormFeed,
+            ) if y0 == bottom => {
+                if in_region {
+                    Self::delete_lines(surface, st, region, 1);
+                }
+                Some(surface.add_change("\r"))
+            }
+            Action::Print(ch)
+                if y0 == bottom
+                    && UnicodeWidthChar::width(*ch).unwrap_or(0) > w.saturating_sub(x0) =>
+            {
+                if in_region {
+                    if let Some(flag) = st.wrap_flags.get_mut(y0) {
+                        *flag = true;
+                    }
+                    let seq = surface.current_seqno();
+                    Self::mark_row_soft_wrapped(surface, y0, seq);
+                    Self::delete_lines(surface, st, region, 1);
+                }
+                surface.add_change(Change::CursorPosition {
+                    x: Position::Absolute(0),
+                    y: Position::Absolute(y0),
+                });
+                Some(Self::apply_action(surface, st, writer, action.clone()))
+            }
+            Action::PrintString(s)
+                if y0 <= bottom && UnicodeWidthStr::width(s.as_str()) > w.saturating_sub(x0) =>
+            {
+                // The text may wrap at the bottom margin, so print it one character at a time.
+                for ch in s.chars() {
+                    Self::apply_action_with_autowrap_internal(
+                        surface,
+                        st,
+                        writer,
+                        Action::Print(ch),
+                    );
+                }
+                Some(surface.current_seqno())
+            }
+            _ => None,
+        }
+    }
+
+    /// Put the given cells of a row into the erased state.
+    ///
+    /// Erased cells are blanks taking the background color of the current graphic rendition,
+    /// the current attributes themselves are left intact. Erasing the end of a row also ends
+    /// its soft wrap.
+    fn erase_cells(surface: &mut Surface, st: &mut State, row: usize, cols: Range<usize>) {
+        let mut attrs = CellAttributes::default();
+        attrs.set_background(st.sgr.background());
+        let blank = Cell::new(' ', attrs);
+
+        let mut rows = surface.screen_cells();
+        let Some(cells) = rows.get_mut(row) else {
+            return;
+        };
+
+        let end = cols.end.min(cells.len());
+        let start = cols.start.min(end);
+        for cell in &mut cells[start..end] {
+            *cell = blank.clone();
+        }
+
+        if end == cells.len()
+            && start != end
+            && let Some(flag) = st.wrap_flags.get_mut(row)
+        {
+            *flag = false;
+        }
+    }
+
+    /// Insert, delete or erase cells of the cursor row starting at the cursor column.
+    ///
+    /// Wide characters cut by the edit are blanked as a whole, and the soft wrap of the row is
+    /// kept on its last cell. The cursor does not move.
+    fn edit_cells(surface: &mut Surface, st: &mut State, edit: CellEdit) -> SequenceNo {
+        let (w, h) = surface.dimensions();
+        let (x, y) = surface.cursor_position();
+        if w == 0 || y >= h {
+            return SEQ_ZERO;
+        }
+
+        let mut attrs = CellAttributes::default();
+        attrs.set_background(st.sgr.background());
+        let blank = Cell::new(' ', attrs);
+
+        let x = x.min(w - 1);
+        let mut rows = surface.screen_cells();
+        let cells = &mut *rows[y];
+
+        match edit {
+            CellEdit::Insert(n) => {
+                let n = n.clamp(1, w - x);
+                split_wide_cell(cells, x, &blank);
+                split_wide_cell(cells, w - n, &blank);
+                cells[x..].rotate_right(n);
+                cells[x..x + n].fill(blank.clone());
+            }
+            CellEdit::Delete(n) => {
+                let n = n.clamp(1, w - x);
+                split_wide_cell(cells, x, &blank);
+                split_wide_cell(cells, x + n, &blank);
+                cells[x..].rotate_left(n);
+                cells[w - n..].fill(blank.clone());
+            }
+            CellEdit::Erase(n) => {
+                let n = n.clamp(1, w - x);
+                split_wide_cell(cells, x, &blank);
+                split_wide_cell(cells, x + n, &blank);
+                cells[x..x + n].fill(blank.clone());
+            }
+        }
+
+        let wrapped = st.wrap_flags.get(y).copied().unwrap_or(false);
+        let last = if w >= 2 && cells[w - 2].width() > 1 {
+            w - 2
+        } else {
+            w - 1
+        };
+        for (i, cell) in cells.iter_mut().enumerate() {
+            cell.attrs_mut().set_wrapped(wrapped && i == last);
+        }
+
+        surface.current_seqno()
+    }
+
+    /// Insert `n` blank rows at the top of the given region, pushing the rows below down.
+    /// Rows pushed past the bottom of the region are discarded.
+    fn insert_lines(surface: &mut Surface, st: &mut State, region: Range<usize>, n: usize) {
+        let n = n.min(region.len());
+        {
+            let mut rows = surface.screen_cells();
+            for row in (region.start + n..region.end).rev() {
+                let src = rows[row - n].to_vec();
+                rows[row].clone_from_slice(&src);
+            }
+        }
+        if let Some(flags) = st.wrap_flags.get_mut(region.clone()) {
+            flags.rotate_right(n);
+        }
+
+        let (w, _) = surface.dimensions();
+        for row in region.start..region.start + n {
+            Self::erase_cells(surface, st, row, 0..w);
+        }
+    }
+
+    /// Delete `n` rows at the top of the given region, pulling the rows below up.
+    /// The rows vacated at the bottom of the region are blanked.
+    fn delete_lines(surface: &mut Surface, st: &mut State, region: Range<usize>, n: usize) {
+        let n = n.min(region.len());
+        {
+            let mut rows = surface.screen_cells();
+            for row in region.start..region.end - n {
+                let src = rows[row + n].to_vec();
+                rows[row].clone_from_slice(&src);
+            }
+        }
+        if let Some(flags) = st.wrap_flags.get_mut(region.clone()) {
+            flags.rotate_left(n);
+        }
+
+        let (w, _) = surface.dimensions();
+        for row in region.end - n..region.end {
+            Self::erase_cells(surface, st, row, 0..w);
+        }
+    }
+
     /// Mark a specific row as soft-wrapped by setting the wrapped bit on its last cell.
     /// Writes the updated line back to the surface via a minimal diff.
     fn mark_row_soft_wrapped(surface: &mut Surface, row: usize, _seq: SequenceNo) {
@@ -1327,5 +2617,8 @@ impl Terminal {
     }
 }
 
+pub mod cast;
+pub mod sixel;
+
 #[cfg(test)]
 mod tests;
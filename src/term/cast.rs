@@ -0,0 +1,87 @@
+// std imports
+use std::{collections::HashMap, io::BufRead};
+
+// third-party imports
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+/// Header of an asciicast v2 recording.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Header {
+    pub version: u8,
+    pub width: u16,
+    pub height: u16,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+/// An output event of an asciicast v2 recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    /// Time in seconds since the beginning of the recording.
+    pub time: f64,
+    /// Data printed to the terminal.
+    pub data: String,
+}
+
+/// A recorded terminal session in asciicast v2 format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    pub header: Header,
+    /// Output events in the order of appearance, other kinds of events are dropped.
+    pub events: Vec<Event>,
+}
+
+impl Recording {
+    /// Reads a recording from the header line followed by a stream of event lines.
+    pub fn read(reader: impl BufRead) -> Result<Self> {
+        let mut lines = reader
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()));
+
+        let Some((_, header)) = lines.next() else {
+            bail!("asciicast header is missing");
+        };
+        let header: Header =
+            serde_json::from_str(&header?).context("failed to parse asciicast header")?;
+        if header.version != 2 {
+            bail!("unsupported asciicast version {}", header.version);
+        }
+
+        let mut events = Vec::new();
+        for (i, line) in lines {
+            let (time, code, data): (f64, String, String) = serde_json::from_str(&line?)
+                .with_context(|| format!("failed to parse asciicast event at line {}", i + 1))?;
+            if code == "o" {
+                events.push(Event { time, data });
+            }
+        }
+
+        Ok(Self { header, events })
+    }
+
+    /// Checks whether the data starts with an asciicast header line.
+    pub fn detect(data: &[u8]) -> bool {
+        let Some(line) = data
+            .split(|&b| b == b'\n')
+            .find(|line| !line.trim_ascii().is_empty())
+        else {
+            return false;
+        };
+        serde_json::from_slice::<Header>(line).is_ok()
+    }
+
+    /// Returns all printed data concatenated.
+    pub fn output(&self) -> String {
+        self.events
+            .iter()
+            .map(|event| event.data.as_str())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,48 @@
+use super::*;
+
+use crate::term::{Options, Terminal};
+
+const CAST: &str = r#"{"version": 2, "width": 12, "height": 3, "title": "demo", "env": {"TERM": "xterm-256color"}}
+[0.1, "o", "$ echo hi\r\n"]
+[0.2, "i", "ignored"]
+[0.3, "o", "\u001b[32mhi\u001b[0m\r\n"]
+[0.4, "m", ""]
+"#;
+
+#[test]
+fn test_read_recording() {
+    let recording = Recording::read(CAST.as_bytes()).unwrap();
+
+    assert_eq!(recording.header.width, 12);
+    assert_eq!(recording.header.height, 3);
+    assert_eq!(recording.header.title.as_deref(), Some("demo"));
+    assert_eq!(recording.header.env["TERM"], "xterm-256color");
+    assert_eq!(recording.events.len(), 2);
+    assert_eq!(recording.events[1].time, 0.3);
+
+    let mut terminal = Terminal::new(Options {
+        cols: Some(recording.header.width),
+        rows: Some(recording.header.height),
+        ..Default::default()
+    });
+    terminal.advance(recording.output().as_bytes());
+
+    let text = terminal.surface().screen_chars_to_string();
+    let lines = text.lines().map(str::trim_end).collect::<Vec<_>>();
+    assert_eq!(lines, ["$ echo hi", "hi", ""]);
+}
+
+#[test]
+fn test_read_unsupported_version() {
+    let err = Recording::read(r#"{"version": 1, "width": 80, "height": 24}"#.as_bytes());
+    assert!(err.is_err());
+}
+
+#[test]
+fn test_detect_recording() {
+    assert!(Recording::detect(CAST.as_bytes()));
+    assert!(Recording::detect(format!("\n{CAST}").as_bytes()));
+    assert!(!Recording::detect(b"\x1b[32mhi\x1b[0m\r\n"));
+    assert!(!Recording::detect(b"{\"name\": \"not a header\"}\n"));
+    assert!(!Recording::detect(b""));
+}
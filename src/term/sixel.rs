@@ -0,0 +1,235 @@
+// std imports
+use std::{collections::HashMap, io::Write, sync::Arc};
+
+// third-party imports
+use flate2::{Compression, Crc, write::ZlibEncoder};
+use termwiz::{
+    color::SrgbaTuple,
+    escape::{Sixel, SixelData},
+};
+
+/// Width of a terminal cell in pixels assumed for sixel images.
+pub const CELL_WIDTH: u32 = 10;
+/// Height of a terminal cell in pixels assumed for sixel images.
+pub const CELL_HEIGHT: u32 = 20;
+
+/// A decoded bitmap image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// Pixels in RGBA order, row by row.
+    pub rgba: Vec<u8>,
+}
+
+impl Image {
+    /// Checks whether the image has no pixels.
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// Returns the number of columns and rows covered by the image, rounded up to whole cells.
+    pub fn cells(&self) -> (usize, usize) {
+        (
+            self.width.div_ceil(CELL_WIDTH) as usize,
+            self.height.div_ceil(CELL_HEIGHT) as usize,
+        )
+    }
+
+    /// Encodes the image in PNG format.
+    pub fn to_png(&self) -> Vec<u8> {
+        let stride = self.width as usize * 4;
+        let mut raw = ZlibEncoder::new(Vec::new(), Compression::default());
+        for row in self.rgba.chunks(stride.max(1)) {
+            raw.write_all(&[0]) // no filter
+                .and_then(|_| raw.write_all(row))
+                .expect("writing to a vector does not fail");
+        }
+        let data = raw.finish().expect("writing to a vector does not fail");
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&self.width.to_be_bytes());
+        header.extend_from_slice(&self.height.to_be_bytes());
+        header.extend_from_slice(&[8, 6, 0, 0, 0]); // 8-bit RGBA, deflate, no filter, no interlace
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut png, b"IHDR", &header);
+        png_chunk(&mut png, b"IDAT", &data);
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+/// An image placed at a cell.
+#[derive(Debug, Clone)]
+pub struct Placement {
+    /// Row of the top left corner of the image.
+    pub row: isize,
+    /// Column of the top left corner of the image.
+    pub column: usize,
+    pub image: Arc<Image>,
+}
+
+/// Decodes sixel data to a bitmap.
+///
+/// Pixels that are not painted get the given background color, or stay transparent
+/// if the sixel sequence asks for a transparent background.
+/// Returns `None` if the image does not fit into `max_width` x `max_height` pixels.
+pub fn decode(
+    sixel: &Sixel,
+    background: SrgbaTuple,
+    max_width: u32,
+    max_height: u32,
+) -> Option<Image> {
+    let mut palette: HashMap<u16, [u8; 4]> = DEFAULT_PALETTE
+        .iter()
+        .enumerate()
+        .map(|(i, &(r, g, b))| (i as u16, percent_rgb(r, g, b)))
+        .collect();
+
+    let mut color = palette[&0];
+    let mut pixels = Vec::new();
+    let (mut x, mut y) = (0u32, 0u32);
+    let (mut width, mut height) = (0u32, 0u32);
+
+    let mut paint = |x: u32, y: u32, bits: u8, color: [u8; 4]| {
+        if x >= max_width {
+            return None;
+        }
+        for bit in 0..6 {
+            if bits & (1 << bit) != 0 {
+                let y = y.checked_add(bit).filter(|&y| y < max_height)?;
+                pixels.push((x, y, color));
+                height = height.max(y + 1);
+            }
+        }
+        Some(())
+    };
+
+    for data in &sixel.data {
+        match *data {
+            SixelData::Data(bits) => {
+                paint(x, y, bits, color)?;
+                x += 1;
+            }
+            SixelData::Repeat { repeat_count, data } => {
+                let end = x.checked_add(repeat_count)?;
+                for x in x..end {
+                    paint(x, y, data, color)?;
+                }
+                x = end;
+            }
+            SixelData::DefineColorMapRGB {
+                color_number,
+                r,
+                g,
+                b,
+            } => {
+                palette.insert(color_number, percent_rgb(r, g, b));
+            }
+            SixelData::DefineColorMapHSL {
+                color_number,
+                hue_angle,
+                saturation,
+                lightness,
+            } => {
+                // Sixel hue starts with blue, while the conventional one starts with red.
+                let hue = (hue_angle as f32 + 240.0) % 360.0;
+                palette.insert(
+                    color_number,
+                    hsl_rgb(hue, saturation as f32 / 100.0, lightness as f32 / 100.0),
+                );
+            }
+            SixelData::SelectColorMapEntry(n) => {
+                color = palette.get(&n).copied().unwrap_or(color);
+            }
+            SixelData::CarriageReturn => x = 0,
+            SixelData::NewLine => {
+                x = 0;
+                y = y.checked_add(6)?;
+            }
+        }
+        width = width.max(x);
+    }
+
+    let width = width.max(sixel.pixel_width.unwrap_or(0));
+    let height = height.max(sixel.pixel_height.unwrap_or(0));
+    if width > max_width || height > max_height {
+        return None;
+    }
+
+    let fill = if sixel.background_is_transparent {
+        [0; 4]
+    } else {
+        let (r, g, b, _) = background.as_rgba_u8();
+        [r, g, b, 255]
+    };
+    let mut rgba = fill.repeat(width as usize * height as usize);
+    for (x, y, color) in pixels {
+        let offset = (y as usize * width as usize + x as usize) * 4;
+        rgba[offset..offset + 4].copy_from_slice(&color);
+    }
+
+    Some(Image {
+        width,
+        height,
+        rgba,
+    })
+}
+
+/// Converts color components in percent to RGBA.
+fn percent_rgb(r: u8, g: u8, b: u8) -> [u8; 4] {
+    let c = |v: u8| (v.min(100) as u32 * 255 / 100) as u8;
+    [c(r), c(g), c(b), 255]
+}
+
+/// Converts a color given by hue in degrees, saturation and lightness in range 0..=1 to RGBA.
+fn hsl_rgb(h: f32, s: f32, l: f32) -> [u8; 4] {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s.clamp(0.0, 1.0);
+    let x = c * (1.0 - ((h / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match (h / 60.0) as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    let v = |v: f32| ((v + m).clamp(0.0, 1.0) * 255.0).round() as u8;
+    [v(r), v(g), v(b), 255]
+}
+
+/// Appends a PNG chunk with the given type and data.
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let mut crc = Crc::new();
+    crc.update(&png[start..]);
+    png.extend_from_slice(&crc.sum().to_be_bytes());
+}
+
+/// Default color registers of VT340 in percent.
+const DEFAULT_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (20, 20, 80),
+    (80, 13, 13),
+    (20, 80, 20),
+    (80, 20, 80),
+    (20, 80, 80),
+    (80, 80, 20),
+    (53, 53, 53),
+    (26, 26, 26),
+    (33, 33, 60),
+    (60, 26, 26),
+    (33, 60, 33),
+    (60, 33, 60),
+    (33, 60, 60),
+    (60, 60, 33),
+    (80, 80, 80),
+];
+
+#[cfg(test)]
+mod tests;
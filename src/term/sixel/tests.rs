@@ -0,0 +1,37 @@
+use super::*;
+
+use std::io::Read;
+
+#[test]
+fn test_percent_rgb() {
+    assert_eq!(percent_rgb(100, 0, 50), [255, 0, 127, 255]);
+}
+
+#[test]
+fn test_hsl_rgb() {
+    assert_eq!(hsl_rgb(0.0, 1.0, 0.5), [255, 0, 0, 255]);
+    assert_eq!(hsl_rgb(240.0, 1.0, 0.5), [0, 0, 255, 255]);
+}
+
+#[test]
+fn test_to_png() {
+    let image = Image {
+        width: 2,
+        height: 1,
+        rgba: vec![255, 0, 0, 255, 0, 0, 255, 255],
+    };
+    let png = image.to_png();
+
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[12..16], b"IHDR");
+    assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), 2);
+    assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), 1);
+    assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+
+    let data = &png[41..png.len() - 16];
+    let mut raw = Vec::new();
+    flate2::read::ZlibDecoder::new(data)
+        .read_to_end(&mut raw)
+        .unwrap();
+    assert_eq!(raw, [0, 255, 0, 0, 255, 0, 0, 255, 255]);
+}
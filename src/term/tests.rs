@@ -10,6 +10,7 @@ fn test_autowrap_marks_wrapped_lines() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     let mut reader = Cursor::new(b"abcdef".as_ref());
@@ -46,6 +47,7 @@ fn test_explicit_newline_not_marked_wrapped() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     let mut reader = Cursor::new(b"abc\ndef".as_ref());
@@ -70,6 +72,7 @@ fn test_print_single_char_bottom_scroll() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     let mut writer = Vec::new();
@@ -119,6 +122,7 @@ fn test_autowrap_marks_on_bottom_scroll() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     let mut reader = std::io::Cursor::new(b"abcdefg".as_ref());
@@ -165,6 +169,7 @@ fn test_multiple_bottom_scrolls_preserve_wrap_and_content() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     // 12 chars: will cause several wraps and two bottom scrolls
@@ -211,6 +216,7 @@ fn test_recommended_width_autowrap() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     let mut reader = Cursor::new(b"abcdef".as_ref());
@@ -231,6 +237,7 @@ fn test_recommended_width_with_scrollback_optimization() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     // First line: "hello!" (6 chars, fits in one row)
@@ -276,6 +283,7 @@ fn test_long_lines_with_scroll_no_merge_and_correct_width() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     let s1: String = "A".repeat(17); // 17 columns
@@ -349,6 +357,7 @@ fn test_many_long_lines_scroll_no_corruption() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     // Generate 12 lines alternating characters to detect any cross-line merging.
@@ -422,6 +431,7 @@ fn test_ledger_rotates_on_lf_at_bottom() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     // "abcdef" wraps into bottom; "\n" triggers scroll from bottom
@@ -460,6 +470,7 @@ fn test_bottom_autowrap_printstring_marks_previous_row() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     let mut reader = Cursor::new(b"abcdefg".as_ref());
@@ -484,6 +495,7 @@ fn test_unscroll_rewrap_height_minimal_small_width() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     let data = "AAAAAAAAA\nBBBBBBBBB\nCCCCCCCCC\n";
@@ -508,6 +520,7 @@ fn test_building_blocks_reusability() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     // Add some content: "hello\n" + "verylongline\n" + "short"
@@ -561,6 +574,7 @@ fn test_unscroll_on_height_increase_minimal_small_width() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     let data = "AAAAAAAAA\nBBBBBBBBB\nCCCCCCCCC\n";
@@ -597,6 +611,7 @@ fn test_wrap_flags_edge_case_empty() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     let mut writer = Vec::new();
@@ -622,6 +637,7 @@ fn test_printstring_very_wide_character_breaks_loop() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     let mut writer = Vec::new();
@@ -653,6 +669,7 @@ fn test_print_wrap_within_buffer() {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     });
 
     let mut writer = Vec::new();
@@ -704,6 +721,29 @@ fn test_recommended_width_ignores_default_bg_spaces() {
     assert_eq!(term.recommended_width(), 2);
 }
 
+#[test]
+fn test_recommended_width_counts_reverse_spaces() {
+    // Reverse video spaces are painted with the foreground color, so they are visible.
+    let mut term = make_term(20, 5);
+    feed(&mut term, b"ab\x1b[7m   \x1b[0m\n");
+    assert_eq!(term.recommended_width(), 5);
+}
+
+#[test]
+fn test_rewrap_keeps_trailing_reverse_spaces() {
+    let mut term = make_term(20, 5);
+    feed(&mut term, b"ab\x1b[7m   \x1b[0m\n");
+    term.set_width(10);
+
+    let line = &term.surface().screen_lines()[0];
+    let reversed: Vec<_> = line
+        .visible_cells()
+        .filter(|cell| cell.attrs().reverse())
+        .map(|cell| cell.cell_index())
+        .collect();
+    assert_eq!(reversed, vec![2, 3, 4]);
+}
+
 fn make_term(cols: u16, rows: u16) -> Terminal {
     Terminal::new(Options {
         cols: Some(cols),
@@ -711,6 +751,7 @@ fn make_term(cols: u16, rows: u16) -> Terminal {
         background: None,
         foreground: None,
         env: HashMap::new(),
+        ..Default::default()
     })
 }
 
@@ -757,3 +798,1018 @@ fn test_show_command_with_special_chars() {
     assert!(line0.contains("$ "), "line 0 missing prompt: {line0:?}");
     assert!(line0.contains("echo"), "line 0 missing command: {line0:?}");
 }
+
+#[test]
+fn test_show_command_with_styled_prompt() {
+    let mut term = make_term(80, 3);
+
+    let prompt = crate::command::Prompt {
+        text: "% ",
+        style: "1;32",
+    };
+    let cmd_line = crate::command::to_terminal(prompt, "cargo", ["build"], None);
+    feed(&mut term, &cmd_line);
+    feed(&mut term, b"Finished\n");
+
+    let line0 = visible_line_text(&term, 0);
+    assert!(
+        line0.starts_with("% cargo build"),
+        "line 0 missing prompt and command: {line0:?}"
+    );
+
+    let line = &term.surface().screen_lines()[0];
+    let cell = line.visible_cells().next().unwrap();
+    assert_eq!(cell.attrs().intensity(), termwiz::cell::Intensity::Bold);
+    assert_eq!(cell.attrs().foreground(), ColorAttribute::PaletteIndex(2));
+
+    let line1 = visible_line_text(&term, 1);
+    assert!(
+        line1.starts_with("Finished"),
+        "line 1 missing output: {line1:?}"
+    );
+}
+
+#[test]
+fn test_tab_width() {
+    let mut term = Terminal::new(Options {
+        cols: Some(20),
+        rows: Some(2),
+        tab_width: Some(4),
+        ..Default::default()
+    });
+
+    feed(&mut term, b"\tx");
+
+    assert_eq!(term.surface().cursor_position(), (5, 0));
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "    x");
+}
+
+#[test]
+fn test_default_tab_width() {
+    let mut term = make_term(20, 2);
+
+    feed(&mut term, b"ab\tx");
+
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "ab      x");
+}
+
+#[test]
+fn test_formfeed_clear() {
+    let mut term = Terminal::new(Options {
+        cols: Some(10),
+        rows: Some(3),
+        formfeed: FormFeed::Clear,
+        ..Default::default()
+    });
+
+    feed(&mut term, b"one\ntwo\x0c");
+
+    for row in 0..3 {
+        assert_eq!(visible_line_text(&term, row).trim_end(), "");
+    }
+    assert_eq!(term.surface().cursor_position(), (0, 0));
+
+    feed(&mut term, b"three");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "three");
+}
+
+#[test]
+fn test_formfeed_newline() {
+    let mut term = make_term(10, 3);
+
+    feed(&mut term, b"one\x0ctwo");
+
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "one");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "two");
+}
+
+#[test]
+fn test_formfeed_pagebreak() {
+    let mut term = Terminal::new(Options {
+        cols: Some(10),
+        rows: Some(3),
+        formfeed: FormFeed::Pagebreak,
+        ..Default::default()
+    });
+
+    feed(&mut term, b"one\x0ctwo");
+
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "one");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "");
+    assert_eq!(visible_line_text(&term, 2).trim_end(), "two");
+}
+
+#[test]
+fn test_advance_with_split_escape_sequence() {
+    let mut term = make_term(10, 2);
+
+    term.advance(b"a\x1b[");
+    term.advance(b"1mb");
+
+    let line = &term.surface().screen_lines()[0];
+    let a = line.get_cell(0).unwrap();
+    let b = line.get_cell(1).unwrap();
+    assert_eq!(a.str(), "a");
+    assert_eq!(a.attrs().intensity(), termwiz::cell::Intensity::Normal);
+    assert_eq!(b.str(), "b");
+    assert_eq!(b.attrs().intensity(), termwiz::cell::Intensity::Bold);
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "ab");
+}
+
+fn rewrap_long_token(wrap_mode: WrapMode) -> Terminal {
+    let mut term = Terminal::new(Options {
+        cols: Some(30),
+        rows: Some(4),
+        wrap_mode,
+        ..Default::default()
+    });
+
+    feed(&mut term, b"aaaaaaa bbbbbbbbbbbb");
+    term.set_width(10);
+    let height = term.recommended_height();
+    term.set_height(height);
+    term
+}
+
+#[test]
+fn test_wrap_mode_char() {
+    let term = rewrap_long_token(WrapMode::Char);
+
+    assert_eq!(term.surface().dimensions(), (10, 2));
+    assert_eq!(visible_line_text(&term, 0), "aaaaaaa bb");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "bbbbbbbbbb");
+}
+
+#[test]
+fn test_wrap_mode_word() {
+    let term = rewrap_long_token(WrapMode::Word);
+
+    assert_eq!(term.surface().dimensions(), (10, 3));
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "aaaaaaa");
+    assert_eq!(visible_line_text(&term, 1), "bbbbbbbbbb");
+    assert_eq!(visible_line_text(&term, 2).trim_end(), "bb");
+}
+
+#[test]
+fn test_wrap_mode_none() {
+    let term = rewrap_long_token(WrapMode::None);
+
+    assert_eq!(term.surface().dimensions(), (10, 1));
+    assert_eq!(visible_line_text(&term, 0), "aaaaaaa bb");
+}
+
+fn feed_with_c0_filter(c0_filter: C0Filter, data: &[u8]) -> Terminal {
+    let mut term = Terminal::new(Options {
+        cols: Some(10),
+        rows: Some(2),
+        c0_filter,
+        ..Default::default()
+    });
+
+    feed(&mut term, data);
+    term
+}
+
+#[test]
+fn test_c0_filter_ignore() {
+    let term = feed_with_c0_filter(C0Filter::Ignore, b"a\x00b\x07c");
+
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "abc");
+    assert_eq!(term.surface().cursor_position(), (3, 0));
+}
+
+#[test]
+fn test_c0_filter_caret() {
+    let term = feed_with_c0_filter(C0Filter::Caret, b"a\x00b\x07c\x1fd");
+
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "a^@bc^_d");
+    assert_eq!(term.surface().cursor_position(), (8, 0));
+}
+
+#[test]
+fn test_c0_filter_replace() {
+    let term = feed_with_c0_filter(C0Filter::Replace, b"a\x00b");
+
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "a\u{fffd}b");
+    assert_eq!(term.surface().cursor_position(), (3, 0));
+}
+
+#[test]
+fn test_osc7_cwd() {
+    let mut term = make_term(10, 2);
+    assert_eq!(term.cwd(), None);
+
+    feed(&mut term, b"\x1b]7;file://devbox/home/alice\x07");
+
+    assert_eq!(term.cwd(), Some("/home/alice"));
+
+    feed(
+        &mut term,
+        b"\x1b]7;file://devbox/home/alice/My%20Files/%C3%A9t%C3%A9\x07",
+    );
+
+    assert_eq!(term.cwd(), Some("/home/alice/My Files/\u{e9}t\u{e9}"));
+}
+
+#[test]
+fn test_unwrap_lines() {
+    let mut term = make_term(10, 5);
+    feed(&mut term, b"abcdefghijklmnopqrstuvwxy\r\nz\r\n");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "abcdefghij");
+
+    assert_eq!(term.unwrap_lines(), 25);
+    term.set_height(term.recommended_height());
+
+    assert_eq!(term.surface().dimensions(), (25, 2));
+    assert_eq!(visible_line_text(&term, 0), "abcdefghijklmnopqrstuvwxy");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "z");
+}
+
+#[test]
+fn test_final_unterminated_line_counts() {
+    let logical_lines = |term: &Terminal| {
+        term.process_logical_lines_with_accumulator(Vec::new(), |acc, width| acc.push(width))
+    };
+
+    let mut term = make_term(10, 5);
+    feed(&mut term, b"a\nb");
+    // The blank rows below the output are reported as empty logical lines.
+    assert_eq!(logical_lines(&term), [1, 1, 0, 0, 0]);
+    assert_eq!(term.recommended_height(), 2);
+
+    // A trailing partial line is counted exactly like a terminated one.
+    let mut terminated = make_term(10, 5);
+    feed(&mut terminated, b"a\nb\n");
+    assert_eq!(logical_lines(&terminated), [1, 1, 0, 0, 0]);
+    assert_eq!(terminated.recommended_height(), 2);
+
+    // A trailing partial line that wraps still contributes all of its rows.
+    let mut wrapped = make_term(10, 5);
+    feed(&mut wrapped, b"a\nbcdefghijklm");
+    assert_eq!(logical_lines(&wrapped), [1, 12, 0, 0]);
+    assert_eq!(wrapped.recommended_height(), 3);
+}
+
+fn feed_with_newline(newline: Newline, data: &[u8]) -> Terminal {
+    let mut term = Terminal::new(Options {
+        cols: Some(10),
+        rows: Some(4),
+        newline,
+        ..Default::default()
+    });
+
+    feed(&mut term, data);
+    term
+}
+
+#[test]
+fn test_newline_crlf_single_break() {
+    for newline in [Newline::Auto, Newline::Lf, Newline::Crlf] {
+        let term = feed_with_newline(newline, b"a\r\nb");
+
+        assert_eq!(visible_line_text(&term, 0).trim_end(), "a", "{newline:?}");
+        assert_eq!(visible_line_text(&term, 1).trim_end(), "b", "{newline:?}");
+        assert_eq!(term.recommended_height(), 2, "{newline:?}");
+    }
+}
+
+#[test]
+fn test_newline_lone_cr() {
+    let data = b"50%\r100%\r\nok";
+
+    let term = feed_with_newline(Newline::Auto, data);
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "100%");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "ok");
+
+    let term = feed_with_newline(Newline::Lf, data);
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "50%100%");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "ok");
+
+    let term = feed_with_newline(Newline::Crlf, data);
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "50%");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "100%");
+    assert_eq!(visible_line_text(&term, 2).trim_end(), "ok");
+    assert_eq!(term.recommended_height(), 3);
+}
+
+#[test]
+fn test_newline_crlf_split_between_chunks() {
+    let mut term = feed_with_newline(Newline::Crlf, b"a\r");
+    feed(&mut term, b"\nb");
+
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "a");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "b");
+    assert_eq!(term.recommended_height(), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_placeholder_for_command_without_output() {
+    let mut term = make_term(20, 3);
+    term.run(CommandBuilder::new("true"), Some(Duration::from_secs(10)))
+        .unwrap();
+
+    assert!(term.is_blank());
+    assert!(term.fill_if_blank("(no output)"));
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "(no output)");
+    assert_eq!(term.recommended_height(), 1);
+
+    // Nothing is added once there is some content.
+    assert!(!term.fill_if_blank("(no output)"));
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_in_working_directory() {
+    let dir = std::env::temp_dir().join(format!("termframe-cwd-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // The temporary directory may be a symbolic link, `pwd` prints the resolved path.
+    let dir = dir.canonicalize().unwrap();
+
+    let mut cmd = CommandBuilder::new("pwd");
+    cmd.cwd(&dir);
+
+    let mut term = make_term(200, 3);
+    term.run(cmd, Some(Duration::from_secs(10))).unwrap();
+
+    assert_eq!(
+        visible_line_text(&term, 0).trim_end(),
+        dir.to_string_lossy()
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_with_env() {
+    let mut term = make_term(20, 3);
+    term.env.insert("FOO".into(), "bar".into());
+
+    let mut cmd = CommandBuilder::new("sh");
+    cmd.args(["-c", "echo \"$FOO\""]);
+    term.run(cmd, Some(Duration::from_secs(10))).unwrap();
+
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "bar");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_shell_pipeline() {
+    let mut cmd = CommandBuilder::new(crate::command::shell(Some("sh")));
+    cmd.args([
+        "-c",
+        &crate::command::to_script("printf 'foo\\nbar\\n'", ["|", "grep", "bar"]),
+    ]);
+
+    let mut term = make_term(20, 3);
+    term.run(cmd, Some(Duration::from_secs(10))).unwrap();
+
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "bar");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_exit_status() {
+    let mut term = make_term(20, 3);
+    let status = term
+        .run(CommandBuilder::new("true"), Some(Duration::from_secs(10)))
+        .unwrap();
+    assert!(status.success());
+
+    let mut cmd = CommandBuilder::new("sh");
+    cmd.args(["-c", "echo failed; exit 3"]);
+    let mut term = make_term(20, 3);
+    let status = term.run(cmd, Some(Duration::from_secs(10))).unwrap();
+    assert!(!status.success());
+    assert_eq!(status.exit_code(), 3);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_and_hold_captures_late_output() {
+    let mut cmd = CommandBuilder::new("sh");
+    cmd.args(["-c", "(trap '' HUP; sleep 0.5; echo late) & echo early"]);
+
+    let mut term = make_term(20, 3);
+    term.run_and_hold(cmd, Some(Duration::from_secs(10)))
+        .unwrap();
+
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "early");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "late");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_and_hold_is_bounded_by_timeout() {
+    let mut cmd = CommandBuilder::new("sh");
+    cmd.args(["-c", "(trap '' HUP; sleep 5) & echo early"]);
+
+    let start = std::time::Instant::now();
+    let mut term = make_term(20, 3);
+    term.run_and_hold(cmd, Some(Duration::from_secs(1)))
+        .unwrap();
+
+    assert!(start.elapsed() < Duration::from_secs(4));
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "early");
+}
+
+#[test]
+fn test_erase_in_line() {
+    let mut term = make_term(10, 2);
+    feed(&mut term, b"abc\x1b[2K");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "");
+
+    let mut term = make_term(10, 2);
+    feed(&mut term, b"abcdef\x1b[3G\x1b[K");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "ab");
+
+    let mut term = make_term(10, 2);
+    feed(&mut term, b"abcdef\x1b[3G\x1b[1K");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "   def");
+}
+
+#[test]
+fn test_erase_in_display() {
+    let mut term = make_term(10, 3);
+    feed(&mut term, b"abc\r\ndef\r\nghi\x1b[2;2H\x1b[1J");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "  f");
+    assert_eq!(visible_line_text(&term, 2).trim_end(), "ghi");
+
+    let mut term = make_term(10, 3);
+    feed(&mut term, b"abc\x1b[1J");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "");
+
+    let mut term = make_term(10, 3);
+    feed(&mut term, b"abc\r\ndef\r\nghi\x1b[2;2H\x1b[J");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "abc");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "d");
+    assert_eq!(visible_line_text(&term, 2).trim_end(), "");
+
+    let mut term = make_term(10, 3);
+    feed(&mut term, b"abc\r\ndef\x1b[2J");
+    assert_eq!(term.recommended_height(), 0);
+}
+
+#[test]
+fn test_erase_uses_current_background() {
+    let mut term = make_term(6, 1);
+    feed(&mut term, b"abc\r\x1b[1;41m\x1b[2Kx");
+
+    let lines = term.surface().screen_lines();
+    let cells: Vec<_> = lines[0].visible_cells().collect();
+    assert_eq!(cells[0].str(), "x");
+    // Erasing does not reset the current attributes.
+    assert_eq!(cells[0].attrs().intensity(), termwiz::cell::Intensity::Bold);
+    for cell in &cells[1..] {
+        assert_eq!(cell.str(), " ");
+        assert_eq!(cell.attrs().background(), ColorAttribute::PaletteIndex(1));
+    }
+    assert_eq!(term.recommended_width(), 6);
+}
+
+#[test]
+fn test_insert_line_in_the_middle() {
+    let mut term = make_term(5, 4);
+    feed(&mut term, b"xx\r\naaaaabb\x1b[2H\x1b[L");
+
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "xx");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "");
+    assert_eq!(visible_line_text(&term, 2).trim_end(), "aaaaa");
+    assert_eq!(visible_line_text(&term, 3).trim_end(), "bb");
+
+    // The soft wrap moves together with the row it belongs to
+    assert_eq!(term.state.wrap_flags, vec![false, false, true, false]);
+    let lines = term.surface().screen_lines();
+    assert!(lines[2].last_cell_was_wrapped());
+    assert!(!lines[1].last_cell_was_wrapped());
+    assert_eq!(term.recommended_height(), 3);
+    assert_eq!(term.surface().cursor_position(), (0, 1));
+}
+
+#[test]
+fn test_delete_line_in_the_middle() {
+    let mut term = make_term(5, 4);
+    feed(&mut term, b"xx\r\nyy\r\naaaaabb\x1b[2H\x1b[M");
+
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "xx");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "aaaaa");
+    assert_eq!(visible_line_text(&term, 2).trim_end(), "bb");
+    assert_eq!(visible_line_text(&term, 3).trim_end(), "");
+    assert_eq!(term.state.wrap_flags, vec![false, true, false, false]);
+}
+
+#[test]
+fn test_insert_characters() {
+    let mut term = make_term(20, 2);
+    feed(&mut term, b"$ helo\x1b[5G\x1b[@l");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "$ hello");
+    assert_eq!(term.surface().cursor_position(), (5, 0));
+
+    let mut term = make_term(6, 2);
+    feed(&mut term, b"abcdef\x1b[2G\x1b[3@");
+    assert_eq!(visible_line_text(&term, 0), "a   bc");
+}
+
+#[test]
+fn test_delete_characters() {
+    let mut term = make_term(20, 2);
+    feed(&mut term, b"$ helllo world\x1b[5G\x1b[P");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "$ hello world");
+
+    feed(&mut term, b"\x1b[8G\x1b[10P");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "$ hello");
+}
+
+#[test]
+fn test_erase_characters() {
+    let mut term = make_term(20, 2);
+    feed(&mut term, b"$ hello\x1b[3G\x1b[2X");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "$   llo");
+    assert_eq!(term.surface().cursor_position(), (2, 0));
+}
+
+#[test]
+fn test_character_edits_do_not_split_wide_characters() {
+    let mut term = make_term(10, 2);
+    feed(&mut term, "a中b\x1b[3G\x1b[X".as_bytes());
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "a  b");
+
+    let mut term = make_term(10, 2);
+    feed(&mut term, "a中b\x1b[2G\x1b[P".as_bytes());
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "a b");
+
+    let mut term = make_term(3, 2);
+    feed(&mut term, "a中\x1b[1G\x1b[@".as_bytes());
+    assert_eq!(visible_line_text(&term, 0).trim_end(), " a");
+}
+
+#[test]
+fn test_scroll_region_line_feed() {
+    let mut term = make_term(10, 5);
+    feed(&mut term, b"r1\r\nr2\r\nr3\r\nr4\r\nr5");
+    feed(&mut term, b"\x1b[2;4r\x1b[4H\nx");
+
+    let rows: Vec<_> = (0..5)
+        .map(|row| visible_line_text(&term, row).trim_end().to_owned())
+        .collect();
+    assert_eq!(rows, ["r1", "r3", "r4", "x", "r5"]);
+    assert!(term.state.scrollback.is_empty());
+
+    // Below the region the bottom row of the screen does not scroll at all
+    feed(&mut term, b"\x1b[5H\ny");
+    let rows: Vec<_> = (0..5)
+        .map(|row| visible_line_text(&term, row).trim_end().to_owned())
+        .collect();
+    assert_eq!(rows, ["r1", "r3", "r4", "x", "y5"]);
+    assert!(term.state.scrollback.is_empty());
+}
+
+#[test]
+fn test_scroll_region_autowrap() {
+    let mut term = make_term(5, 4);
+    feed(&mut term, b"top\x1b[2;3r\x1b[3Habcdefg");
+
+    let rows: Vec<_> = (0..4)
+        .map(|row| visible_line_text(&term, row).trim_end().to_owned())
+        .collect();
+    assert_eq!(rows, ["top", "abcde", "fg", ""]);
+    assert_eq!(term.state.wrap_flags, vec![false, true, false, false]);
+    assert!(term.surface().screen_lines()[1].last_cell_was_wrapped());
+    assert!(term.state.scrollback.is_empty());
+}
+
+#[test]
+fn test_scroll_region_reset() {
+    let mut term = make_term(10, 5);
+    feed(&mut term, b"\x1b[2;4r");
+    assert_eq!(term.state.scroll_region, Some(1..4));
+
+    feed(&mut term, b"\x1b[r");
+    assert_eq!(term.state.scroll_region, None);
+
+    feed(&mut term, b"\x1b[2;4rabc\x1bc");
+    assert_eq!(term.state.scroll_region, None);
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "");
+}
+
+#[test]
+fn test_alternate_screen() {
+    let mut term = make_term(10, 3);
+    feed(&mut term, b"one\r\ntwo\r\nthree");
+    let cursor = term.surface().cursor_position();
+
+    feed(&mut term, b"\x1b[?1049h");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "");
+    feed(&mut term, b"\x1b[Hfull\r\nscreen\r\napp\r\nscrolls");
+    assert_eq!(visible_line_text(&term, 2).trim_end(), "scrolls");
+
+    feed(&mut term, b"\x1b[?1049l");
+    let rows: Vec<_> = (0..3)
+        .map(|row| visible_line_text(&term, row).trim_end().to_owned())
+        .collect();
+    assert_eq!(rows, ["one", "two", "three"]);
+    assert_eq!(term.surface().cursor_position(), cursor);
+    assert!(term.state.scrollback.is_empty());
+
+    feed(&mut term, b"\r\nfour");
+    assert_eq!(term.state.scrollback.len(), 1);
+    assert_eq!(visible_line_text(&term, 2).trim_end(), "four");
+}
+
+#[test]
+fn test_alternate_screen_without_cursor_save() {
+    let mut term = make_term(10, 3);
+    feed(&mut term, b"one\x1b[?47h\x1b[2;2Hx\x1b[?47l");
+
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "one");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "");
+    assert_eq!(term.surface().cursor_position(), (2, 1));
+}
+
+#[test]
+fn test_dec_save_restore_cursor() {
+    let mut term = make_term(10, 2);
+    feed(&mut term, b"\x1b7\x1b[31mX\x1b8Y");
+
+    assert_eq!(term.surface().cursor_position(), (1, 0));
+    let lines = term.surface().screen_lines();
+    let cell = lines[0].visible_cells().next().unwrap();
+    assert_eq!(cell.str(), "Y");
+    assert_eq!(cell.attrs().foreground(), ColorAttribute::Default);
+
+    // The saved rendition is restored too, not only reset
+    let mut term = make_term(10, 2);
+    feed(&mut term, b"ab\x1b[1m\x1b7\r\n\x1b[0;31mX\x1b8Y");
+
+    assert_eq!(term.surface().cursor_position(), (3, 0));
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "abY");
+    let lines = term.surface().screen_lines();
+    let cell = lines[0].visible_cells().nth(2).unwrap();
+    assert_eq!(cell.attrs().intensity(), termwiz::cell::Intensity::Bold);
+    assert_eq!(cell.attrs().foreground(), ColorAttribute::Default);
+}
+
+#[test]
+fn test_custom_tab_width() {
+    let mut term = Terminal::new(Options {
+        cols: Some(20),
+        rows: Some(2),
+        tab_width: Some(4),
+        ..Default::default()
+    });
+    feed(&mut term, b"\t");
+    assert_eq!(term.surface().cursor_position(), (4, 0));
+
+    feed(&mut term, b"X\t");
+    assert_eq!(term.surface().cursor_position(), (8, 0));
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "    X");
+}
+
+#[test]
+fn test_set_and_clear_tab_stops() {
+    let mut term = make_term(20, 2);
+
+    // Set a custom stop at column 3, keeping the default ones
+    feed(&mut term, b"\x1b[4G\x1bH\r\t");
+    assert_eq!(term.surface().cursor_position(), (3, 0));
+    feed(&mut term, b"\t");
+    assert_eq!(term.surface().cursor_position(), (8, 0));
+
+    // Clear the stop at column 8
+    feed(&mut term, b"\x1b[g\r\t\t");
+    assert_eq!(term.surface().cursor_position(), (16, 0));
+
+    // Clear all stops, tabs move to the last column
+    feed(&mut term, b"\x1b[3g\r\t");
+    assert_eq!(term.surface().cursor_position(), (19, 0));
+
+    // Full reset restores the default stops
+    feed(&mut term, b"\x1bc\t");
+    assert_eq!(term.surface().cursor_position(), (8, 0));
+}
+
+#[test]
+fn test_scrollback_limit() {
+    let make = |limit| {
+        Terminal::new(Options {
+            cols: Some(10),
+            rows: Some(2),
+            scrollback_limit: Some(limit),
+            ..Default::default()
+        })
+    };
+
+    let mut term = make(2);
+    feed(&mut term, b"1\r\n2\r\n3\r\n4\r\n5");
+    let kept: Vec<String> = term
+        .state
+        .scrollback
+        .iter()
+        .map(|line| line.as_str().trim_end().to_owned())
+        .collect();
+    assert_eq!(kept, ["2", "3"]);
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "5");
+
+    let mut term = make(0);
+    feed(&mut term, b"1\r\n2\r\n3");
+    assert_eq!(term.scrollback_len(), 0);
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "2");
+}
+
+#[test]
+fn test_dynamic_cursor_and_highlight_colors() {
+    let mut term = make_term(10, 2);
+    let mut writer = Vec::new();
+    term.feed(
+        Cursor::new(b"\x1b]12;#ff0000\x07\x1b]12;?\x07\x1b]17;?\x07".as_ref()),
+        &mut writer,
+    )
+    .unwrap();
+
+    let response = String::from_utf8(writer).unwrap();
+    assert!(
+        response.contains("12;rgb:ffff/0000/0000"),
+        "unexpected response {response:?}"
+    );
+    assert!(
+        response.contains("17;rgb:"),
+        "unexpected response {response:?}"
+    );
+    assert_eq!(term.cursor_color(), Some(SrgbaTuple(1.0, 0.0, 0.0, 1.0)));
+}
+
+#[test]
+fn test_window_title() {
+    let mut term = make_term(10, 2);
+    assert_eq!(term.title(), None);
+
+    feed(&mut term, b"\x1b]2;Build Output\x07");
+    assert_eq!(term.title(), Some("Build Output"));
+
+    feed(&mut term, b"\x1b]0;Done\x1b\\");
+    assert_eq!(term.title(), Some("Done"));
+}
+
+#[test]
+fn test_hyperlink_attribute() {
+    let mut term = make_term(20, 1);
+    feed(
+        &mut term,
+        b"a\x1b]8;;https://example.com\x1b\\b\x1b]8;;\x1b\\c",
+    );
+
+    let lines = term.surface().screen_lines();
+    let links: Vec<_> = lines[0]
+        .visible_cells()
+        .take(3)
+        .map(|cell| cell.attrs().hyperlink().map(|link| link.uri().to_owned()))
+        .collect();
+    assert_eq!(links, [None, Some("https://example.com".to_owned()), None]);
+}
+
+#[test]
+fn test_sixel_image_placement() {
+    let mut term = make_term(10, 4);
+    feed(&mut term, b"line\r\nab\x1bPq#0;2;100;0;0#0~~-~~\x1b\\c");
+
+    let images = term.images();
+    assert_eq!(images.len(), 1);
+    assert_eq!((images[0].row, images[0].column), (1, 2));
+    assert_eq!((images[0].image.width, images[0].image.height), (2, 12));
+    assert_eq!(&images[0].image.rgba[..4], &[255, 0, 0, 255]);
+
+    // The cursor moves below the image and keeps its column.
+    assert_eq!(term.surface().cursor_position(), (3, 2));
+
+    // Images scrolled out of the screen are dropped.
+    feed(&mut term, b"\r\n\r\n\r\n\r\n");
+    assert!(term.images().is_empty());
+}
+
+#[test]
+fn test_sixel_image_over_limit() {
+    let mut term = make_term(10, 4);
+
+    // Wider than 10 columns of 10 pixels.
+    feed(&mut term, b"\x1bPq!101~\x1b\\");
+    assert!(term.images().is_empty());
+
+    // Repeat count overflowing the column.
+    feed(&mut term, b"\x1bPq~!4294967295~\x1b\\");
+    assert!(term.images().is_empty());
+
+    // Declared size larger than the screen.
+    feed(&mut term, b"\x1bPq\"1;1;10;100~\x1b\\");
+    assert!(term.images().is_empty());
+
+    // The largest image fitting into the screen is kept.
+    feed(&mut term, b"\x1bPq!100~\x1b\\");
+    assert_eq!(term.images().len(), 1);
+    assert_eq!(term.images()[0].image.width, 100);
+}
+
+#[test]
+fn test_repeat_preceding_character() {
+    let mut term = make_term(10, 2);
+    feed(&mut term, b"A\x1b[4b");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "AAAAA");
+    assert_eq!(term.surface().cursor_position(), (5, 0));
+
+    // Repeats crossing the right margin wrap to the next row.
+    feed(&mut term, b"B\x1b[6b");
+    assert_eq!(visible_line_text(&term, 0), "AAAAABBBBB");
+    assert_eq!(visible_line_text(&term, 1).trim_end(), "BB");
+    assert!(term.surface().screen_lines()[0].last_cell_was_wrapped());
+}
+
+#[test]
+fn test_repeat_without_preceding_character() {
+    let mut term = make_term(10, 2);
+    feed(&mut term, b"\x1b[3b");
+    assert_eq!(visible_line_text(&term, 0).trim_end(), "");
+    assert_eq!(term.surface().cursor_position(), (0, 0));
+}
+
+#[test]
+fn test_scroll_up_and_down() {
+    let mut term = make_term(5, 4);
+    feed(&mut term, b"one\r\ntwo\r\nthree\r\nfour\x1b[2S");
+
+    let rows: Vec<_> = (0..4).map(|row| visible_line_text(&term, row)).collect();
+    assert_eq!(
+        rows.iter().map(|row| row.trim_end()).collect::<Vec<_>>(),
+        ["three", "four", "", ""]
+    );
+    assert_eq!(term.surface().cursor_position(), (4, 3));
+
+    let scrollback: Vec<String> = term
+        .state
+        .scrollback
+        .iter()
+        .map(|line| line.as_str().trim_end().to_string())
+        .collect();
+    assert_eq!(scrollback, ["one", "two"]);
+
+    feed(&mut term, b"\x1b[T");
+    let rows: Vec<_> = (0..4).map(|row| visible_line_text(&term, row)).collect();
+    assert_eq!(
+        rows.iter().map(|row| row.trim_end()).collect::<Vec<_>>(),
+        ["", "three", "four", ""]
+    );
+    assert_eq!(term.state.scrollback.len(), 2);
+}
+
+#[test]
+fn test_scroll_up_within_region() {
+    let mut term = make_term(5, 4);
+    feed(&mut term, b"one\r\ntwo\r\nthree\r\nfour\x1b[2;3r\x1b[S");
+
+    let rows: Vec<_> = (0..4).map(|row| visible_line_text(&term, row)).collect();
+    assert_eq!(
+        rows.iter().map(|row| row.trim_end()).collect::<Vec<_>>(),
+        ["one", "three", "", "four"]
+    );
+    assert!(term.state.scrollback.is_empty());
+}
+
+#[test]
+fn test_device_attributes_reply() {
+    let mut term = Terminal::new(Options {
+        device_attributes: vec![4, 22],
+        ..Default::default()
+    });
+
+    let mut writer = Vec::new();
+    term.feed(Cursor::new(b"\x1b[c\x1b[>c".as_ref()), &mut writer)
+        .unwrap();
+    assert_eq!(writer, b"\x1b[?62;4;22c\x1b[>1;10;0c");
+
+    let mut term = make_term(10, 2);
+    let mut writer = Vec::new();
+    term.feed(Cursor::new(b"\x1b[0c".as_ref()), &mut writer)
+        .unwrap();
+    assert_eq!(writer, b"\x1b[?62c");
+}
+
+#[test]
+fn test_palette_set_query_and_reset() {
+    let mut term = make_term(10, 2);
+    feed(&mut term, b"\x1b]4;1;rgb:12/34/56;2;#abcdef\x07");
+    assert_eq!(
+        term.palette().get(&1).map(|color| color.to_rgb_string()),
+        Some("#123456".to_string())
+    );
+    assert_eq!(term.palette().len(), 2);
+
+    let mut writer = Vec::new();
+    term.feed(
+        Cursor::new(b"\x1b]4;1;?\x07\x1b]4;3;?\x07".as_ref()),
+        &mut writer,
+    )
+    .unwrap();
+    let reply = String::from_utf8(writer).unwrap();
+    assert!(reply.starts_with("\x1b]4;1;"), "{reply:?}");
+    assert_eq!(reply.matches("\x1b]4;").count(), 1, "{reply:?}");
+
+    feed(&mut term, b"\x1b]104;2\x07");
+    assert_eq!(term.palette().keys().copied().collect::<Vec<_>>(), [1]);
+
+    feed(&mut term, b"\x1b]104\x07");
+    assert!(term.palette().is_empty());
+}
+
+/// Reflows the transcript the straightforward way, cloning every line, for comparison.
+fn reflow_cloned(term: &Terminal, width: usize) -> Vec<String> {
+    let seq = term.surface().current_seqno();
+    let mut lines: Vec<Line> = term.state.scrollback.iter().cloned().collect();
+    lines.extend(
+        term.surface()
+            .screen_lines()
+            .into_iter()
+            .map(Cow::into_owned),
+    );
+
+    let mut logicals: Vec<Line> = Vec::new();
+    let mut prev_wrapped = false;
+    for line in lines {
+        let wrapped = line.last_cell_was_wrapped();
+        match logicals.last_mut() {
+            Some(last) if prev_wrapped => last.append_line(line, seq),
+            _ => logicals.push(line),
+        }
+        prev_wrapped = wrapped;
+    }
+
+    let mut rows: Vec<String> = logicals
+        .iter()
+        .flat_map(|line| Terminal::wrap_line(line, width, WrapMode::Char, seq))
+        .map(|line| line.as_str().trim_end().to_string())
+        .collect();
+    while rows.last().is_some_and(|row| row.is_empty()) {
+        rows.pop();
+    }
+    rows
+}
+
+#[test]
+fn test_reflow_borrowed_transcript_matches_cloned() {
+    let mut term = make_term(8, 3);
+    feed(
+        &mut term,
+        b"first line\r\nsecond logical line here\r\n\r\nshort\r\nthe last wrapped line",
+    );
+    assert!(!term.state.scrollback.is_empty());
+
+    for width in [4, 8, 13, 40] {
+        let rows: Vec<String> = term
+            .reflow_transcript_to_width(width)
+            .iter()
+            .map(|line| line.as_str().trim_end().to_string())
+            .collect();
+        assert_eq!(rows, reflow_cloned(&term, width), "width {width}");
+    }
+
+    assert_eq!(term.recommended_width(), 24);
+    assert_eq!(term.recommended_height(), 10);
+
+    let expected = reflow_cloned(&term, 13);
+    term.set_width(13);
+    term.set_height(expected.len() as u16);
+    let rows: Vec<String> = (0..expected.len())
+        .map(|row| visible_line_text(&term, row).trim_end().to_string())
+        .collect();
+    assert_eq!(rows, expected);
+    assert!(term.state.scrollback.is_empty());
+}
+
+#[test]
+fn test_blink_attribute() {
+    use termwiz::cell::Blink;
+
+    let mut term = make_term(10, 1);
+    term.advance(b"a\x1b[5mb\x1b[6mc\x1b[25md");
+
+    let line = &term.surface().screen_lines()[0];
+    let blink = |x| line.get_cell(x).unwrap().attrs().blink();
+    assert_eq!(blink(0), Blink::None);
+    assert_eq!(blink(1), Blink::Slow);
+    assert_eq!(blink(2), Blink::Rapid);
+    assert_eq!(blink(3), Blink::None);
+}
+
+#[test]
+fn test_overline_attribute() {
+    let mut term = make_term(10, 1);
+    term.advance(b"a\x1b[1;53mb\x1b[55mc");
+
+    let line = &term.surface().screen_lines()[0];
+    let attrs = |x| line.get_cell(x).unwrap().attrs().clone();
+    assert!(!attrs(0).overline());
+    assert!(attrs(1).overline());
+    // Other attributes are kept when overline is switched.
+    assert_eq!(attrs(1).intensity(), termwiz::cell::Intensity::Bold);
+    assert!(!attrs(2).overline());
+    assert_eq!(attrs(2).intensity(), termwiz::cell::Intensity::Bold);
+}
@@ -96,6 +96,7 @@ impl Default for AdaptiveTheme {
             bg,
             fg,
             bright_fg,
+            cursor: None,
             palette,
         }
         .into();
@@ -124,6 +125,7 @@ impl Default for AdaptiveTheme {
             bg,
             fg,
             bright_fg,
+            cursor: None,
             palette,
         }
         .into();
@@ -141,6 +143,8 @@ pub struct Theme {
     pub fg: Color,
     /// Optional bright foreground color.
     pub bright_fg: Option<Color>,
+    /// Optional cursor color.
+    pub cursor: Option<Color>,
     /// Color palette.
     pub palette: Palette,
 }
@@ -159,11 +163,13 @@ impl Theme {
         let bg = cfg.background.clone();
         let fg = cfg.foreground.clone();
         let bright_fg = cfg.bright_foreground.clone();
+        let cursor = cfg.cursor.clone();
         let palette = Palette::from_config(&cfg.palette);
         Self {
             bg,
             fg,
             bright_fg,
+            cursor,
             palette,
         }
     }
@@ -298,5 +304,8 @@ impl Default for &Palette {
     }
 }
 
+pub mod export;
+pub mod import;
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,46 @@
+// third-party imports
+use enumset::EnumSet;
+
+// local imports
+use super::Palette;
+use crate::config::{
+    mode::Mode,
+    theme::{self, Colors, Fixed, PaletteIndex, Tag, ThemeConfig},
+};
+
+/// Resolves the theme configuration for the given mode into a self-contained fixed theme.
+///
+/// The 16 ANSI palette entries are always present, the ones not defined by the theme
+/// get their default colors, other palette entries are kept only if defined by the theme.
+pub fn export(cfg: &ThemeConfig, mode: Mode) -> ThemeConfig {
+    let colors = cfg.theme.resolve(mode);
+    let resolved = Palette::from_config(&colors.palette);
+
+    let mut palette: theme::Palette = (0..16)
+        .map(|i| (PaletteIndex::U8(i), resolved[i as usize].clone()))
+        .collect();
+    for index in colors.palette.keys().filter_map(PaletteIndex::resolve) {
+        palette.insert(PaletteIndex::U8(index), resolved[index as usize].clone());
+    }
+
+    let tags = match &cfg.theme {
+        theme::Theme::Fixed(_) => cfg.tags,
+        theme::Theme::Adaptive(_) => EnumSet::only(match mode {
+            Mode::Dark => Tag::Dark,
+            Mode::Light => Tag::Light,
+        }),
+    };
+
+    ThemeConfig {
+        tags,
+        theme: theme::Theme::Fixed(Fixed {
+            colors: Colors {
+                palette,
+                ..colors.clone()
+            },
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,61 @@
+use super::*;
+use crate::{
+    config::load::{Format, Load},
+    theme::Theme,
+};
+
+fn assert_equivalent(a: &Theme, b: &Theme) {
+    let hex = |theme: &Theme| {
+        (
+            theme.bg.to_css_hex(),
+            theme.fg.to_css_hex(),
+            theme.bright_fg.as_ref().map(|c| c.to_css_hex()),
+            theme.cursor.as_ref().map(|c| c.to_css_hex()),
+            theme
+                .palette
+                .iter()
+                .map(|c| c.to_css_hex())
+                .collect::<Vec<_>>(),
+        )
+    };
+    assert_eq!(hex(a), hex(b));
+}
+
+fn round_trip(name: &str, mode: Mode) {
+    let cfg = ThemeConfig::load(name).unwrap();
+    let exported = export(&cfg, mode);
+    let toml = exported.to_toml();
+
+    let reloaded = ThemeConfig::from_buf(toml.as_bytes(), Format::Toml).unwrap();
+    assert!(matches!(reloaded.theme, theme::Theme::Fixed(_)));
+    assert_equivalent(
+        &Theme::from_config(cfg.theme.resolve(mode)),
+        &Theme::from_config(reloaded.theme.resolve(mode)),
+    );
+}
+
+#[test]
+fn test_export_fixed_theme() {
+    round_trip("Atom One Dark", Mode::Dark);
+
+    let cfg = ThemeConfig::load("Atom One Dark").unwrap();
+    let exported = export(&cfg, Mode::Light);
+    assert_eq!(exported.tags, cfg.tags);
+
+    let toml = exported.to_toml();
+    for i in 0..16 {
+        assert!(
+            toml.contains(&format!("\n{i} = \"#")),
+            "missing palette entry {i}"
+        );
+    }
+}
+
+#[test]
+fn test_export_adaptive_theme() {
+    round_trip("One Double", Mode::Dark);
+    round_trip("One Double", Mode::Light);
+
+    let cfg = ThemeConfig::load("One Double").unwrap();
+    assert_eq!(export(&cfg, Mode::Light).tags, EnumSet::only(Tag::Light));
+}
@@ -0,0 +1,357 @@
+// std imports
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+// third-party imports
+use csscolorparser::Color;
+use enumset::EnumSet;
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator};
+use yaml_peg::serde as yaml;
+
+// local imports
+use crate::{
+    config::{
+        load::Format as FileFormat,
+        theme::{Colors, Fixed, PaletteIndex, Tag, Theme, ThemeConfig},
+    },
+    error::Result,
+};
+
+/// Format of a color scheme defined by another terminal emulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, EnumIter)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Format {
+    Alacritty,
+    WindowsTerminal,
+    Iterm2,
+}
+
+/// A color scheme file to import as a theme, given as `FORMAT:PATH`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Source {
+    /// Format of the color scheme.
+    pub format: Format,
+    /// Path to the file containing the color scheme.
+    pub path: PathBuf,
+}
+
+impl Source {
+    /// Returns the theme name derived from the file name.
+    pub fn name(&self) -> String {
+        self.path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.format.to_string())
+    }
+
+    /// Reads the color scheme file and converts it into a theme configuration.
+    pub fn import(&self) -> Result<ThemeConfig> {
+        let data = std::fs::read(&self.path)?;
+        match self.format {
+            Format::Alacritty => alacritty(std::str::from_utf8(&data)?, file_format(&self.path)),
+            Format::WindowsTerminal => windows_terminal(std::str::from_utf8(&data)?),
+            Format::Iterm2 => iterm2(&data),
+        }
+    }
+}
+
+impl FromStr for Source {
+    type Err = String;
+
+    /// Parses a color scheme source in `FORMAT:PATH` format.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (format, path) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected FORMAT:PATH, got {s:?}"))?;
+
+        let format = format.parse().map_err(|_| {
+            let known = Format::iter().map(|f| f.to_string()).collect::<Vec<_>>();
+            format!(
+                "unknown theme format {format:?}, expected one of: {}",
+                known.join(", ")
+            )
+        })?;
+
+        if path.is_empty() {
+            return Err(format!("missing path to the {format} theme file"));
+        }
+
+        Ok(Self {
+            format,
+            path: path.into(),
+        })
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.format, self.path.display())
+    }
+}
+
+/// Converts an Alacritty configuration with a `colors` section into a theme configuration.
+///
+/// Both the current TOML and the legacy YAML configuration formats are supported.
+pub fn alacritty(data: &str, format: FileFormat) -> Result<ThemeConfig> {
+    let cfg: alacritty::Config = match format {
+        FileFormat::Yaml => yaml::from_str(data)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("empty alacritty configuration"))?,
+        FileFormat::Toml => toml::from_str(data)?,
+        FileFormat::Json => serde_json::from_str(data)?,
+    };
+    let colors = cfg.colors;
+
+    let palette = [colors.normal, colors.bright]
+        .iter()
+        .flat_map(|ansi| ansi.iter())
+        .enumerate()
+        .map(|(i, c)| Ok((PaletteIndex::U8(i as u8), color(c)?)))
+        .collect::<Result<_>>()?;
+
+    Ok(fixed(Colors {
+        background: color(&colors.primary.background)?,
+        foreground: color(&colors.primary.foreground)?,
+        bright_foreground: colors
+            .primary
+            .bright_foreground
+            .as_deref()
+            .map(color)
+            .transpose()?,
+        // The cursor may refer to the cell colors instead of a fixed color.
+        cursor: colors
+            .cursor
+            .and_then(|cursor| cursor.cursor)
+            .and_then(|c| color(&c).ok()),
+        palette,
+    }))
+}
+
+/// Converts a Windows Terminal color scheme JSON object into a theme configuration.
+pub fn windows_terminal(data: &str) -> Result<ThemeConfig> {
+    let scheme: windows_terminal::Scheme = serde_json::from_str(data)?;
+
+    let palette = scheme
+        .iter()
+        .enumerate()
+        .map(|(i, c)| Ok((PaletteIndex::U8(i as u8), color(c)?)))
+        .collect::<Result<_>>()?;
+
+    Ok(fixed(Colors {
+        background: color(&scheme.background)?,
+        foreground: color(&scheme.foreground)?,
+        bright_foreground: None,
+        cursor: scheme.cursor_color.as_deref().map(color).transpose()?,
+        palette,
+    }))
+}
+
+/// Converts an iTerm2 `.itermcolors` property list into a theme configuration.
+///
+/// Both XML and binary property lists are supported.
+pub fn iterm2(data: &[u8]) -> Result<ThemeConfig> {
+    let scheme: HashMap<String, iterm2::Color> = plist::from_bytes(data)?;
+    let get = |key: &str| {
+        scheme
+            .get(key)
+            .map(iterm2::Color::convert)
+            .ok_or_else(|| anyhow::anyhow!("missing {key:?} in iterm2 color scheme"))
+    };
+
+    let palette = (0..16)
+        .map(|i| Ok((PaletteIndex::U8(i), get(&format!("Ansi {i} Color"))?)))
+        .collect::<Result<_>>()?;
+
+    Ok(fixed(Colors {
+        background: get("Background Color")?,
+        foreground: get("Foreground Color")?,
+        bright_foreground: get("Bold Color").ok(),
+        cursor: get("Cursor Color").ok(),
+        palette,
+    }))
+}
+
+/// Builds a fixed theme configuration tagged by the brightness of its background.
+fn fixed(colors: Colors) -> ThemeConfig {
+    let bg = &colors.background;
+    let tag = if 0.2126 * bg.r + 0.7152 * bg.g + 0.0722 * bg.b < 0.5 {
+        Tag::Dark
+    } else {
+        Tag::Light
+    };
+
+    ThemeConfig {
+        tags: EnumSet::only(tag),
+        theme: Theme::Fixed(Fixed { colors }),
+    }
+}
+
+/// Parses a color, accepting the `0xRRGGBB` notation in addition to CSS colors.
+fn color(value: &str) -> Result<Color> {
+    let value = value.trim();
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => format!("#{hex}").parse::<Color>(),
+        None => value.parse::<Color>(),
+    };
+    Ok(parsed.map_err(|e| anyhow::anyhow!("invalid color {value:?}: {e}"))?)
+}
+
+/// Detects the configuration file format by the file extension, TOML is assumed by default.
+fn file_format(path: &Path) -> FileFormat {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("yml" | "yaml") => FileFormat::Yaml,
+        Some("json") => FileFormat::Json,
+        _ => FileFormat::Toml,
+    }
+}
+
+mod alacritty {
+    use serde::Deserialize;
+
+    /// Alacritty configuration, only the colors are used.
+    #[derive(Deserialize)]
+    pub struct Config {
+        pub colors: Colors,
+    }
+
+    /// Alacritty `colors` section.
+    #[derive(Deserialize)]
+    pub struct Colors {
+        pub primary: Primary,
+        pub cursor: Option<Cursor>,
+        pub normal: Ansi,
+        pub bright: Ansi,
+    }
+
+    /// Alacritty `colors.primary` section.
+    #[derive(Deserialize)]
+    pub struct Primary {
+        pub background: String,
+        pub foreground: String,
+        pub bright_foreground: Option<String>,
+    }
+
+    /// Alacritty `colors.cursor` section.
+    #[derive(Deserialize)]
+    pub struct Cursor {
+        pub cursor: Option<String>,
+    }
+
+    /// Alacritty `colors.normal` or `colors.bright` section.
+    #[derive(Deserialize)]
+    pub struct Ansi {
+        pub black: String,
+        pub red: String,
+        pub green: String,
+        pub yellow: String,
+        pub blue: String,
+        pub magenta: String,
+        pub cyan: String,
+        pub white: String,
+    }
+
+    impl Ansi {
+        /// Returns the colors in palette order.
+        pub fn iter(&self) -> impl Iterator<Item = &String> {
+            [
+                &self.black,
+                &self.red,
+                &self.green,
+                &self.yellow,
+                &self.blue,
+                &self.magenta,
+                &self.cyan,
+                &self.white,
+            ]
+            .into_iter()
+        }
+    }
+}
+
+mod windows_terminal {
+    use serde::Deserialize;
+
+    /// Windows Terminal color scheme, an item of the `schemes` list in its settings.
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Scheme {
+        pub background: String,
+        pub foreground: String,
+        pub cursor_color: Option<String>,
+        pub black: String,
+        pub red: String,
+        pub green: String,
+        pub yellow: String,
+        pub blue: String,
+        pub purple: String,
+        pub cyan: String,
+        pub white: String,
+        pub bright_black: String,
+        pub bright_red: String,
+        pub bright_green: String,
+        pub bright_yellow: String,
+        pub bright_blue: String,
+        pub bright_purple: String,
+        pub bright_cyan: String,
+        pub bright_white: String,
+    }
+
+    impl Scheme {
+        /// Returns the 16 ANSI colors in palette order.
+        pub fn iter(&self) -> impl Iterator<Item = &String> {
+            [
+                &self.black,
+                &self.red,
+                &self.green,
+                &self.yellow,
+                &self.blue,
+                &self.purple,
+                &self.cyan,
+                &self.white,
+                &self.bright_black,
+                &self.bright_red,
+                &self.bright_green,
+                &self.bright_yellow,
+                &self.bright_blue,
+                &self.bright_purple,
+                &self.bright_cyan,
+                &self.bright_white,
+            ]
+            .into_iter()
+        }
+    }
+}
+
+mod iterm2 {
+    use serde::Deserialize;
+    use termwiz::color::SrgbaTuple;
+
+    use crate::Convert;
+
+    /// iTerm2 color with components in the range from 0 to 1.
+    #[derive(Deserialize)]
+    pub struct Color {
+        #[serde(rename = "Red Component")]
+        pub red: f32,
+        #[serde(rename = "Green Component")]
+        pub green: f32,
+        #[serde(rename = "Blue Component")]
+        pub blue: f32,
+        #[serde(rename = "Alpha Component")]
+        pub alpha: Option<f32>,
+    }
+
+    impl Convert<csscolorparser::Color> for Color {
+        fn convert(&self) -> csscolorparser::Color {
+            SrgbaTuple(self.red, self.green, self.blue, self.alpha.unwrap_or(1.0)).convert()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,249 @@
+use super::*;
+use crate::{config::load::Load, theme::Theme as ResolvedTheme};
+
+const ALACRITTY_TOML: &str = r##"
+[colors.primary]
+background = "#1d1f21"
+foreground = "#c5c8c6"
+bright_foreground = "#eaeaea"
+
+[colors.cursor]
+text = "CellBackground"
+cursor = "CellForeground"
+
+[colors.normal]
+black = "#282a2e"
+red = "#a54242"
+green = "#8c9440"
+yellow = "#de935f"
+blue = "#5f819d"
+magenta = "#85678f"
+cyan = "#5e8d87"
+white = "#707880"
+
+[colors.bright]
+black = "#373b41"
+red = "#cc6666"
+green = "#b5bd68"
+yellow = "#f0c674"
+blue = "#81a2be"
+magenta = "#b294bb"
+cyan = "#8abeb7"
+white = "#c5c8c6"
+"##;
+
+const ALACRITTY_YAML: &str = r#"
+colors:
+  primary:
+    background: '0xfdf6e3'
+    foreground: '0x586e75'
+  cursor:
+    text: '0xfdf6e3'
+    cursor: '0x586e75'
+  normal:
+    black: '0x073642'
+    red: '0xdc322f'
+    green: '0x859900'
+    yellow: '0xb58900'
+    blue: '0x268bd2'
+    magenta: '0xd33682'
+    cyan: '0x2aa198'
+    white: '0xeee8d5'
+  bright:
+    black: '0x002b36'
+    red: '0xcb4b16'
+    green: '0x586e75'
+    yellow: '0x657b83'
+    blue: '0x839496'
+    magenta: '0x6c71c4'
+    cyan: '0x93a1a1'
+    white: '0xfdf6e3'
+"#;
+
+fn hex(color: &Color) -> String {
+    color.to_css_hex()
+}
+
+#[test]
+fn test_alacritty_toml() {
+    let cfg = alacritty(ALACRITTY_TOML, FileFormat::Toml).unwrap();
+    assert_eq!(cfg.tags, EnumSet::only(Tag::Dark));
+
+    let Theme::Fixed(fixed) = &cfg.theme else {
+        panic!("expected a fixed theme");
+    };
+    let theme = ResolvedTheme::from_config(&fixed.colors);
+
+    assert_eq!(hex(&theme.bg), "#1d1f21");
+    assert_eq!(hex(&theme.fg), "#c5c8c6");
+    assert_eq!(
+        theme.bright_fg.as_ref().map(hex).as_deref(),
+        Some("#eaeaea")
+    );
+    assert!(theme.cursor.is_none());
+    assert_eq!(hex(&theme.palette[0]), "#282a2e");
+    assert_eq!(hex(&theme.palette[1]), "#a54242");
+    assert_eq!(hex(&theme.palette[7]), "#707880");
+    assert_eq!(hex(&theme.palette[8]), "#373b41");
+    assert_eq!(hex(&theme.palette[12]), "#81a2be");
+    assert_eq!(hex(&theme.palette[15]), "#c5c8c6");
+    assert_eq!(hex(&theme.palette[16]), "#000000");
+}
+
+#[test]
+fn test_alacritty_yaml() {
+    let cfg = alacritty(ALACRITTY_YAML, FileFormat::Yaml).unwrap();
+    assert_eq!(cfg.tags, EnumSet::only(Tag::Light));
+
+    let colors = cfg.theme.resolve(crate::config::mode::Mode::Light);
+    assert_eq!(hex(&colors.background), "#fdf6e3");
+    assert_eq!(colors.cursor.as_ref().map(hex).as_deref(), Some("#586e75"));
+    assert!(colors.bright_foreground.is_none());
+    assert_eq!(colors.palette.len(), 16);
+    assert_eq!(
+        colors.palette.get(&PaletteIndex::U8(9)).map(hex).as_deref(),
+        Some("#cb4b16")
+    );
+}
+
+#[test]
+fn test_alacritty_invalid_color() {
+    let data = ALACRITTY_TOML.replace("#a54242", "not-a-color");
+    assert!(alacritty(&data, FileFormat::Toml).is_err());
+}
+
+#[test]
+fn test_imported_theme_round_trip() {
+    let cfg = alacritty(ALACRITTY_TOML, FileFormat::Toml).unwrap();
+    let toml = cfg.to_toml();
+    assert!(toml.starts_with("tags = [\"dark\"]\n\n[theme.colors]\n"));
+    assert!(toml.contains("\n[theme.colors.palette]\n0 = \"#282a2e\"\n1 = \"#a54242\"\n"));
+
+    let loaded = ThemeConfig::from_buf(toml.as_bytes(), FileFormat::Toml).unwrap();
+    let colors = loaded.theme.resolve(crate::config::mode::Mode::Dark);
+    assert_eq!(hex(&colors.foreground), "#c5c8c6");
+    assert_eq!(colors.palette.len(), 16);
+}
+
+#[test]
+fn test_source_from_str() {
+    let source: Source = "alacritty:themes/tomorrow-night.toml".parse().unwrap();
+    assert_eq!(source.format, Format::Alacritty);
+    assert_eq!(source.path, PathBuf::from("themes/tomorrow-night.toml"));
+    assert_eq!(source.name(), "tomorrow-night");
+    assert_eq!(source.to_string(), "alacritty:themes/tomorrow-night.toml");
+
+    let source: Source = "windows-terminal:campbell.json".parse().unwrap();
+    assert_eq!(source.format, Format::WindowsTerminal);
+    assert_eq!(source.name(), "campbell");
+
+    let source: Source = "iterm2:Tomorrow Night.itermcolors".parse().unwrap();
+    assert_eq!(source.format, Format::Iterm2);
+    assert_eq!(source.name(), "Tomorrow Night");
+
+    assert!("tomorrow-night.toml".parse::<Source>().is_err());
+    assert!("kitty:theme.conf".parse::<Source>().is_err());
+    assert!("alacritty:".parse::<Source>().is_err());
+}
+
+#[test]
+fn test_windows_terminal() {
+    let data = r##"{
+        "name": "Campbell",
+        "background": "#0C0C0C",
+        "foreground": "#CCCCCC",
+        "cursorColor": "#FFFFFF",
+        "selectionBackground": "#FFFFFF",
+        "black": "#0C0C0C",
+        "red": "#C50F1F",
+        "green": "#13A10E",
+        "yellow": "#C19C00",
+        "blue": "#0037DA",
+        "purple": "#881798",
+        "cyan": "#3A96DD",
+        "white": "#CCCCCC",
+        "brightBlack": "#767676",
+        "brightRed": "#E74856",
+        "brightGreen": "#16C60C",
+        "brightYellow": "#F9F1A5",
+        "brightBlue": "#3B78FF",
+        "brightPurple": "#B4009E",
+        "brightCyan": "#61D6D6",
+        "brightWhite": "#F2F2F2"
+    }"##;
+
+    let cfg = windows_terminal(data).unwrap();
+    assert_eq!(cfg.tags, EnumSet::only(Tag::Dark));
+
+    let colors = cfg.theme.resolve(crate::config::mode::Mode::Dark);
+    assert_eq!(hex(&colors.background), "#0c0c0c");
+    assert_eq!(hex(&colors.foreground), "#cccccc");
+    assert_eq!(colors.cursor.as_ref().map(hex).as_deref(), Some("#ffffff"));
+
+    let theme = ResolvedTheme::from_config(colors);
+    let expected = [
+        (0, "#0c0c0c"),
+        (1, "#c50f1f"),
+        (5, "#881798"),
+        (7, "#cccccc"),
+        (8, "#767676"),
+        (11, "#f9f1a5"),
+        (13, "#b4009e"),
+        (15, "#f2f2f2"),
+    ];
+    for (index, color) in expected {
+        assert_eq!(hex(&theme.palette[index]), color, "palette index {index}");
+    }
+}
+
+#[test]
+fn test_windows_terminal_missing_color() {
+    let data = r##"{"background": "#000000", "foreground": "#ffffff"}"##;
+    assert!(windows_terminal(data).is_err());
+}
+
+#[test]
+fn test_iterm2() {
+    let data = include_bytes!("../../../assets/test/themes/tomorrow-night.itermcolors");
+
+    let cfg = iterm2(data).unwrap();
+    assert_eq!(cfg.tags, EnumSet::only(Tag::Dark));
+
+    let colors = cfg.theme.resolve(crate::config::mode::Mode::Dark);
+    assert_eq!(hex(&colors.background), "#1d1f21");
+    assert_eq!(hex(&colors.foreground), "#c5c8c6");
+    assert_eq!(colors.cursor.as_ref().map(hex).as_deref(), Some("#aeafad"));
+    assert!(colors.bright_foreground.is_none());
+
+    let theme = ResolvedTheme::from_config(colors);
+    let expected = [
+        (0, "#1d1f21"),
+        (1, "#cc6666"),
+        (4, "#81a2be"),
+        (7, "#c5c8c6"),
+        (8, "#666666"),
+        (12, "#7aa6da"),
+        (15, "#eaeaea"),
+    ];
+    for (index, color) in expected {
+        assert_eq!(hex(&theme.palette[index]), color, "palette index {index}");
+    }
+}
+
+#[test]
+fn test_iterm2_missing_color() {
+    let data = br#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+    <key>Background Color</key>
+    <dict>
+        <key>Blue Component</key><real>0</real>
+        <key>Green Component</key><real>0</real>
+        <key>Red Component</key><real>0</real>
+    </dict>
+</dict>
+</plist>"#;
+
+    let err = iterm2(data).unwrap_err();
+    assert!(err.to_string().contains("Ansi 0 Color"), "{err}");
+}
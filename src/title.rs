@@ -0,0 +1,81 @@
+// std imports
+use std::collections::HashMap;
+
+/// Values available for window title template placeholders.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Vars {
+    pub user: Option<String>,
+    pub host: Option<String>,
+    pub cwd: Option<String>,
+}
+
+impl Vars {
+    /// Collects placeholder values from the terminal environment and the process environment.
+    ///
+    /// Variables from `env` take precedence over the ones inherited by the process,
+    /// the host name falls back to the one reported by the system.
+    pub fn from_env(env: &HashMap<String, String>, cwd: Option<&str>) -> Self {
+        let var = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                env.get(*name)
+                    .cloned()
+                    .or_else(|| std::env::var(name).ok())
+                    .filter(|value| !value.is_empty())
+            })
+        };
+
+        Self {
+            user: var(&["USER", "LOGNAME", "USERNAME"]),
+            host: var(&["HOSTNAME", "HOST", "COMPUTERNAME"]).or_else(hostname),
+            cwd: cwd.map(|cwd| cwd.to_owned()),
+        }
+    }
+}
+
+/// Returns the name of the host the process is running on, used if the environment does not provide it.
+fn hostname() -> Option<String> {
+    gethostname::gethostname()
+        .into_string()
+        .ok()
+        .filter(|host| !host.is_empty())
+}
+
+/// Expands `{user}`, `{host}` and `{cwd}` placeholders in a window title template.
+///
+/// Placeholders with unknown values expand to an empty string,
+/// unrecognized placeholders are kept as is.
+pub fn expand(template: &str, vars: &Vars) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+
+        let value = match &rest[1..end] {
+            "user" => &vars.user,
+            "host" => &vars.host,
+            "cwd" => &vars.cwd,
+            _ => {
+                result.push_str(&rest[..=end]);
+                rest = &rest[end + 1..];
+                continue;
+            }
+        };
+
+        if let Some(value) = value {
+            result.push_str(value);
+        }
+        rest = &rest[end + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,66 @@
+use super::*;
+
+fn sample_vars() -> Vars {
+    Vars {
+        user: Some("alice".into()),
+        host: Some("devbox".into()),
+        cwd: Some("/home/alice/src".into()),
+    }
+}
+
+#[test]
+fn test_expand_user_host() {
+    assert_eq!(expand("{user}@{host}", &sample_vars()), "alice@devbox");
+}
+
+#[test]
+fn test_expand_cwd() {
+    assert_eq!(
+        expand("{user}@{host}: {cwd}", &sample_vars()),
+        "alice@devbox: /home/alice/src"
+    );
+}
+
+#[test]
+fn test_expand_missing_values() {
+    let vars = Vars {
+        host: Some("devbox".into()),
+        ..Default::default()
+    };
+
+    assert_eq!(expand("{user}@{host}:{cwd}", &vars), "@devbox:");
+}
+
+#[test]
+fn test_expand_unknown_placeholders() {
+    assert_eq!(
+        expand("{shell} {user} {unterminated", &sample_vars()),
+        "{shell} alice {unterminated"
+    );
+}
+
+#[test]
+fn test_vars_from_env_prefers_terminal_env() {
+    let env = HashMap::from([
+        ("USER".to_string(), "bob".to_string()),
+        ("HOSTNAME".to_string(), "server".to_string()),
+    ]);
+
+    let vars = Vars::from_env(&env, Some("/tmp"));
+
+    assert_eq!(vars.user.as_deref(), Some("bob"));
+    assert_eq!(vars.host.as_deref(), Some("server"));
+    assert_eq!(vars.cwd.as_deref(), Some("/tmp"));
+}
+
+#[test]
+fn test_vars_from_env() {
+    let env = HashMap::from([("HOSTNAME".to_string(), "devbox".to_string())]);
+    let vars = Vars::from_env(&env, Some("/tmp"));
+    assert_eq!(vars.host.as_deref(), Some("devbox"));
+    assert_eq!(vars.cwd.as_deref(), Some("/tmp"));
+
+    // The system host name is used when none is set in the environment.
+    let vars = Vars::from_env(&HashMap::new(), None);
+    assert!(vars.host.is_some());
+}
@@ -4,12 +4,13 @@ use std::{
     ops::Deref,
     path::{Path, PathBuf},
     sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use serde_json::{Map, Value};
 use ureq::{
     Body, Error, SendBody,
-    http::{Request, Response},
+    http::{HeaderMap, HeaderValue, Request, Response, StatusCode, header},
     middleware::{Middleware, MiddlewareNext},
 };
 
@@ -21,10 +22,19 @@ pub fn new(dir: &Path) -> CacheMiddleware {
     CacheMiddleware::new(dir)
 }
 
+/// Reads the body of a response cached for the URL, regardless of its age.
+pub fn lookup(dir: &Path, url: &str) -> Option<Vec<u8>> {
+    cacache::read_sync(dir, url).ok()
+}
+
 /// Middleware for caching HTTP responses.
+///
+/// Cached responses older than the configured TTL are revalidated with a conditional request
+/// using the stored `ETag` and `Last-Modified` headers, a `304 Not Modified` reply keeps the cached body.
 pub struct CacheMiddleware {
     dir: PathBuf,
     lockers: LockerMap,
+    ttl: Option<Duration>,
 }
 
 impl CacheMiddleware {
@@ -33,40 +43,32 @@ impl CacheMiddleware {
         Self {
             dir: dir.to_owned(),
             lockers: LockerMap::new(),
+            ttl: None,
         }
     }
 
-    /// Retrieves a cached response if available.
-    fn cached(&self, key: &str) -> Option<Response<Body>> {
+    /// Sets the age after which cached responses are revalidated, they never expire by default.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Retrieves a cached entry if available.
+    fn cached(&self, key: &str) -> Option<Entry> {
         let meta = cacache::metadata_sync(&self.dir, key).ok()??;
         let attrs = meta.metadata.as_object()?;
-        let headers = attrs.get(KEY_HEADERS)?.as_object()?;
+        let headers = attrs.get(KEY_HEADERS)?.as_object()?.clone();
 
         let data = cacache::read_sync(&self.dir, key).ok()?;
 
-        let mut response = Response::builder();
-
-        for (key, value) in headers {
-            if let Some(value) = value.as_str() {
-                response = response.header(key, value);
-            }
-        }
+        let saved = UNIX_EPOCH + Duration::from_millis(meta.time.try_into().ok()?);
+        let age = SystemTime::now().duration_since(saved).unwrap_or_default();
 
-        response.body(Body::builder().data(data)).ok()
+        Some(Entry { headers, data, age })
     }
 
     /// Saves a response to the cache.
-    fn save(&self, key: &str, response: &Response<&[u8]>) -> Option<()> {
-        let mut headers = Map::new();
-        for (key, value) in response.headers() {
-            headers.insert(
-                key.as_str().to_string(),
-                Value::String(value.to_str().ok()?.to_string()),
-            );
-        }
-
-        let data = response.body();
-
+    fn save(&self, key: &str, headers: Map<String, Value>, data: &[u8]) -> Option<()> {
         let mut attrs = Map::new();
         attrs.insert(KEY_HEADERS.to_string(), Value::Object(headers));
 
@@ -80,28 +82,69 @@ impl CacheMiddleware {
         cache.commit().ok()?;
         Some(())
     }
-}
 
-impl Middleware for CacheMiddleware {
-    /// Handles the middleware logic for caching HTTP responses.
-    fn handle(
-        &self,
-        request: Request<SendBody>,
-        next: MiddlewareNext,
-    ) -> Result<Response<Body>, ureq::Error> {
+    /// Checks whether the cached entry can be used without revalidation.
+    fn is_fresh(&self, entry: &Entry) -> bool {
+        self.ttl.is_none_or(|ttl| entry.age < ttl)
+    }
+
+    /// Handles a request, sending it with `send` unless it can be served from the cache.
+    fn process<F>(&self, mut request: Request<SendBody>, send: F) -> Result<Response<Body>>
+    where
+        F: FnOnce(Request<SendBody>) -> Result<Response<Body>>,
+    {
         let key = request.uri().to_string();
 
         let locker = self.lockers.locker(key.clone());
         let _guard = locker.lock();
 
-        if let Some(response) = self.cached(&key) {
-            log::debug!("item found in cache: {key}");
-            return Ok(response);
+        let entry = match self.cached(&key) {
+            Some(entry) if self.is_fresh(&entry) => {
+                if let Some(response) = entry.into_response() {
+                    log::debug!("item found in cache: {key}");
+                    return Ok(response);
+                }
+                None
+            }
+            entry => entry,
+        };
+
+        if let Some(entry) = &entry {
+            log::debug!("item found in cache, revalidating: {key}");
+            entry.add_validators(request.headers_mut());
+        } else {
+            log::debug!("item not found in cache: {key}");
         }
 
-        log::debug!("item not found in cache: {key}");
+        let mut response = match send(request) {
+            Ok(response) => response,
+            Err(err) => {
+                return match entry.and_then(Entry::into_response) {
+                    Some(response) => {
+                        log::warn!("failed to revalidate {key}, using cached item: {err}");
+                        Ok(response)
+                    }
+                    None => Err(err),
+                };
+            }
+        };
+
+        if response.status() == StatusCode::NOT_MODIFIED
+            && let Some(mut entry) = entry
+        {
+            log::debug!("item not modified: {key}");
+            if let Some(headers) = headers(response.headers()) {
+                entry.headers.extend(headers);
+            }
+            if self
+                .save(&key, entry.headers.clone(), &entry.data)
+                .is_none()
+            {
+                log::warn!("failed to refresh item in cache: {key}");
+            }
+            return Ok(entry.into_response().unwrap_or(response));
+        }
 
-        let mut response = next.handle(request)?;
         if response.status() != 200 {
             return Ok(response);
         }
@@ -125,9 +168,8 @@ impl Middleware for CacheMiddleware {
             bb = bb.mime_type(mime_type);
         }
 
-        let response = Response::from_parts(response.into_parts().0, data.as_slice());
-
-        if self.save(&key, &response).is_some() {
+        let saved = headers(response.headers()).and_then(|h| self.save(&key, h, &data));
+        if saved.is_some() {
             log::debug!("item saved to cache: {key}");
         } else {
             log::warn!("failed to save item to cache: {key}");
@@ -139,6 +181,71 @@ impl Middleware for CacheMiddleware {
     }
 }
 
+impl Middleware for CacheMiddleware {
+    /// Handles the middleware logic for caching HTTP responses.
+    fn handle(
+        &self,
+        request: Request<SendBody>,
+        next: MiddlewareNext,
+    ) -> Result<Response<Body>, ureq::Error> {
+        self.process(request, |request| next.handle(request))
+    }
+}
+
+/// A cached response.
+struct Entry {
+    headers: Map<String, Value>,
+    data: Vec<u8>,
+    age: Duration,
+}
+
+impl Entry {
+    /// Returns the value of a stored header.
+    fn header(&self, name: &header::HeaderName) -> Option<&str> {
+        self.headers.get(name.as_str())?.as_str()
+    }
+
+    /// Adds the conditional request headers matching the stored validators.
+    fn add_validators(&self, headers: &mut HeaderMap) {
+        let validators = [
+            (header::ETAG, header::IF_NONE_MATCH),
+            (header::LAST_MODIFIED, header::IF_MODIFIED_SINCE),
+        ];
+        for (validator, condition) in validators {
+            if let Some(value) = self.header(&validator)
+                && let Ok(value) = HeaderValue::from_str(value)
+            {
+                headers.insert(condition, value);
+            }
+        }
+    }
+
+    /// Converts the entry into a response.
+    fn into_response(self) -> Option<Response<Body>> {
+        let mut response = Response::builder();
+
+        for (key, value) in &self.headers {
+            if let Some(value) = value.as_str() {
+                response = response.header(key, value);
+            }
+        }
+
+        response.body(Body::builder().data(self.data)).ok()
+    }
+}
+
+/// Converts the response headers to be stored in the cache metadata.
+fn headers(headers: &HeaderMap) -> Option<Map<String, Value>> {
+    let mut result = Map::new();
+    for (key, value) in headers {
+        result.insert(
+            key.as_str().to_string(),
+            Value::String(value.to_str().ok()?.to_string()),
+        );
+    }
+    Some(result)
+}
+
 /// A map for managing locks on cache keys.
 struct LockerMap(Mutex<HashMap<String, Arc<Mutex<()>>>>);
 
@@ -191,3 +298,6 @@ impl Deref for Locker<'_> {
         &self.lock
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,108 @@
+use super::*;
+
+const URL: &str = "https://example.com/fonts/font.ttf";
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "termframe-cache-test-{}-{name}",
+        std::process::id()
+    ));
+    std::fs::remove_dir_all(&dir).ok();
+    dir
+}
+
+fn request() -> Request<SendBody<'static>> {
+    Request::builder().uri(URL).body(SendBody::none()).unwrap()
+}
+
+fn response(status: u16, headers: &[(&str, &str)], data: &str) -> Result<Response<Body>> {
+    let mut response = Response::builder().status(status);
+    for (key, value) in headers {
+        response = response.header(*key, *value);
+    }
+    Ok(response.body(Body::builder().data(data)).unwrap())
+}
+
+fn body(response: Result<Response<Body>>) -> String {
+    response.unwrap().body_mut().read_to_string().unwrap()
+}
+
+#[test]
+fn test_cache_hit_without_ttl() {
+    let dir = temp_dir("hit");
+    let mw = CacheMiddleware::new(&dir);
+
+    let first = mw.process(request(), |_| response(200, &[], "data"));
+    assert_eq!(body(first), "data");
+
+    let second = mw.process(request(), |_| panic!("unexpected request"));
+    assert_eq!(body(second), "data");
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_revalidate_not_modified() {
+    let dir = temp_dir("not-modified");
+    let mw = CacheMiddleware::new(&dir).with_ttl(Duration::ZERO);
+
+    let headers = [
+        ("etag", "\"v1\""),
+        ("last-modified", "Wed, 21 Oct 2015 07:28:00 GMT"),
+    ];
+    let first = mw.process(request(), |request| {
+        assert!(request.headers().get(header::IF_NONE_MATCH).is_none());
+        response(200, &headers, "data")
+    });
+    assert_eq!(body(first), "data");
+
+    let second = mw.process(request(), |request| {
+        let headers = request.headers();
+        assert_eq!(headers[header::IF_NONE_MATCH], "\"v1\"");
+        assert_eq!(
+            headers[header::IF_MODIFIED_SINCE],
+            "Wed, 21 Oct 2015 07:28:00 GMT"
+        );
+        response(304, &[("etag", "\"v1\"")], "")
+    });
+    let second = second.unwrap();
+    assert_eq!(second.status(), 200);
+    assert_eq!(second.headers()[header::ETAG], "\"v1\"");
+    assert_eq!(body(Ok(second)), "data");
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_revalidate_modified() {
+    let dir = temp_dir("modified");
+    let mw = CacheMiddleware::new(&dir).with_ttl(Duration::ZERO);
+
+    let first = mw.process(request(), |_| response(200, &[("etag", "\"v1\"")], "old"));
+    assert_eq!(body(first), "old");
+
+    let second = mw.process(request(), |_| response(200, &[("etag", "\"v2\"")], "new"));
+    assert_eq!(body(second), "new");
+
+    let third = mw.process(request(), |request| {
+        assert_eq!(request.headers()[header::IF_NONE_MATCH], "\"v2\"");
+        response(304, &[], "")
+    });
+    assert_eq!(body(third), "new");
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_revalidate_failure_uses_cached_item() {
+    let dir = temp_dir("failure");
+    let mw = CacheMiddleware::new(&dir).with_ttl(Duration::ZERO);
+
+    let first = mw.process(request(), |_| response(200, &[("etag", "\"v1\"")], "data"));
+    assert_eq!(body(first), "data");
+
+    let second = mw.process(request(), |_| Err(Error::ConnectionFailed));
+    assert_eq!(body(second), "data");
+
+    std::fs::remove_dir_all(&dir).ok();
+}